@@ -0,0 +1,458 @@
+use chrono::{Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{ActiveTimer, AppData, DailyTask, QuickNote, Review, Todo};
+use crate::storage::format_time;
+use crate::tz;
+
+/// Operations that can be applied to `AppData` from outside the webview,
+/// shared by the local API and the companion CLI.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum Request {
+    ListTasks {
+        #[serde(default)]
+        date: Option<String>,
+    },
+    AddTask {
+        text: String,
+        #[serde(default)]
+        description: String,
+        #[serde(default)]
+        category: Option<String>,
+        #[serde(default)]
+        date: Option<String>,
+    },
+    AddTodo {
+        text: String,
+        #[serde(default)]
+        description: String,
+    },
+    AddNote {
+        text: String,
+    },
+    AddReview {
+        url: String,
+        #[serde(default)]
+        re_review: bool,
+    },
+    CompleteTask {
+        id: String,
+    },
+    StartTimer {
+        minutes: i32,
+        #[serde(default)]
+        task_id: Option<String>,
+        #[serde(default)]
+        name: Option<String>,
+    },
+    Export,
+}
+
+#[derive(Debug)]
+pub struct Outcome {
+    pub result: serde_json::Value,
+    pub modified: bool,
+}
+
+impl Outcome {
+    fn read(result: serde_json::Value) -> Self {
+        Self { result, modified: false }
+    }
+
+    fn write(result: serde_json::Value) -> Self {
+        Self { result, modified: true }
+    }
+}
+
+/// Today in the zone set in `data`, or the system's.
+pub fn today(data: &AppData) -> String {
+    tz::now_in(data).format("%Y-%m-%d").to_string()
+}
+
+/// Timestamps in the same shape the frontend writes (`Date.toISOString()`).
+pub fn timestamp() -> String {
+    format_time(Utc::now())
+}
+
+pub fn execute(data: &mut AppData, request: &Request) -> Result<Outcome, String> {
+    match request {
+        Request::ListTasks { date } => {
+            let date = match date {
+                Some(date) => validate_date(date)?,
+                None => today(data),
+            };
+            let tasks = tasks_for_date(data, &date);
+            Ok(Outcome::read(to_value(&tasks)?))
+        }
+        Request::AddTask { text, description, category, date } => {
+            let text = require_text(text, "Task text")?;
+            let category = match category.as_deref() {
+                None => "work".to_string(),
+                Some(c @ ("work" | "personal")) => c.to_string(),
+                Some(other) => return Err(format!("Unknown category: {}", other)),
+            };
+            let date = match date {
+                Some(date) => validate_date(date)?,
+                None => today(data),
+            };
+
+            for task in data.daily_tasks.iter_mut().filter(|t| t.date == date) {
+                if let Some(order) = task.order.as_mut() {
+                    *order += 1;
+                }
+            }
+
+            let task = DailyTask {
+                id: Uuid::new_v4().to_string(),
+                text,
+                description: description.trim().to_string(),
+                category,
+                date,
+                order: Some(0),
+                ..Default::default()
+            };
+            data.daily_tasks.insert(0, task.clone());
+            Ok(Outcome::write(to_value(&task)?))
+        }
+        Request::AddTodo { text, description } => {
+            let todo = Todo {
+                id: Uuid::new_v4().to_string(),
+                text: require_text(text, "Todo text")?,
+                description: description.trim().to_string(),
+                created_at: timestamp(),
+                ..Default::default()
+            };
+            data.todos.insert(0, todo.clone());
+            Ok(Outcome::write(to_value(&todo)?))
+        }
+        Request::AddNote { text } => {
+            let note = QuickNote {
+                id: Uuid::new_v4().to_string(),
+                text: require_text(text, "Note text")?,
+                timestamp: timestamp(),
+            };
+            data.quick_notes.insert(0, note.clone());
+            Ok(Outcome::write(to_value(&note)?))
+        }
+        Request::AddReview { url, re_review } => {
+            let url = url.trim();
+            let pr = parse_pr_link(url).ok_or_else(|| format!("Not a GitHub or Graphite PR link: {}", url))?;
+            let duplicate = data.reviews.iter().any(|r| {
+                r.title.trim_start_matches('[').trim_end_matches(']').to_lowercase() == pr.key
+            });
+            if duplicate && !re_review {
+                return Err(format!("{} has already been reviewed", pr.title));
+            }
+
+            let now = timestamp();
+            let review = Review {
+                id: Uuid::new_v4().to_string(),
+                pr_link: url.to_string(),
+                title: pr.title,
+                source: pr.source.to_string(),
+                completed: true,
+                completed_at: Some(now.clone()),
+                created_at: now,
+                date: today(data),
+            };
+            data.reviews.insert(0, review.clone());
+            Ok(Outcome::write(to_value(&review)?))
+        }
+        Request::CompleteTask { id } => {
+            let index = find_task(data, id)?;
+            let task = &mut data.daily_tasks[index];
+            if !task.completed {
+                task.completed = true;
+                task.completed_at = Some(timestamp());
+            }
+            Ok(Outcome::write(to_value(&*task)?))
+        }
+        Request::StartTimer { minutes, task_id, name } => {
+            let timer = new_timer(data, *minutes, task_id.as_deref(), name.as_deref())?;
+            data.active_timers.push(timer.clone());
+            Ok(Outcome::write(to_value(&timer)?))
+        }
+        Request::Export => Ok(Outcome::read(to_value(&*data)?)),
+    }
+}
+
+/// Builds a running timer. Task timers take their label from the task; focus
+/// timers use the optional name.
+pub fn new_timer(data: &AppData, minutes: i32, task_id: Option<&str>, name: Option<&str>) -> Result<ActiveTimer, String> {
+    if !(1..=24 * 60).contains(&minutes) {
+        return Err(format!("Timer length must be between 1 and 1440 minutes, got {}", minutes));
+    }
+
+    let (timer_type, task_id, task_name) = match task_id {
+        Some(id) => {
+            let task = &data.daily_tasks[find_task(data, id)?];
+            ("task", Some(task.id.clone()), Some(task.text.clone()))
+        }
+        None => ("focus", None, name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty())),
+    };
+
+    let now = Utc::now();
+    let end_time = now + Duration::minutes(minutes as i64);
+    Ok(ActiveTimer {
+        id: Uuid::new_v4().to_string(),
+        timer_type: timer_type.to_string(),
+        task_id,
+        task_name,
+        end_time: format_time(end_time),
+        duration_minutes: minutes,
+        started_at: Some(format_time(now)),
+        ..Default::default()
+    })
+}
+
+/// Tasks scheduled on `date`, frog first and then by manual order, matching
+/// the Today view.
+pub fn tasks_for_date(data: &AppData, date: &str) -> Vec<DailyTask> {
+    let mut tasks: Vec<DailyTask> = data
+        .daily_tasks
+        .iter()
+        .filter(|t| t.date == date)
+        .cloned()
+        .collect();
+    tasks.sort_by_key(|t| (!t.is_frog.unwrap_or(false), t.order.unwrap_or(i32::MAX)));
+    tasks
+}
+
+/// Resolves a task by full id or by a unique id prefix (as printed by the CLI).
+pub fn find_task(data: &AppData, id: &str) -> Result<usize, String> {
+    let id = id.trim();
+    if id.is_empty() {
+        return Err("Task id is required".to_string());
+    }
+    if let Some(index) = data.daily_tasks.iter().position(|t| t.id == id) {
+        return Ok(index);
+    }
+
+    let matches: Vec<usize> = data
+        .daily_tasks
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| t.id.starts_with(id))
+        .map(|(i, _)| i)
+        .collect();
+    match matches.as_slice() {
+        [index] => Ok(*index),
+        [] => Err(format!("No task matches id {}", id)),
+        _ => Err(format!("Task id {} is ambiguous", id)),
+    }
+}
+
+pub struct PrLink {
+    pub title: String,
+    pub source: &'static str,
+    pub key: String,
+}
+
+/// Mirrors `parsePrLink` in the frontend: GitHub and Graphite PR URLs become
+/// an `[org/repo#123]` title.
+pub fn parse_pr_link(url: &str) -> Option<PrLink> {
+    let clean = url.split(['?', '#']).next().unwrap_or_default();
+
+    let (source, rest) = if let Some(i) = clean.find("github.com/") {
+        ("github", &clean[i + "github.com/".len()..])
+    } else if let Some(i) = clean.find("app.graphite.com/github/pr/") {
+        ("graphite", &clean[i + "app.graphite.com/github/pr/".len()..])
+    } else {
+        return None;
+    };
+
+    let parts: Vec<&str> = rest.split('/').collect();
+    let (org, repo, number) = match (source, parts.as_slice()) {
+        ("github", [org, repo, "pull", number, ..]) => (*org, *repo, *number),
+        ("graphite", [org, repo, number, ..]) => (*org, *repo, *number),
+        _ => return None,
+    };
+    if org.is_empty() || repo.is_empty() || number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    Some(PrLink {
+        title: format!("[{}/{}#{}]", org, repo, number),
+        source,
+        key: format!("{}/{}#{}", org, repo, number).to_lowercase(),
+    })
+}
+
+fn validate_date(date: &str) -> Result<String, String> {
+    NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .map_err(|_| format!("Invalid date (expected YYYY-MM-DD): {}", date))
+}
+
+fn require_text(text: &str, what: &str) -> Result<String, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(format!("{} must not be empty", what));
+    }
+    Ok(text.to_string())
+}
+
+fn to_value<T: Serialize>(value: &T) -> Result<serde_json::Value, String> {
+    serde_json::to_value(value).map_err(|e| format!("Failed to serialize result: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str, date: &str, order: i32) -> DailyTask {
+        DailyTask {
+            id: id.to_string(),
+            text: format!("Task {}", id),
+            date: date.to_string(),
+            category: "work".to_string(),
+            order: Some(order),
+            ..Default::default()
+        }
+    }
+
+    fn add_task(text: &str, category: Option<&str>, date: Option<&str>) -> Request {
+        Request::AddTask {
+            text: text.to_string(),
+            description: String::new(),
+            category: category.map(str::to_string),
+            date: date.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn add_task_goes_to_the_top_of_its_day() {
+        let mut data = AppData {
+            daily_tasks: vec![task("a", "2026-03-02", 0), task("b", "2026-03-02", 1), task("c", "2026-03-03", 0)],
+            ..Default::default()
+        };
+
+        let outcome = execute(&mut data, &add_task("  Write tests ", Some("personal"), Some("2026-03-02"))).unwrap();
+
+        assert!(outcome.modified);
+        assert_eq!(outcome.result["text"], "Write tests");
+        assert_eq!(outcome.result["category"], "personal");
+        let orders: Vec<(&str, Option<i32>)> = data
+            .daily_tasks
+            .iter()
+            .map(|t| (t.text.as_str(), t.order))
+            .collect();
+        assert_eq!(
+            orders,
+            [("Write tests", Some(0)), ("Task a", Some(1)), ("Task b", Some(2)), ("Task c", Some(0))]
+        );
+    }
+
+    #[test]
+    fn add_task_rejects_bad_input() {
+        let mut data = AppData::default();
+        assert!(execute(&mut data, &add_task("   ", None, None)).is_err());
+        assert!(execute(&mut data, &add_task("Task", Some("chores"), None)).is_err());
+        assert!(execute(&mut data, &add_task("Task", None, Some("2026-02-30"))).is_err());
+        assert!(data.daily_tasks.is_empty());
+    }
+
+    #[test]
+    fn list_tasks_puts_the_frog_first_and_does_not_modify() {
+        let mut frog = task("f", "2026-03-02", 5);
+        frog.is_frog = Some(true);
+        let mut data = AppData {
+            daily_tasks: vec![task("b", "2026-03-02", 1), frog, task("a", "2026-03-02", 0), task("x", "2026-03-01", 0)],
+            ..Default::default()
+        };
+
+        let outcome = execute(&mut data, &Request::ListTasks { date: Some("2026-03-02".to_string()) }).unwrap();
+
+        assert!(!outcome.modified);
+        let ids: Vec<&str> = outcome.result.as_array().unwrap().iter().map(|t| t["id"].as_str().unwrap()).collect();
+        assert_eq!(ids, ["f", "a", "b"]);
+    }
+
+    #[test]
+    fn complete_task_accepts_a_unique_prefix() {
+        let mut data = AppData {
+            daily_tasks: vec![task("abc123", "2026-03-02", 0), task("abd456", "2026-03-02", 1)],
+            ..Default::default()
+        };
+
+        let err = execute(&mut data, &Request::CompleteTask { id: "ab".to_string() }).unwrap_err();
+        assert!(err.contains("ambiguous"), "{}", err);
+        assert!(execute(&mut data, &Request::CompleteTask { id: "zz".to_string() }).is_err());
+
+        let outcome = execute(&mut data, &Request::CompleteTask { id: "abd".to_string() }).unwrap();
+        assert_eq!(outcome.result["id"], "abd456");
+        assert!(data.daily_tasks[1].completed);
+        assert!(data.daily_tasks[1].completed_at.is_some());
+        assert!(!data.daily_tasks[0].completed);
+    }
+
+    #[test]
+    fn add_review_rejects_duplicates_unless_re_reviewing() {
+        let mut data = AppData::default();
+        let review = |url: &str, re_review| Request::AddReview { url: url.to_string(), re_review };
+
+        let outcome = execute(&mut data, &review("https://github.com/Org/Repo/pull/42", false)).unwrap();
+        assert_eq!(outcome.result["title"], "[Org/Repo#42]");
+        assert_eq!(outcome.result["source"], "github");
+
+        let duplicate = "https://app.graphite.com/github/pr/org/repo/42/some-title";
+        assert!(execute(&mut data, &review(duplicate, false)).is_err());
+        assert!(execute(&mut data, &review(duplicate, true)).is_ok());
+        assert!(execute(&mut data, &review("https://example.com/pull/1", true)).is_err());
+        assert_eq!(data.reviews.len(), 2);
+    }
+
+    #[test]
+    fn start_timer_labels_task_timers_with_the_task() {
+        let mut data = AppData {
+            daily_tasks: vec![task("abc123", "2026-03-02", 0)],
+            ..Default::default()
+        };
+        let start = |minutes, task_id: Option<&str>, name: Option<&str>| Request::StartTimer {
+            minutes,
+            task_id: task_id.map(str::to_string),
+            name: name.map(str::to_string),
+        };
+
+        let outcome = execute(&mut data, &start(25, Some("abc"), Some("ignored"))).unwrap();
+        assert_eq!(outcome.result["type"], "task");
+        assert_eq!(outcome.result["taskName"], "Task abc123");
+
+        let outcome = execute(&mut data, &start(10, None, Some("  "))).unwrap();
+        assert_eq!(outcome.result["type"], "focus");
+        assert!(outcome.result["taskName"].is_null());
+
+        assert!(execute(&mut data, &start(0, None, None)).is_err());
+        assert!(execute(&mut data, &start(24 * 60 + 1, None, None)).is_err());
+        assert_eq!(data.active_timers.len(), 2);
+    }
+
+    #[test]
+    fn parse_pr_link_handles_github_and_graphite() {
+        let pr = parse_pr_link("https://github.com/Org/Repo/pull/123/files?w=1#diff").unwrap();
+        assert_eq!(pr.title, "[Org/Repo#123]");
+        assert_eq!(pr.source, "github");
+        assert_eq!(pr.key, "org/repo#123");
+
+        let pr = parse_pr_link("https://app.graphite.com/github/pr/org/repo/7/title?ref=inbox").unwrap();
+        assert_eq!(pr.title, "[org/repo#7]");
+        assert_eq!(pr.source, "graphite");
+    }
+
+    #[test]
+    fn parse_pr_link_rejects_other_links() {
+        for url in [
+            "",
+            "https://github.com/org/repo",
+            "https://github.com/org/repo/issues/12",
+            "https://github.com/org/repo/pull/12a",
+            "https://github.com//repo/pull/12",
+            "https://app.graphite.com/github/pr/org/repo/",
+            "https://gitlab.com/org/repo/pull/12",
+        ] {
+            assert!(parse_pr_link(url).is_none(), "{}", url);
+        }
+    }
+}
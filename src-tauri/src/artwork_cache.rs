@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{self, format_time, parse_time};
+
+/// Directory in the data dir holding the images and their index.
+pub const ARTWORK_DIR: &str = "artwork";
+
+const INDEX_FILE: &str = "index.json";
+
+/// Total size of cached images before the least recently used go.
+const MAX_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Entries kept at most, counting remembered misses.
+const MAX_ENTRIES: usize = 2000;
+
+/// How long an album known to have no artwork is left alone before looking
+/// it up again.
+const MISS_TTL: Duration = Duration::days(1);
+
+/// Recording every use would rewrite the index on each poll; uses closer
+/// together than this don't change an entry's place in the LRU order.
+const TOUCH_INTERVAL: Duration = Duration::hours(1);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct Entry {
+    /// Image file in the cache directory, or `None` for an album known to
+    /// have no artwork.
+    file: Option<String>,
+    #[serde(default)]
+    size: u64,
+    #[serde(rename = "fetchedAt")]
+    fetched_at: String,
+    #[serde(rename = "lastUsed")]
+    last_used: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct Index {
+    /// Keyed by artist and album, see `key`.
+    #[serde(default)]
+    entries: HashMap<String, Entry>,
+}
+
+enum Lookup {
+    Hit(String),
+    /// Recently looked up and not found.
+    Miss,
+    Unknown,
+}
+
+/// Album artwork kept on disk across restarts, keyed by artist and album.
+/// Albums without artwork are remembered for a day so they aren't looked up
+/// on every track change, and the least recently shown images are evicted
+/// once the cache outgrows its limits.
+pub struct ArtworkCache {
+    dir: PathBuf,
+    index: Mutex<Index>,
+}
+
+impl ArtworkCache {
+    pub fn load(dir: PathBuf) -> Self {
+        let mut index: Index = storage::read_json(&dir.join(INDEX_FILE));
+        // Images removed behind our back are looked up again.
+        index
+            .entries
+            .retain(|_, entry| entry.file.as_ref().is_none_or(|file| dir.join(file).exists()));
+        Self { dir, index: Mutex::new(index) }
+    }
+
+    /// The cached image's file name for the album: the copy on disk if there
+    /// is one, else `embedded` (artwork the player hands over), else
+    /// whatever `fetch` finds. Neither is called for albums already cached,
+    /// and `fetch` is skipped for albums recently found to have no artwork.
+    pub fn resolve<E, F>(&self, artist: &str, album: &str, embedded: E, fetch: F) -> Option<String>
+    where
+        E: FnOnce() -> Option<Vec<u8>>,
+        F: FnOnce() -> Option<Vec<u8>>,
+    {
+        let key = key(artist, album);
+        let image = match self.lookup(&key) {
+            Lookup::Hit(file) => return Some(file),
+            Lookup::Miss => Some(embedded()?),
+            Lookup::Unknown => embedded().or_else(fetch),
+        };
+        self.store(&key, image.as_deref())
+    }
+
+    /// Path of a cached image by file name, refusing anything that would
+    /// reach outside the cache directory.
+    pub fn path(&self, file: &str) -> Option<PathBuf> {
+        let valid = !file.is_empty() && file.chars().all(|c| c.is_ascii_alphanumeric() || c == '.') && !file.starts_with('.');
+        Some(self.dir.join(file)).filter(|path| valid && path.is_file())
+    }
+
+    fn lookup(&self, key: &str) -> Lookup {
+        let mut index = self.lock();
+        let Some(entry) = index.entries.get_mut(key) else {
+            return Lookup::Unknown;
+        };
+        let now = Utc::now();
+        match entry.file.clone() {
+            Some(file) => {
+                if parse_time(&entry.last_used).is_none_or(|used| now - used > TOUCH_INTERVAL) {
+                    entry.last_used = format_time(now);
+                    self.save(&index);
+                }
+                Lookup::Hit(file)
+            }
+            None if parse_time(&entry.fetched_at).is_some_and(|fetched| now - fetched < MISS_TTL) => Lookup::Miss,
+            None => Lookup::Unknown,
+        }
+    }
+
+    /// Writes the image (or remembers there is none) and trims the cache.
+    fn store(&self, key: &str, image: Option<&[u8]>) -> Option<String> {
+        let file = image.and_then(|bytes| {
+            // Named after the image too, so a new image for the album gets a
+            // new URL and the webview may cache each one forever.
+            let file = format!("{:016x}{:016x}.{}", fnv1a(key.as_bytes()), fnv1a(bytes), extension(bytes));
+            match storage::write_atomic(&self.dir.join(&file), bytes) {
+                Ok(()) => Some(file),
+                Err(e) => {
+                    eprintln!("Failed to cache artwork: {}", e);
+                    None
+                }
+            }
+        });
+        let now = format_time(Utc::now());
+        let mut index = self.lock();
+        let previous = index.entries.insert(
+            key.to_string(),
+            Entry {
+                file: file.clone(),
+                size: image.map_or(0, |bytes| bytes.len() as u64),
+                fetched_at: now.clone(),
+                last_used: now,
+            },
+        );
+        if let Some(old) = previous.and_then(|entry| entry.file).filter(|old| Some(old) != file.as_ref()) {
+            let _ = fs::remove_file(self.dir.join(old));
+        }
+        self.evict(&mut index);
+        self.save(&index);
+        // An image too big for the cache is evicted straight away.
+        file.filter(|_| index.entries.contains_key(key))
+    }
+
+    /// Drops expired misses, then the least recently used entries until the
+    /// cache is within its limits.
+    fn evict(&self, index: &mut Index) {
+        let now = Utc::now();
+        index.entries.retain(|_, entry| {
+            entry.file.is_some() || parse_time(&entry.fetched_at).is_some_and(|fetched| now - fetched < MISS_TTL)
+        });
+
+        let mut total: u64 = index.entries.values().map(|entry| entry.size).sum();
+        if total <= MAX_BYTES && index.entries.len() <= MAX_ENTRIES {
+            return;
+        }
+        let mut by_use: Vec<(String, String)> =
+            index.entries.iter().map(|(key, entry)| (entry.last_used.clone(), key.clone())).collect();
+        by_use.sort();
+        for (_, key) in by_use {
+            if total <= MAX_BYTES && index.entries.len() <= MAX_ENTRIES {
+                break;
+            }
+            if let Some(entry) = index.entries.remove(&key) {
+                total -= entry.size;
+                if let Some(file) = entry.file {
+                    let _ = fs::remove_file(self.dir.join(file));
+                }
+            }
+        }
+    }
+
+    fn save(&self, index: &Index) {
+        if let Err(e) = storage::write_json(&self.dir.join(INDEX_FILE), index) {
+            eprintln!("Failed to save artwork index: {}", e);
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Index> {
+        self.index.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The URL the webview loads a cached image from, served by the `artwork`
+/// protocol. Windows webviews reach custom protocols over http.
+pub fn url(file: &str) -> String {
+    if cfg!(windows) {
+        format!("http://artwork.localhost/{}", file)
+    } else {
+        format!("artwork://localhost/{}", file)
+    }
+}
+
+pub fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("png") => "image/png",
+        _ => "image/jpeg",
+    }
+}
+
+fn key(artist: &str, album: &str) -> String {
+    format!("{}\u{1f}{}", artist.trim().to_lowercase(), album.trim().to_lowercase())
+}
+
+fn extension(bytes: &[u8]) -> &'static str {
+    if bytes.starts_with(b"\x89PNG") {
+        "png"
+    } else {
+        "jpg"
+    }
+}
+
+/// A hash that stays the same across builds, for stable file names.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(name: &str) -> (ArtworkCache, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("growing-artwork-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        (ArtworkCache::load(dir.clone()), dir)
+    }
+
+    #[test]
+    fn cached_albums_skip_the_player_and_the_lookup() {
+        let (cache, dir) = cache("hit");
+        let file = cache.resolve("Artist", "Album", || Some(b"\x89PNG one".to_vec()), || None).unwrap();
+        assert!(file.ends_with(".png"));
+        assert_eq!(fs::read(cache.path(&file).unwrap()).unwrap(), b"\x89PNG one");
+
+        let again = cache.resolve(" artist", "ALBUM ", || panic!("read the player's artwork"), || panic!("looked it up"));
+        assert_eq!(again, Some(file));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn albums_without_artwork_are_not_looked_up_again() {
+        let (cache, dir) = cache("miss");
+        assert_eq!(cache.resolve("Artist", "Album", || None, || None), None);
+        assert_eq!(cache.resolve("Artist", "Album", || None, || panic!("looked it up")), None);
+        // Artwork the player hands over later is still taken.
+        assert!(cache.resolve("Artist", "Album", || Some(b"jpeg".to_vec()), || None).is_some());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn a_new_image_gets_a_new_file_name() {
+        let (cache, dir) = cache("versions");
+        let first = cache.store(&key("a", "b"), Some(b"first")).unwrap();
+        let second = cache.store(&key("a", "b"), Some(b"second")).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(cache.path(&first), None);
+        assert!(cache.path(&second).is_some());
+        assert_eq!(cache.resolve("a", "b", || None, || None), Some(second));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn paths_stay_in_the_cache() {
+        let (cache, dir) = cache("paths");
+        let file = cache.resolve("a", "b", || Some(b"jpeg".to_vec()), || None).unwrap();
+        assert!(cache.path(&file).is_some());
+        for file in ["", "../data.json", ".hidden", "index.json/..", "missing.jpg"] {
+            assert_eq!(cache.path(file), None, "{}", file);
+        }
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,315 @@
+//! Companion command line interface for Growing.
+//!
+//! Talks to the running app through its local API when available, otherwise
+//! edits `data.json` directly under a lock file.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use growing_lib::actions::{self, Request};
+use growing_lib::local_api::{self, ClientError};
+use growing_lib::storage::{self, DataFileLock};
+use growing_lib::{ActiveTimer, DailyTask, QuickNote, Review, Todo};
+
+const USAGE: &str = "\
+Usage: growing-cli [--data-dir DIR] [--json] <command>
+
+Commands:
+  today [--date YYYY-MM-DD]                    List tasks for today (or a date)
+  add task <text> [--category work|personal]   Add a task
+               [--date YYYY-MM-DD] [--description TEXT]
+  add todo <text> [--description TEXT]         Add a backlog todo
+  add note <text>                              Add a quick note
+  add review <pr-url> [--re-review]            Log a PR review
+  complete <task-id>                           Complete a task (id prefix is fine)
+  timer start <minutes> [--task ID] [--name NAME]
+                                               Start a focus or task timer
+  export [--output FILE]                       Export all data as JSON
+
+The data directory defaults to $GROWING_DATA_DIR or the app's data directory.";
+
+#[derive(Default)]
+struct Args {
+    positional: Vec<String>,
+    data_dir: Option<PathBuf>,
+    json: bool,
+    output: Option<PathBuf>,
+    category: Option<String>,
+    date: Option<String>,
+    description: Option<String>,
+    task: Option<String>,
+    name: Option<String>,
+    re_review: bool,
+    help: bool,
+}
+
+fn main() -> ExitCode {
+    match run(std::env::args().skip(1).collect()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("growing-cli: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(raw: Vec<String>) -> Result<(), String> {
+    let args = parse_args(raw)?;
+    if args.help || args.positional.is_empty() {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let request = build_request(&args)?;
+    let data_dir = args
+        .data_dir
+        .clone()
+        .or_else(|| std::env::var_os("GROWING_DATA_DIR").map(PathBuf::from))
+        .or_else(storage::default_data_dir)
+        .ok_or("Could not determine the data directory; pass --data-dir")?;
+
+    let result = dispatch(&data_dir, &request)?;
+
+    if let Request::Export = request {
+        let content = serde_json::to_string_pretty(&result)
+            .map_err(|e| format!("Failed to serialize export: {}", e))?;
+        return match &args.output {
+            Some(path) => fs::write(path, content)
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e)),
+            None => {
+                println!("{}", content);
+                Ok(())
+            }
+        };
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&result).unwrap_or_default());
+        return Ok(());
+    }
+    print_result(&request, result)
+}
+
+fn parse_args(raw: Vec<String>) -> Result<Args, String> {
+    let mut args = Args::default();
+    let mut iter = raw.into_iter();
+
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| iter.next().ok_or_else(|| format!("{} requires a value", name));
+        match arg.as_str() {
+            "-h" | "--help" => args.help = true,
+            "--json" => args.json = true,
+            "--re-review" => args.re_review = true,
+            "--data-dir" => args.data_dir = Some(PathBuf::from(value("--data-dir")?)),
+            "--output" | "-o" => args.output = Some(PathBuf::from(value("--output")?)),
+            "--category" => args.category = Some(value("--category")?),
+            "--date" => args.date = Some(value("--date")?),
+            "--description" => args.description = Some(value("--description")?),
+            "--task" => args.task = Some(value("--task")?),
+            "--name" => args.name = Some(value("--name")?),
+            flag if flag.starts_with("--") => return Err(format!("Unknown option: {}", flag)),
+            _ => args.positional.push(arg),
+        }
+    }
+
+    Ok(args)
+}
+
+fn build_request(args: &Args) -> Result<Request, String> {
+    let words: Vec<&str> = args.positional.iter().map(String::as_str).collect();
+    let text = |rest: &[&str]| -> Result<String, String> {
+        if rest.is_empty() {
+            Err("Missing text".to_string())
+        } else {
+            Ok(rest.join(" "))
+        }
+    };
+
+    let request = match words.as_slice() {
+        ["today"] => Request::ListTasks { date: args.date.clone() },
+        ["add", "task", rest @ ..] => Request::AddTask {
+            text: text(rest)?,
+            description: args.description.clone().unwrap_or_default(),
+            category: args.category.clone(),
+            date: args.date.clone(),
+        },
+        ["add", "todo", rest @ ..] => Request::AddTodo {
+            text: text(rest)?,
+            description: args.description.clone().unwrap_or_default(),
+        },
+        ["add", "note", rest @ ..] => Request::AddNote { text: text(rest)? },
+        ["add", "review", url] => Request::AddReview {
+            url: url.to_string(),
+            re_review: args.re_review,
+        },
+        ["complete", id] => Request::CompleteTask { id: id.to_string() },
+        ["timer", "start", minutes] => Request::StartTimer {
+            minutes: minutes
+                .parse()
+                .map_err(|_| format!("Invalid number of minutes: {}", minutes))?,
+            task_id: args.task.clone(),
+            name: args.name.clone(),
+        },
+        ["export"] => Request::Export,
+        _ => return Err(format!("Unknown command: {}\n\n{}", words.join(" "), USAGE)),
+    };
+
+    Ok(request)
+}
+
+/// Prefers the running app so its in-memory state stays authoritative; only
+/// touches the file when no app answers on the published endpoint.
+fn dispatch(data_dir: &Path, request: &Request) -> Result<serde_json::Value, String> {
+    if let Some(endpoint) = local_api::read_endpoint(data_dir) {
+        match local_api::send(&endpoint, request) {
+            Ok(result) => return Ok(result),
+            Err(ClientError::Failed(e)) => return Err(e),
+            Err(ClientError::Unavailable(_)) => {}
+        }
+    }
+
+    let data_path = data_dir.join(storage::DATA_FILE);
+    if !data_path.exists() {
+        return Err(format!(
+            "No data found at {}. Launch Growing once to set it up.",
+            data_path.display()
+        ));
+    }
+
+    let _lock = DataFileLock::acquire(data_dir)?;
+    let mut data = storage::read_app_data(&data_path)?;
+    let outcome = actions::execute(&mut data, request)?;
+    if outcome.modified {
+        storage::write_app_data(&data_path, &data)?;
+    }
+    Ok(outcome.result)
+}
+
+fn print_result(request: &Request, result: serde_json::Value) -> Result<(), String> {
+    fn decode<T: serde::de::DeserializeOwned>(value: serde_json::Value) -> Result<T, String> {
+        serde_json::from_value(value).map_err(|e| format!("Unexpected response: {}", e))
+    }
+
+    match request {
+        Request::ListTasks { .. } => {
+            let tasks: Vec<DailyTask> = decode(result)?;
+            if tasks.is_empty() {
+                println!("No tasks.");
+            }
+            for task in tasks {
+                println!("{}", format_task(&task));
+            }
+        }
+        Request::AddTask { .. } => {
+            let task: DailyTask = decode(result)?;
+            println!("Added task {} for {}: {}", short_id(&task.id), task.date, task.text);
+        }
+        Request::AddTodo { .. } => {
+            let todo: Todo = decode(result)?;
+            println!("Added todo {}: {}", short_id(&todo.id), todo.text);
+        }
+        Request::AddNote { .. } => {
+            let note: QuickNote = decode(result)?;
+            println!("Added note {}", short_id(&note.id));
+        }
+        Request::AddReview { .. } => {
+            let review: Review = decode(result)?;
+            println!("Logged review {}", review.title);
+        }
+        Request::CompleteTask { .. } => {
+            let task: DailyTask = decode(result)?;
+            println!("Completed {}: {}", short_id(&task.id), task.text);
+        }
+        Request::StartTimer { .. } => {
+            let timer: ActiveTimer = decode(result)?;
+            let label = timer.task_name.as_deref().unwrap_or("Focus");
+            println!("Started {} minute timer: {}", timer.duration_minutes, label);
+        }
+        Request::Export => {}
+    }
+    Ok(())
+}
+
+fn format_task(task: &DailyTask) -> String {
+    let check = if task.completed { "[x]" } else { "[ ]" };
+    let frog = if task.is_frog.unwrap_or(false) { " (frog)" } else { "" };
+    let timebox = task
+        .timebox_minutes
+        .map(|m| format!(" [{}m]", m))
+        .unwrap_or_default();
+    let steps = match task.checklist.len() {
+        0 => String::new(),
+        total => format!(" ({}/{})", task.checklist.iter().filter(|item| item.completed).count(), total),
+    };
+    format!("{} {}  {}{}{}{}  #{}", check, short_id(&task.id), task.text, steps, frog, timebox, task.category)
+}
+
+fn short_id(id: &str) -> &str {
+    id.get(..8).unwrap_or(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(raw: &[&str]) -> Result<Args, String> {
+        parse_args(raw.iter().map(|s| s.to_string()).collect())
+    }
+
+    fn request(raw: &[&str]) -> Result<Request, String> {
+        build_request(&args(raw)?)
+    }
+
+    #[test]
+    fn options_can_go_anywhere() {
+        let parsed = args(&["--json", "add", "task", "--date", "2026-03-02", "Write", "--data-dir", "/tmp/g", "tests"]).unwrap();
+        assert!(parsed.json);
+        assert_eq!(parsed.positional, ["add", "task", "Write", "tests"]);
+        assert_eq!(parsed.date.as_deref(), Some("2026-03-02"));
+        assert_eq!(parsed.data_dir, Some(PathBuf::from("/tmp/g")));
+    }
+
+    #[test]
+    fn bad_options_are_rejected() {
+        assert_eq!(args(&["today", "--date"]).err().as_deref(), Some("--date requires a value"));
+        assert_eq!(args(&["today", "--verbose"]).err().as_deref(), Some("Unknown option: --verbose"));
+        assert!(args(&["-h"]).unwrap().help);
+    }
+
+    #[test]
+    fn add_task_joins_the_words() {
+        match request(&["add", "task", "Write", "the", "tests", "--category", "personal"]).unwrap() {
+            Request::AddTask { text, category, date, description } => {
+                assert_eq!(text, "Write the tests");
+                assert_eq!(category.as_deref(), Some("personal"));
+                assert_eq!(date, None);
+                assert_eq!(description, "");
+            }
+            other => panic!("unexpected request {:?}", other),
+        }
+    }
+
+    #[test]
+    fn commands_map_to_requests() {
+        assert!(matches!(request(&["today"]), Ok(Request::ListTasks { date: None })));
+        assert!(matches!(request(&["export", "-o", "out.json"]), Ok(Request::Export)));
+        assert!(matches!(
+            request(&["add", "review", "https://github.com/o/r/pull/1", "--re-review"]),
+            Ok(Request::AddReview { re_review: true, .. })
+        ));
+        assert!(matches!(
+            request(&["timer", "start", "25", "--task", "abc"]),
+            Ok(Request::StartTimer { minutes: 25, task_id: Some(_), name: None })
+        ));
+        assert!(matches!(request(&["complete", "abc"]), Ok(Request::CompleteTask { .. })));
+    }
+
+    #[test]
+    fn bad_commands_are_rejected() {
+        assert_eq!(request(&["add", "note"]).unwrap_err(), "Missing text");
+        assert!(request(&["timer", "start", "soon"]).unwrap_err().contains("Invalid number of minutes"));
+        assert!(request(&["complete"]).unwrap_err().starts_with("Unknown command: complete"));
+        assert!(request(&["add", "review", "a", "b"]).is_err());
+    }
+}
@@ -0,0 +1,212 @@
+use uuid::Uuid;
+
+use crate::models::{AppData, ChecklistItem};
+
+/// The items a task carried forward takes along; the done ones stay with
+/// the task left behind.
+pub fn unfinished(items: &[ChecklistItem]) -> Vec<ChecklistItem> {
+    items.iter().filter(|item| !item.completed).cloned().collect()
+}
+
+/// Data at this schema version has had its task list lines moved into
+/// checklists.
+pub const CHECKLIST_SCHEMA_VERSION: u32 = 1;
+
+/// Runs `migrate_descriptions` on data from before checklists and marks it
+/// as migrated, so later task list lines typed into a description are left
+/// as they are. Returns how many tasks and todos changed, or `None` if the
+/// data was already migrated.
+pub fn migrate_once(data: &mut AppData) -> Option<usize> {
+    if data.schema_version >= CHECKLIST_SCHEMA_VERSION {
+        return None;
+    }
+    data.schema_version = CHECKLIST_SCHEMA_VERSION;
+    Some(migrate_descriptions(data))
+}
+
+/// Turns Markdown task list lines (`- [ ] step`, `- [x] step`) in task and
+/// todo descriptions into checklist items, appended after any the task
+/// already has, and takes them out of the description. Returns how many
+/// tasks and todos changed.
+pub fn migrate_descriptions(data: &mut AppData) -> usize {
+    let tasks = data
+        .daily_tasks
+        .iter_mut()
+        .map(|task| (&mut task.description, &mut task.checklist));
+    let todos = data.todos.iter_mut().map(|todo| (&mut todo.description, &mut todo.checklist));
+
+    let mut changed = 0;
+    for (description, checklist) in tasks.chain(todos) {
+        let (rest, items) = extract_items(description);
+        if !items.is_empty() {
+            *description = rest;
+            checklist.extend(items);
+            changed += 1;
+        }
+    }
+    changed
+}
+
+/// Splits `description` into the text without task list lines and the items
+/// those lines make.
+fn extract_items(description: &str) -> (String, Vec<ChecklistItem>) {
+    let mut rest = Vec::new();
+    let mut items = Vec::new();
+    for line in description.lines() {
+        match parse_item(line) {
+            Some(item) => items.push(item),
+            None => rest.push(line),
+        }
+    }
+    (rest.join("\n").trim().to_string(), items)
+}
+
+fn parse_item(line: &str) -> Option<ChecklistItem> {
+    let line = line.trim_start();
+    let rest = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))?
+        .trim_start();
+    let (completed, text) = if let Some(text) = rest.strip_prefix("[ ]") {
+        (false, text)
+    } else if let Some(text) = rest.strip_prefix("[x]").or_else(|| rest.strip_prefix("[X]")) {
+        (true, text)
+    } else {
+        return None;
+    };
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    Some(ChecklistItem {
+        id: Uuid::new_v4().to_string(),
+        text: text.to_string(),
+        completed,
+        completed_at: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DailyTask, Todo};
+    use crate::task_reminders::{self, ItemKind};
+
+    fn items(checklist: &[ChecklistItem]) -> Vec<(&str, bool)> {
+        checklist.iter().map(|item| (item.text.as_str(), item.completed)).collect()
+    }
+
+    fn item(text: &str, completed: bool) -> ChecklistItem {
+        ChecklistItem { id: text.to_string(), text: text.to_string(), completed, completed_at: None }
+    }
+
+    #[test]
+    fn task_list_lines_become_items() {
+        let mut data = AppData::default();
+        data.daily_tasks.push(DailyTask {
+            description: "Before\n- [ ] one\n- [x]  two  \n* [X] three\n+ [ ] four\n  - [ ] nested\nAfter\n".to_string(),
+            checklist: vec![item("existing", true)],
+            ..Default::default()
+        });
+        assert_eq!(migrate_descriptions(&mut data), 1);
+        let task = &data.daily_tasks[0];
+        assert_eq!(task.description, "Before\nAfter");
+        assert_eq!(
+            items(&task.checklist),
+            [("existing", true), ("one", false), ("two", true), ("three", true), ("four", false), ("nested", false)]
+        );
+        assert!(task.checklist.iter().all(|item| !item.id.is_empty() && item.completed_at.is_none()));
+        assert_eq!(migrate_descriptions(&mut data), 0);
+    }
+
+    #[test]
+    fn other_lines_stay_in_the_description() {
+        let description = "- plain bullet\n-[ ] no space\n- [ ]\n- [x]   \n- [y] odd box\n1. [ ] numbered\n[ ] bare";
+        let mut data = AppData::default();
+        data.todos.push(Todo { description: description.to_string(), ..Default::default() });
+        assert_eq!(migrate_descriptions(&mut data), 0);
+        assert_eq!(data.todos[0].description, description);
+        assert!(data.todos[0].checklist.is_empty());
+    }
+
+    #[test]
+    fn counts_the_tasks_and_todos_changed() {
+        let mut data = AppData::default();
+        let task = |description: &str| DailyTask { description: description.to_string(), ..Default::default() };
+        data.daily_tasks = vec![task("- [ ] a\n- [ ] b"), task("Nothing to see"), task("")];
+        data.todos.push(Todo { description: "  \n* [x] done\n\n".to_string(), ..Default::default() });
+        assert_eq!(migrate_descriptions(&mut data), 2);
+        assert_eq!(data.todos[0].description, "");
+        assert_eq!(items(&data.todos[0].checklist), [("done", true)]);
+        assert_eq!(data.daily_tasks[1].description, "Nothing to see");
+    }
+
+    #[test]
+    fn descriptions_are_migrated_once() {
+        let task = |description: &str| DailyTask { description: description.to_string(), ..Default::default() };
+        let mut data = AppData { daily_tasks: vec![task("- [ ] a"), task("plain")], ..Default::default() };
+        assert_eq!(migrate_once(&mut data), Some(1));
+        assert_eq!(data.schema_version, CHECKLIST_SCHEMA_VERSION);
+        // Nothing to move still marks the data as migrated.
+        let mut empty = AppData::default();
+        assert_eq!(migrate_once(&mut empty), Some(0));
+        assert_eq!(empty.schema_version, CHECKLIST_SCHEMA_VERSION);
+
+        // Lines typed after the migration stay in the description, also
+        // once the data has been saved and read back.
+        data.daily_tasks[1].description = "- [ ] typed later".to_string();
+        let mut saved: AppData = serde_json::from_value(serde_json::to_value(&data).unwrap()).unwrap();
+        assert_eq!(migrate_once(&mut saved), None);
+        assert_eq!(saved.daily_tasks[1].description, "- [ ] typed later");
+        assert_eq!(items(&saved.daily_tasks[0].checklist), [("a", false)]);
+    }
+
+    #[test]
+    fn carried_tasks_take_the_unfinished_items() {
+        let checklist = [item("a", true), item("b", false), item("c", false)];
+        assert_eq!(items(&unfinished(&checklist)), [("b", false), ("c", false)]);
+        assert!(unfinished(&[item("done", true)]).is_empty());
+    }
+
+    #[test]
+    fn the_checklist_survives_moving_between_task_and_todo() {
+        let task = DailyTask {
+            id: "task".to_string(),
+            text: "Ship it".to_string(),
+            date: "2026-03-02".to_string(),
+            checklist: vec![item("write", true), item("review", false)],
+            ..Default::default()
+        };
+        // Deferring to the backlog copies the checklist as the webview does.
+        let task_json = serde_json::to_value(&task).unwrap();
+        let todo: Todo = serde_json::from_value(serde_json::json!({
+            "id": "todo",
+            "text": task_json["text"],
+            "createdAt": "2026-03-02T10:00:00.000Z",
+            "checklist": task_json["checklist"],
+        }))
+        .unwrap();
+        assert_eq!(items(&todo.checklist), [("write", true), ("review", false)]);
+
+        // Scheduling it again.
+        let todo_json = serde_json::to_value(&todo).unwrap();
+        let scheduled: DailyTask = serde_json::from_value(serde_json::json!({
+            "id": "again",
+            "text": todo_json["text"],
+            "date": "2026-03-03",
+            "completed": false,
+            "category": "work",
+            "checklist": todo_json["checklist"],
+        }))
+        .unwrap();
+        assert_eq!(items(&scheduled.checklist), items(&task.checklist));
+        assert_eq!(scheduled.checklist[0].id, "write");
+
+        // Completing the todo from a reminder makes it a task, checklist and all.
+        let mut data = AppData { todos: vec![todo], ..Default::default() };
+        task_reminders::complete(&mut data, ItemKind::Todo, "todo").unwrap();
+        assert!(data.todos.is_empty());
+        assert_eq!(items(&data.daily_tasks[0].checklist), [("write", true), ("review", false)]);
+    }
+}
@@ -0,0 +1,83 @@
+#[cfg(test)]
+use std::sync::Mutex;
+
+use chrono::DateTime;
+#[cfg(test)]
+use chrono::Duration;
+use chrono_tz::Tz;
+
+use crate::tz;
+
+/// Where the schedulers get the current time from, so their logic can be
+/// driven by a fake clock. The time comes in the zone schedules are computed
+/// in, which may change between calls.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Tz>;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Tz> {
+        tz::now()
+    }
+}
+
+/// A clock that only moves when told to, for exercising schedules
+/// deterministically.
+#[cfg(test)]
+pub struct ManualClock {
+    now: Mutex<DateTime<Tz>>,
+}
+
+#[cfg(test)]
+impl ManualClock {
+    pub fn new(now: DateTime<Tz>) -> Self {
+        Self { now: Mutex::new(now) }
+    }
+
+    pub fn set(&self, now: DateTime<Tz>) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) = now;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) += by;
+    }
+
+    /// Moves to another zone at the same instant, as when travelling.
+    pub fn set_zone(&self, zone: Tz) {
+        let mut now = self.now.lock().unwrap_or_else(|e| e.into_inner());
+        *now = now.with_timezone(&zone);
+    }
+}
+
+#[cfg(test)]
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Tz> {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn manual_clock_moves_only_when_told() {
+        let start = chrono_tz::Europe::Berlin.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap();
+        let clock = ManualClock::new(start);
+        assert_eq!(clock.now(), start);
+
+        clock.advance(Duration::minutes(90));
+        assert_eq!(clock.now(), start + Duration::minutes(90));
+
+        // Travelling keeps the instant and changes the wall clock.
+        clock.set_zone(chrono_tz::America::New_York);
+        assert_eq!(clock.now(), start + Duration::minutes(90));
+        assert_eq!(clock.now().format("%H:%M").to_string(), "04:30");
+
+        clock.set(start);
+        assert_eq!(clock.now(), start);
+    }
+}
@@ -0,0 +1,86 @@
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+
+use crate::models::AppData;
+
+type Listener = Box<dyn Fn(&Arc<AppData>) + Send>;
+
+/// Hands every saved copy of the data to the background workers, so they
+/// keep what they need in memory instead of re-reading the data file. The
+/// latest copy is kept for code that only needs a look at it.
+#[derive(Default)]
+pub struct DataBus {
+    listeners: Mutex<Vec<Listener>>,
+    latest: Mutex<Option<Arc<AppData>>>,
+}
+
+impl DataBus {
+    pub fn listen<F>(&self, listener: F)
+    where
+        F: Fn(&Arc<AppData>) + Send + 'static,
+    {
+        self.listeners.lock().unwrap_or_else(|e| e.into_inner()).push(Box::new(listener));
+    }
+
+    /// A channel receiving each saved copy of the data.
+    pub fn subscribe(&self) -> Receiver<Arc<AppData>> {
+        let (sender, receiver) = mpsc::channel();
+        self.listen(move |data| {
+            let _ = sender.send(data.clone());
+        });
+        receiver
+    }
+
+    pub fn publish(&self, data: AppData) {
+        let data = Arc::new(data);
+        *self.latest.lock().unwrap_or_else(|e| e.into_inner()) = Some(data.clone());
+        for listener in self.listeners.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+            listener(&data);
+        }
+    }
+
+    /// The last saved copy, or the one `load` reads if nothing has been
+    /// saved since launch.
+    pub fn latest_or_load<F>(&self, load: F) -> Result<Arc<AppData>, String>
+    where
+        F: FnOnce() -> Result<AppData, String>,
+    {
+        if let Some(data) = self.latest.lock().unwrap_or_else(|e| e.into_inner()).clone() {
+            return Ok(data);
+        }
+        let data = Arc::new(load()?);
+        // A save while loading wins over the copy read from disk.
+        Ok(self.latest.lock().unwrap_or_else(|e| e.into_inner()).get_or_insert(data).clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(timezone: &str) -> AppData {
+        AppData { timezone: Some(timezone.to_string()), ..Default::default() }
+    }
+
+    #[test]
+    fn loads_once_then_serves_saved_copies() {
+        let bus = DataBus::default();
+        let loaded = bus.latest_or_load(|| Ok(data("Europe/Paris"))).unwrap();
+        assert_eq!(loaded.timezone.as_deref(), Some("Europe/Paris"));
+        let again = bus.latest_or_load(|| panic!("read the file twice")).unwrap();
+        assert!(Arc::ptr_eq(&loaded, &again));
+
+        let received = bus.subscribe();
+        bus.publish(data("Asia/Tokyo"));
+        assert_eq!(received.try_recv().unwrap().timezone.as_deref(), Some("Asia/Tokyo"));
+        let latest = bus.latest_or_load(|| panic!("read the file after a save")).unwrap();
+        assert_eq!(latest.timezone.as_deref(), Some("Asia/Tokyo"));
+    }
+
+    #[test]
+    fn a_failed_load_is_not_remembered() {
+        let bus = DataBus::default();
+        assert!(bus.latest_or_load(|| Err("unreadable".to_string())).is_err());
+        assert!(bus.latest_or_load(|| Ok(AppData::default())).is_ok());
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+use url::Url;
+
+use crate::actions::Request;
+
+pub const SCHEME: &str = "growing";
+
+const MAX_TEXT_CHARS: usize = 1000;
+const MAX_TIMER_MINUTES: u32 = 24 * 60;
+
+/// Parses a `growing://` quick-capture link into the request it stands for.
+///
+/// Supported links:
+/// - `growing://task/new?text=...&category=work|personal`
+/// - `growing://review/new?url=...`
+/// - `growing://timer/start?minutes=25`
+/// - `growing://note?text=...`
+///
+/// Anything else, including unknown or repeated parameters, is rejected
+/// rather than guessed at, since links can come from any app or web page.
+pub fn parse(link: &str) -> Result<Request, String> {
+    let url = Url::parse(link.trim()).map_err(|e| format!("Invalid link: {}", e))?;
+
+    if url.scheme() != SCHEME {
+        return Err(format!("Unsupported scheme: {}", url.scheme()));
+    }
+    if !url.username().is_empty() || url.password().is_some() || url.port().is_some() || url.fragment().is_some() {
+        return Err("Links must not contain credentials, ports or fragments".to_string());
+    }
+
+    let host = url.host_str().unwrap_or_default();
+    let path = url.path().trim_matches('/');
+    let mut params = query_params(&url)?;
+
+    let request = match (host, path) {
+        ("task", "new") => {
+            expect_only(&params, &["text", "category"])?;
+            let category = match params.remove("category").as_deref() {
+                None => None,
+                Some(c @ ("work" | "personal")) => Some(c.to_string()),
+                Some(other) => return Err(format!("Unknown category: {}", other)),
+            };
+            Request::AddTask {
+                text: text_param(&mut params, "text")?,
+                description: String::new(),
+                category,
+                date: None,
+            }
+        }
+        ("review", "new") => {
+            expect_only(&params, &["url"])?;
+            let pr_url = required(&mut params, "url")?;
+            let parsed = Url::parse(&pr_url).map_err(|_| format!("Invalid review url: {}", pr_url))?;
+            if parsed.scheme() != "https" {
+                return Err("Review links must use https".to_string());
+            }
+            Request::AddReview { url: pr_url, re_review: false }
+        }
+        ("timer", "start") => {
+            expect_only(&params, &["minutes"])?;
+            let raw = required(&mut params, "minutes")?;
+            let minutes = raw
+                .parse::<u32>()
+                .ok()
+                .filter(|m| raw.bytes().all(|b| b.is_ascii_digit()) && (1..=MAX_TIMER_MINUTES).contains(m))
+                .ok_or_else(|| format!("minutes must be a whole number from 1 to {}", MAX_TIMER_MINUTES))?;
+            Request::StartTimer { minutes: minutes as i32, task_id: None, name: None }
+        }
+        ("note", "") => {
+            expect_only(&params, &["text"])?;
+            Request::AddNote { text: text_param(&mut params, "text")? }
+        }
+        _ => return Err(format!("Unknown link: {}://{}/{}", SCHEME, host, path)),
+    };
+
+    Ok(request)
+}
+
+/// Picks out `growing://` links from a process argument list, as forwarded by
+/// the single-instance plugin on Linux and Windows. Only release builds
+/// register that plugin.
+#[cfg_attr(debug_assertions, allow(dead_code))]
+pub fn links_in_args(args: &[String]) -> Vec<String> {
+    args.iter()
+        .filter(|arg| arg.starts_with(&format!("{}://", SCHEME)))
+        .cloned()
+        .collect()
+}
+
+fn query_params(url: &Url) -> Result<HashMap<String, String>, String> {
+    let mut params = HashMap::new();
+    for (key, value) in url.query_pairs() {
+        if params.insert(key.to_string(), value.to_string()).is_some() {
+            return Err(format!("Parameter {} given more than once", key));
+        }
+    }
+    Ok(params)
+}
+
+fn expect_only(params: &HashMap<String, String>, allowed: &[&str]) -> Result<(), String> {
+    match params.keys().find(|key| !allowed.contains(&key.as_str())) {
+        Some(key) => Err(format!("Unknown parameter: {}", key)),
+        None => Ok(()),
+    }
+}
+
+fn required(params: &mut HashMap<String, String>, key: &str) -> Result<String, String> {
+    params
+        .remove(key)
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| format!("Missing required parameter: {}", key))
+}
+
+fn text_param(params: &mut HashMap<String, String>, key: &str) -> Result<String, String> {
+    let text = required(params, key)?;
+    if text.chars().count() > MAX_TEXT_CHARS {
+        return Err(format!("{} is longer than {} characters", key, MAX_TEXT_CHARS));
+    }
+    if text.chars().any(|c| c.is_control() && c != '\n' && c != '\t') {
+        return Err(format!("{} contains control characters", key));
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rejected(link: &str) -> String {
+        match parse(link) {
+            Ok(request) => panic!("{} parsed as {:?}", link, request),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn task_links_add_a_task() {
+        match parse("growing://task/new?text=Buy%20milk%20%26%20eggs&category=personal").unwrap() {
+            Request::AddTask { text, description, category, date } => {
+                assert_eq!(text, "Buy milk & eggs");
+                assert_eq!(description, "");
+                assert_eq!(category.as_deref(), Some("personal"));
+                assert_eq!(date, None);
+            }
+            other => panic!("unexpected request {:?}", other),
+        }
+        assert!(matches!(parse("growing://task/new/?text=x"), Ok(Request::AddTask { category: None, .. })));
+        assert_eq!(rejected("growing://task/new?text=x&category=chores"), "Unknown category: chores");
+    }
+
+    #[test]
+    fn review_links_need_https() {
+        match parse("growing://review/new?url=https%3A%2F%2Fgithub.com%2Fo%2Fr%2Fpull%2F1").unwrap() {
+            Request::AddReview { url, re_review } => {
+                assert_eq!(url, "https://github.com/o/r/pull/1");
+                assert!(!re_review);
+            }
+            other => panic!("unexpected request {:?}", other),
+        }
+        assert_eq!(rejected("growing://review/new?url=http://github.com/o/r/pull/1"), "Review links must use https");
+        assert_eq!(rejected("growing://review/new?url=not%20a%20url"), "Invalid review url: not a url");
+    }
+
+    #[test]
+    fn timer_links_take_whole_minutes() {
+        assert!(matches!(
+            parse("growing://timer/start?minutes=25"),
+            Ok(Request::StartTimer { minutes: 25, task_id: None, name: None })
+        ));
+        assert!(matches!(parse("growing://timer/start?minutes=1440"), Ok(Request::StartTimer { minutes: 1440, .. })));
+        for minutes in ["0", "1441", "-5", "%2B5", "2.5", "abc", "1e2", "99999999999"] {
+            let e = rejected(&format!("growing://timer/start?minutes={}", minutes));
+            assert_eq!(e, "minutes must be a whole number from 1 to 1440", "{}", minutes);
+        }
+    }
+
+    #[test]
+    fn note_links_add_a_note() {
+        match parse(" growing://note?text=Line%20one%0ALine%20two ").unwrap() {
+            Request::AddNote { text } => assert_eq!(text, "Line one\nLine two"),
+            other => panic!("unexpected request {:?}", other),
+        }
+        assert_eq!(rejected("growing://note?text=a%07b"), "text contains control characters");
+    }
+
+    #[test]
+    fn unknown_links_are_rejected() {
+        assert_eq!(rejected("growing://task/delete?text=x"), "Unknown link: growing://task/delete");
+        assert_eq!(rejected("growing://calendar?text=x"), "Unknown link: growing://calendar/");
+        assert_eq!(rejected("growing://note/new?text=x"), "Unknown link: growing://note/new");
+        assert_eq!(rejected("https://task/new?text=x"), "Unsupported scheme: https");
+        assert!(rejected("growing://user:pw@task/new?text=x").contains("credentials"));
+        assert!(rejected("growing://task:8080/new?text=x").contains("ports"));
+        assert!(rejected("growing://task/new?text=x#frag").contains("fragments"));
+        assert!(rejected("not a link").starts_with("Invalid link"));
+    }
+
+    #[test]
+    fn parameters_are_checked() {
+        assert_eq!(rejected("growing://note?text=a&extra=1"), "Unknown parameter: extra");
+        assert_eq!(rejected("growing://note?text=a&text=b"), "Parameter text given more than once");
+        assert_eq!(rejected("growing://note"), "Missing required parameter: text");
+        assert_eq!(rejected("growing://note?text=%20%20"), "Missing required parameter: text");
+        assert_eq!(rejected("growing://timer/start"), "Missing required parameter: minutes");
+        assert_eq!(rejected("growing://review/new?url="), "Missing required parameter: url");
+
+        let longest = "a".repeat(MAX_TEXT_CHARS);
+        assert!(parse(&format!("growing://note?text={}", longest)).is_ok());
+        assert_eq!(rejected(&format!("growing://note?text={}a", longest)), "text is longer than 1000 characters");
+        // Characters, not bytes.
+        assert!(parse(&format!("growing://note?text={}", "é".repeat(MAX_TEXT_CHARS))).is_ok());
+    }
+
+    #[test]
+    fn links_are_picked_from_arguments() {
+        let args = ["/usr/bin/growing", "--flag", "growing://note?text=a", "https://example.com", "growing://timer/start?minutes=5"]
+            .map(String::from);
+        assert_eq!(links_in_args(&args), ["growing://note?text=a", "growing://timer/start?minutes=5"]);
+    }
+}
@@ -0,0 +1,254 @@
+use std::sync::mpsc::{self, Sender};
+use std::sync::Arc;
+use std::thread;
+
+use crate::models::{ActiveTimer, FocusMusicRule};
+use crate::music::MusicProvider;
+use crate::timers::{self, TimerEvent};
+
+enum Update {
+    Rules(Vec<FocusMusicRule>),
+    Timer { event: Box<TimerEvent>, running: Vec<ActiveTimer> },
+}
+
+/// Starts, pauses and resumes the music player along with timers, following
+/// the `focusMusic` rules for each timer type. Runs on its own thread, as
+/// player commands can take a while, longer still when the player has to
+/// launch first; events are handled in the order the timers produced them.
+#[derive(Clone)]
+pub struct FocusMusic {
+    updates: Sender<Update>,
+}
+
+impl FocusMusic {
+    pub fn start(music: Arc<dyn MusicProvider>, rules: Vec<FocusMusicRule>) -> Self {
+        let (updates, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let mut state = FocusMusicState { music, rules, paused_for: None };
+            for update in receiver {
+                match update {
+                    Update::Rules(rules) => state.rules = rules,
+                    Update::Timer { event, running } => state.on_timer_event(&event, &running),
+                }
+            }
+        });
+        Self { updates }
+    }
+
+    pub fn update(&self, rules: Vec<FocusMusicRule>) {
+        let _ = self.updates.send(Update::Rules(rules));
+    }
+
+    /// `running` are all the timers at the time of the event.
+    pub fn on_timer_event(&self, event: &TimerEvent, running: Vec<ActiveTimer>) {
+        let _ = self.updates.send(Update::Timer { event: Box::new(event.clone()), running });
+    }
+}
+
+struct FocusMusicState {
+    music: Arc<dyn MusicProvider>,
+    rules: Vec<FocusMusicRule>,
+    /// The timer, or pomodoro session, the music was paused for. Only music
+    /// paused here is resumed; music the user paused stays paused.
+    paused_for: Option<String>,
+}
+
+impl FocusMusicState {
+    fn on_timer_event(&mut self, event: &TimerEvent, running: &[ActiveTimer]) {
+        let timer = event.timer();
+        let Some(rule) = self.rule(timer).cloned() else {
+            return;
+        };
+        let working = timers::is_focus(timer) && timer.paused_at.is_none() && timer.expired_at.is_none();
+        match event {
+            TimerEvent::Started { .. } if working => self.start(&rule, session(timer)),
+            TimerEvent::Resumed { .. } | TimerEvent::Extended { .. } if working => self.resume(session(timer)),
+            // Extending a running timer changes nothing.
+            TimerEvent::Extended { .. } => {}
+            // A pause, an end, or a break starting.
+            _ if rule.pause_on_end && !self.others_working(timer, running) => self.pause(session(timer)),
+            _ => {}
+        }
+    }
+
+    fn rule(&self, timer: &ActiveTimer) -> Option<&FocusMusicRule> {
+        self.rules.iter().find(|rule| rule.enabled && rule.timer_type == timer.timer_type)
+    }
+
+    /// Whether another timer with music still runs, so the music should too.
+    fn others_working(&self, timer: &ActiveTimer, running: &[ActiveTimer]) -> bool {
+        running.iter().any(|other| {
+            other.id != timer.id
+                && timers::is_focus(other)
+                && other.paused_at.is_none()
+                && other.expired_at.is_none()
+                && self.rule(other).is_some()
+        })
+    }
+
+    /// A new pomodoro work phase picks up where the last one was paused;
+    /// anything else starts the playlist.
+    fn start(&mut self, rule: &FocusMusicRule, session: &str) {
+        let resuming = self.paused_for.take().as_deref() == Some(session);
+        if resuming || rule.playlist.is_empty() {
+            self.play();
+        } else {
+            self.music.play_playlist(&rule.playlist);
+        }
+    }
+
+    fn resume(&mut self, session: &str) {
+        if self.paused_for.as_deref() == Some(session) {
+            self.paused_for = None;
+            self.play();
+        }
+    }
+
+    fn play(&self) {
+        if !self.music.is_playing() {
+            self.music.play_pause();
+        }
+    }
+
+    fn pause(&mut self, session: &str) {
+        if self.music.is_playing() {
+            self.music.play_pause();
+            self.paused_for = Some(session.to_string());
+        }
+    }
+}
+
+/// Phases of one pomodoro session count as one timer.
+fn session(timer: &ActiveTimer) -> &str {
+    timer.session_id.as_deref().unwrap_or(&timer.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::music::MockMusic;
+
+    fn rule(timer_type: &str, playlist: &str) -> FocusMusicRule {
+        FocusMusicRule { timer_type: timer_type.to_string(), enabled: true, playlist: playlist.to_string(), pause_on_end: true }
+    }
+
+    fn state(rules: Vec<FocusMusicRule>) -> (FocusMusicState, Arc<MockMusic>) {
+        let music = Arc::new(MockMusic::default());
+        music.play_pause();
+        (FocusMusicState { music: music.clone(), rules, paused_for: None }, music)
+    }
+
+    fn timer(id: &str, timer_type: &str) -> ActiveTimer {
+        ActiveTimer { id: id.to_string(), timer_type: timer_type.to_string(), ..Default::default() }
+    }
+
+    fn phase(id: &str, phase: &str) -> ActiveTimer {
+        ActiveTimer {
+            session_id: Some("session".to_string()),
+            pomodoro_phase: Some(phase.to_string()),
+            ..timer(id, "pomodoro")
+        }
+    }
+
+    fn paused(timer: &ActiveTimer) -> ActiveTimer {
+        ActiveTimer { paused_at: Some("2026-03-02T09:00:00.000Z".to_string()), ..timer.clone() }
+    }
+
+    /// Whether the player is playing, and what.
+    fn playing(music: &MockMusic) -> (bool, String, String) {
+        let info = music.now_playing();
+        (info.is_playing, info.title.unwrap_or_default(), info.album.unwrap_or_default())
+    }
+
+    #[test]
+    fn starting_a_timer_starts_its_playlist() {
+        let (mut state, music) = state(vec![rule("focus", "Deep Work"), rule("task", "")]);
+        state.on_timer_event(&TimerEvent::Started { timer: timer("f", "focus") }, &[]);
+        assert_eq!(playing(&music), (true, "Track 1".to_string(), "Deep Work".to_string()));
+        state.on_timer_event(&TimerEvent::Cancelled { timer: timer("f", "focus") }, &[]);
+        assert!(!music.is_playing());
+
+        // Without a playlist the timer just presses play on what's queued.
+        music.next_track();
+        state.on_timer_event(&TimerEvent::Started { timer: timer("t", "task") }, &[]);
+        assert_eq!(playing(&music), (true, "Track 2".to_string(), "Deep Work".to_string()));
+
+        // Timer types without an enabled rule leave the player alone.
+        music.play_pause();
+        state.on_timer_event(&TimerEvent::Started { timer: timer("p", "pomodoro") }, &[]);
+        state.rules[0].enabled = false;
+        state.on_timer_event(&TimerEvent::Started { timer: timer("f2", "focus") }, &[]);
+        assert!(!music.is_playing());
+    }
+
+    #[test]
+    fn only_music_paused_for_the_timer_is_resumed() {
+        let (mut state, music) = state(vec![rule("focus", "Deep Work")]);
+        let focus = timer("f", "focus");
+        state.on_timer_event(&TimerEvent::Started { timer: focus.clone() }, &[]);
+        state.on_timer_event(&TimerEvent::Paused { timer: paused(&focus) }, &[]);
+        assert!(!music.is_playing());
+        state.on_timer_event(&TimerEvent::Resumed { timer: focus.clone() }, &[]);
+        assert!(music.is_playing());
+
+        // The user pauses the music themselves; pausing and resuming the
+        // timer leaves it paused.
+        music.play_pause();
+        state.on_timer_event(&TimerEvent::Paused { timer: paused(&focus) }, &[]);
+        state.on_timer_event(&TimerEvent::Resumed { timer: focus.clone() }, &[]);
+        assert!(!music.is_playing());
+
+        // Music paused for another timer isn't resumed by this one.
+        music.play_pause();
+        let other = timer("g", "focus");
+        state.on_timer_event(&TimerEvent::Expired { timer: other, late: false }, &[]);
+        assert!(!music.is_playing());
+        state.on_timer_event(&TimerEvent::Resumed { timer: focus }, &[]);
+        assert!(!music.is_playing());
+    }
+
+    #[test]
+    fn music_keeps_playing_while_another_timer_works() {
+        let (mut state, music) = state(vec![rule("focus", "Deep Work"), rule("task", "")]);
+        let focus = timer("f", "focus");
+        let task = timer("t", "task");
+        state.on_timer_event(&TimerEvent::Started { timer: focus.clone() }, &[]);
+        state.on_timer_event(&TimerEvent::Expired { timer: focus.clone(), late: false }, &[focus.clone(), task.clone()]);
+        assert!(music.is_playing());
+
+        // A paused timer or one without a music rule doesn't count.
+        let plain = timer("c", "custom");
+        state.on_timer_event(&TimerEvent::Expired { timer: focus.clone(), late: false }, &[paused(&task), plain]);
+        assert!(!music.is_playing());
+
+        // Nor does pausing when the rule says not to.
+        music.play_pause();
+        state.rules[0].pause_on_end = false;
+        state.on_timer_event(&TimerEvent::Cancelled { timer: focus }, &[]);
+        assert!(music.is_playing());
+    }
+
+    #[test]
+    fn a_break_pauses_and_the_next_work_phase_resumes() {
+        let (mut state, music) = state(vec![rule("pomodoro", "Deep Work")]);
+        let work = phase("w1", "work");
+        state.on_timer_event(&TimerEvent::Started { timer: work.clone() }, &[]);
+        music.next_track();
+        music.next_track();
+        state.on_timer_event(&TimerEvent::Expired { timer: work, late: false }, &[]);
+        let rest = phase("b1", "shortBreak");
+        state.on_timer_event(&TimerEvent::Started { timer: rest.clone() }, std::slice::from_ref(&rest));
+        assert!(!music.is_playing());
+
+        // The next work phase carries on with the track, not the playlist's start.
+        state.on_timer_event(&TimerEvent::Expired { timer: rest, late: false }, &[]);
+        state.on_timer_event(&TimerEvent::Started { timer: phase("w2", "work") }, &[]);
+        assert_eq!(playing(&music), (true, "Track 3".to_string(), "Deep Work".to_string()));
+
+        // A new session starts the playlist over.
+        music.play_pause();
+        let next_session = ActiveTimer { session_id: Some("next".to_string()), ..phase("w3", "work") };
+        state.on_timer_event(&TimerEvent::Started { timer: next_session }, &[]);
+        assert_eq!(playing(&music), (true, "Track 1".to_string(), "Deep Work".to_string()));
+    }
+}
@@ -0,0 +1,163 @@
+#[cfg(target_os = "linux")]
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Reports how long the user has been away from the keyboard and mouse.
+pub trait IdleDetector: Send + Sync {
+    /// Seconds since the last input event, or `None` if it can't be told.
+    fn idle_seconds(&self) -> Option<u64>;
+}
+
+/// Picks the detector for this platform. Setting `GROWING_MOCK_IDLE_SECONDS`
+/// substitutes a fixed reading, for trying out the idle handling by hand.
+pub fn detector() -> Box<dyn IdleDetector> {
+    if let Some(seconds) = std::env::var("GROWING_MOCK_IDLE_SECONDS").ok().and_then(|s| s.parse().ok()) {
+        return Box::new(MockIdle::new(seconds));
+    }
+    platform_detector()
+}
+
+#[cfg(target_os = "macos")]
+fn platform_detector() -> Box<dyn IdleDetector> {
+    Box::new(MacIdle)
+}
+
+#[cfg(target_os = "linux")]
+fn platform_detector() -> Box<dyn IdleDetector> {
+    Box::new(LinuxIdle)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn platform_detector() -> Box<dyn IdleDetector> {
+    Box::new(NoIdle)
+}
+
+/// Reports the idle time it was given. Clones share the reading, so tests can
+/// keep one to change what the detector in use reports.
+#[derive(Clone)]
+pub struct MockIdle {
+    seconds: Arc<AtomicU64>,
+}
+
+impl MockIdle {
+    pub fn new(seconds: u64) -> Self {
+        Self { seconds: Arc::new(AtomicU64::new(seconds)) }
+    }
+
+    #[cfg(test)]
+    pub fn set(&self, seconds: u64) {
+        self.seconds.store(seconds, Ordering::Relaxed);
+    }
+}
+
+impl IdleDetector for MockIdle {
+    fn idle_seconds(&self) -> Option<u64> {
+        Some(self.seconds.load(Ordering::Relaxed))
+    }
+}
+
+#[cfg(target_os = "macos")]
+struct MacIdle;
+
+#[cfg(target_os = "macos")]
+impl IdleDetector for MacIdle {
+    fn idle_seconds(&self) -> Option<u64> {
+        #[link(name = "CoreGraphics", kind = "framework")]
+        extern "C" {
+            fn CGEventSourceSecondsSinceLastEventType(state: i32, event_type: u32) -> f64;
+        }
+        // kCGEventSourceStateCombinedSessionState, kCGAnyInputEventType
+        let seconds = unsafe { CGEventSourceSecondsSinceLastEventType(0, !0) };
+        (seconds.is_finite() && seconds >= 0.0).then_some(seconds as u64)
+    }
+}
+
+/// Asks logind for the session's idle hint, falling back to `xprintidle` on
+/// X11 sessions where the desktop doesn't report idleness to logind.
+#[cfg(target_os = "linux")]
+struct LinuxIdle;
+
+#[cfg(target_os = "linux")]
+impl IdleDetector for LinuxIdle {
+    fn idle_seconds(&self) -> Option<u64> {
+        logind_idle_seconds().or_else(xprintidle_seconds)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn logind_idle_seconds() -> Option<u64> {
+    let session = std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "self".to_string());
+    let output = Command::new("loginctl")
+        .args(["show-session", &session, "-p", "IdleHint", "-p", "IdleSinceHint"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).ok()?;
+    parse_logind_idle(&String::from_utf8_lossy(&output.stdout), now.as_secs())
+}
+
+/// Reads `loginctl show-session -p IdleHint -p IdleSinceHint` output.
+/// `IdleSinceHint` (microseconds since the Unix epoch) stays 0 on sessions
+/// whose desktop never reports idleness to logind; there a "no" hint says
+/// nothing, so `None` lets another source be asked.
+#[cfg(any(target_os = "linux", test))]
+fn parse_logind_idle(text: &str, now_secs: u64) -> Option<u64> {
+    let value = |key: &str| text.lines().find_map(|line| line.trim().strip_prefix(key)?.strip_prefix('='));
+    let since: u64 = value("IdleSinceHint")?.parse().ok().filter(|us| *us > 0)?;
+    match value("IdleHint")? {
+        "yes" => Some(now_secs.saturating_sub(since / 1_000_000)),
+        _ => Some(0),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn xprintidle_seconds() -> Option<u64> {
+    let output = Command::new("xprintidle").output().ok().filter(|o| o.status.success())?;
+    let millis: u64 = String::from_utf8_lossy(&output.stdout).trim().parse().ok()?;
+    Some(millis / 1000)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+struct NoIdle;
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+impl IdleDetector for NoIdle {
+    fn idle_seconds(&self) -> Option<u64> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_772_445_600;
+
+    #[test]
+    fn logind_idle_hint_precedence() {
+        let since = (NOW - 300) * 1_000_000;
+        let idle = format!("IdleHint=yes\nIdleSinceHint={}\n", since);
+        assert_eq!(parse_logind_idle(&idle, NOW), Some(300));
+        // The order of the properties doesn't matter.
+        assert_eq!(parse_logind_idle(&format!("IdleSinceHint={}\nIdleHint=yes\n", since), NOW), Some(300));
+        // A desktop that reports idleness and says the user is active.
+        assert_eq!(parse_logind_idle(&format!("IdleHint=no\nIdleSinceHint={}\n", since), NOW), Some(0));
+        // A clock that went backwards doesn't make the idle time negative.
+        assert_eq!(parse_logind_idle(&format!("IdleHint=yes\nIdleSinceHint={}\n", (NOW + 60) * 1_000_000), NOW), Some(0));
+    }
+
+    #[test]
+    fn sessions_without_idle_reporting_fall_back() {
+        for output in [
+            "IdleHint=no\nIdleSinceHint=0\n",
+            "IdleHint=no\n",
+            "IdleHint=yes\nIdleSinceHint=0\n",
+            "IdleHint=no\nIdleSinceHint=\n",
+            "IdleSinceHint=1772445300000000\n",
+            "",
+        ] {
+            assert_eq!(parse_logind_idle(output, NOW), None, "{:?}", output);
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use url::Url;
+
+/// Where the iTunes Search API is reached unless `GROWING_ITUNES_URL` points
+/// somewhere else, such as a local stub server.
+const DEFAULT_BASE_URL: &str = "https://itunes.apple.com";
+
+/// A lookup is abandoned after this long, so a slow network never holds up
+/// the now playing bar or an "open in Music" click for long.
+const TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Artwork larger than this is not worth waiting for.
+const MAX_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    results: Vec<SearchResult>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchResult {
+    #[cfg(any(target_os = "macos", test))]
+    artist_id: Option<u64>,
+    #[cfg(any(target_os = "macos", test))]
+    collection_id: Option<u64>,
+    artwork_url100: Option<String>,
+}
+
+/// Client for the iTunes Search API, used to find Apple Music pages and
+/// album artwork by name.
+pub struct ITunesClient {
+    base_url: Url,
+    agent: ureq::Agent,
+}
+
+impl ITunesClient {
+    pub fn new() -> Self {
+        let base_url = std::env::var("GROWING_ITUNES_URL")
+            .ok()
+            .and_then(|url| match Url::parse(&url) {
+                Ok(url) => Some(url),
+                Err(e) => {
+                    eprintln!("Invalid GROWING_ITUNES_URL {}: {}", url, e);
+                    None
+                }
+            })
+            .unwrap_or_else(|| Url::parse(DEFAULT_BASE_URL).expect("default iTunes URL is valid"));
+        Self::with_base_url(base_url)
+    }
+
+    pub fn with_base_url(base_url: Url) -> Self {
+        Self::with_timeout(base_url, TIMEOUT)
+    }
+
+    fn with_timeout(base_url: Url, timeout: Duration) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(timeout))
+            .user_agent(concat!("Growing/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .into();
+        Self { base_url, agent }
+    }
+
+    /// The Apple Music id of the best match for `artist`.
+    #[cfg(any(target_os = "macos", test))]
+    pub fn artist_id(&self, artist: &str) -> Option<u64> {
+        self.search(artist, "musicArtist", 1).into_iter().find_map(|result| result.artist_id)
+    }
+
+    /// The Apple Music id of the best match for `album`, narrowed down by
+    /// `artist` when known.
+    #[cfg(any(target_os = "macos", test))]
+    pub fn album_id(&self, album: &str, artist: Option<&str>) -> Option<u64> {
+        let term = match artist {
+            Some(artist) => format!("{} {}", album, artist),
+            None => album.to_string(),
+        };
+        self.search(&term, "album", 1).into_iter().find_map(|result| result.collection_id)
+    }
+
+    /// Cover art for the album, at 300 pixels.
+    pub fn album_artwork(&self, artist: &str, album: &str) -> Option<Vec<u8>> {
+        let artwork_url = self
+            .search(&format!("{} {}", artist, album), "album", 5)
+            .into_iter()
+            .find_map(|result| result.artwork_url100)?;
+        let image = self
+            .agent
+            .get(artwork_url.replace("100x100bb", "300x300bb"))
+            .call()
+            .and_then(|mut response| response.body_mut().with_config().limit(MAX_IMAGE_BYTES).read_to_vec());
+        match image {
+            Ok(image) if !image.is_empty() => Some(image),
+            Ok(_) => None,
+            Err(e) => {
+                eprintln!("Failed to download artwork for {} - {}: {}", artist, album, e);
+                None
+            }
+        }
+    }
+
+    fn search(&self, term: &str, entity: &str, limit: u32) -> Vec<SearchResult> {
+        let url = self.search_url(term, entity, limit);
+        let response = self
+            .agent
+            .get(url.as_str())
+            .call()
+            .and_then(|mut response| response.body_mut().read_to_vec());
+        let body = match response {
+            Ok(body) => body,
+            Err(e) => {
+                eprintln!("iTunes search for {} failed: {}", term, e);
+                return Vec::new();
+            }
+        };
+        match serde_json::from_slice::<SearchResponse>(&body) {
+            Ok(response) => response.results,
+            Err(e) => {
+                eprintln!("Unreadable iTunes search response for {}: {}", term, e);
+                Vec::new()
+            }
+        }
+    }
+
+    fn search_url(&self, term: &str, entity: &str, limit: u32) -> Url {
+        let mut url = self.base_url.clone();
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.pop_if_empty().push("search");
+        }
+        url.query_pairs_mut()
+            .append_pair("term", term)
+            .append_pair("entity", entity)
+            .append_pair("limit", &limit.to_string());
+        url
+    }
+}
+
+/// Apple Music's own search for `term`, for when the lookup finds nothing.
+#[cfg(any(target_os = "macos", test))]
+pub fn music_search_url(term: &str) -> String {
+    let encoded: String = url::form_urlencoded::byte_serialize(term.as_bytes()).collect();
+    format!("itmss://music.apple.com/search?term={}", encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc::{self, Receiver};
+    use std::thread;
+
+    /// Answers one request per entry `responses` gives for the stub's URL
+    /// with the body in it, or never answers for `None`. Sends back each
+    /// request's path and query.
+    fn stub<F>(responses: F) -> (Url, Receiver<String>)
+    where
+        F: FnOnce(&Url) -> Vec<Option<Vec<u8>>>,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        let responses = responses(&url);
+        let (requests, received) = mpsc::channel();
+        thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let _ = requests.send(line.split(' ').nth(1).unwrap_or_default().to_string());
+                loop {
+                    let mut header = String::new();
+                    if reader.read_line(&mut header).unwrap_or(0) == 0 || header == "\r\n" {
+                        break;
+                    }
+                }
+                match response {
+                    Some(body) => {
+                        let head = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len());
+                        let _ = stream.write_all(head.as_bytes());
+                        let _ = stream.write_all(&body);
+                    }
+                    None => thread::sleep(Duration::from_secs(2)),
+                }
+            }
+        });
+        (url, received)
+    }
+
+    fn json(value: serde_json::Value) -> Option<Vec<u8>> {
+        Some(value.to_string().into_bytes())
+    }
+
+    #[test]
+    fn search_terms_are_encoded() {
+        let (url, requests) = stub(|_| vec![json(serde_json::json!({"results": [{"artistId": 42}]}))]);
+        let client = ITunesClient::with_base_url(url);
+        assert_eq!(client.artist_id("Björk & Friends #1?"), Some(42));
+        assert_eq!(
+            requests.recv().unwrap(),
+            "/search?term=Bj%C3%B6rk+%26+Friends+%231%3F&entity=musicArtist&limit=1"
+        );
+        assert_eq!(music_search_url("AC/DC & co"), "itmss://music.apple.com/search?term=AC%2FDC+%26+co");
+    }
+
+    #[test]
+    fn album_lookups_include_the_artist() {
+        let (url, requests) = stub(|_| vec![json(serde_json::json!({"results": [{"collectionId": 7}]}))]);
+        let client = ITunesClient::with_base_url(url.join("/api/").unwrap());
+        assert_eq!(client.album_id("東京", Some("Sakanaction")), Some(7));
+        assert_eq!(requests.recv().unwrap(), "/api/search?term=%E6%9D%B1%E4%BA%AC+Sakanaction&entity=album&limit=1");
+    }
+
+    #[test]
+    fn artwork_is_downloaded_at_300_pixels() {
+        // The image is served by the same stub as the search.
+        let (url, requests) = stub(|url| {
+            let artwork = url.join("/image/100x100bb.jpg").unwrap();
+            vec![json(serde_json::json!({"results": [{"artworkUrl100": artwork.as_str()}]})), Some(b"jpeg".to_vec())]
+        });
+        let client = ITunesClient::with_base_url(url);
+        assert_eq!(client.album_artwork("Artist", "Album"), Some(b"jpeg".to_vec()));
+        assert_eq!(requests.recv().unwrap(), "/search?term=Artist+Album&entity=album&limit=5");
+        assert_eq!(requests.recv().unwrap(), "/image/300x300bb.jpg");
+    }
+
+    #[test]
+    fn nothing_found_is_none() {
+        let empty = || json(serde_json::json!({"resultCount": 0, "results": []}));
+        let (url, _requests) = stub(|_| vec![empty(), empty(), Some(b"<html>Bad Gateway</html>".to_vec())]);
+        let client = ITunesClient::with_base_url(url);
+        assert_eq!(client.artist_id("Nobody"), None);
+        assert_eq!(client.album_artwork("Nobody", "Nothing"), None);
+        assert_eq!(client.album_id("Nothing", None), None);
+    }
+
+    #[test]
+    fn a_timeout_is_none() {
+        let (url, _requests) = stub(|_| vec![None]);
+        let client = ITunesClient::with_timeout(url, Duration::from_millis(200));
+        let started = std::time::Instant::now();
+        assert_eq!(client.artist_id("Slow"), None);
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+}
@@ -1,354 +1,115 @@
+pub mod actions;
+mod artwork_cache;
+mod checklist;
+mod clock;
+mod data_bus;
+mod deep_link;
+mod focus_music;
+mod idle;
+mod itunes;
+mod listening_history;
+pub mod local_api;
+mod models;
+mod music;
+mod notification_log;
+mod pomodoro;
+mod recurrence;
+mod reminders;
+mod rollover;
+mod schedule;
+mod snooze;
+mod stand;
+mod stand_history;
+pub mod storage;
+mod suppression;
+mod system_events;
+mod task_reminders;
+mod time_tracking;
+mod timers;
+mod tz;
+
+pub use actions::Request;
+pub use models::*;
+pub use music::NowPlayingInfo;
+
+use artwork_cache::ArtworkCache;
+
 use tauri::{
     AppHandle,
     Emitter,
     Manager,
+    State,
     tray::TrayIconBuilder,
     menu::{Menu, MenuItem},
 };
 
-
-use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 use std::process::Command;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use std::thread;
-use std::time::Duration;
-use chrono::{Local, Timelike, Datelike, Weekday};
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::{Duration, Instant};
+use chrono_tz::Tz;
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use uuid::Uuid;
-
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-pub struct NowPlayingInfo {
-    pub is_playing: bool,
-    pub title: Option<String>,
-    pub artist: Option<String>,
-    pub album: Option<String>,
-    pub album_year: Option<i32>,
-    pub duration: Option<f64>,
-    pub position: Option<f64>,
-    pub artwork: Option<String>,
-}
-
-#[cfg(target_os = "macos")]
-fn query_apple_music() -> NowPlayingInfo {
-    let script = r#"
-        tell application "System Events"
-            if not (exists process "Music") then
-                return "NOT_RUNNING"
-            end if
-        end tell
-        tell application "Music"
-            set currentState to player state
-            if currentState is stopped then
-                return "NOT_RUNNING"
-            end if
-            set isPlaying to (currentState is playing)
-            set trackName to name of current track
-            set trackArtist to artist of current track
-            set trackAlbum to album of current track
-            set trackDuration to duration of current track
-            set trackPosition to player position
-            try
-                set trackYear to year of current track
-            on error
-                set trackYear to 0
-            end try
-            set artworkData to ""
-            try
-                if (count of artworks of current track) > 0 then
-                    set rawData to raw data of artwork 1 of current track
-                    set artworkData to my encodeBase64(rawData)
-                end if
-            end try
-            return (isPlaying as string) & "|||" & trackName & "|||" & trackArtist & "|||" & trackAlbum & "|||" & trackYear & "|||" & trackDuration & "|||" & trackPosition & "|||" & artworkData
-        end tell
-
-        on encodeBase64(theData)
-            set tempFile to (path to temporary items folder as text) & "growing_artwork.tmp"
-            try
-                set fileRef to open for access file tempFile with write permission
-                set eof fileRef to 0
-                write theData to fileRef
-                close access fileRef
-                set base64Result to do shell script "base64 -i " & quoted form of POSIX path of tempFile
-                do shell script "rm -f " & quoted form of POSIX path of tempFile
-                return base64Result
-            on error
-                try
-                    close access file tempFile
-                end try
-                return ""
-            end try
-        end encodeBase64
-    "#;
-
-    let output = Command::new("osascript")
-        .arg("-e")
-        .arg(script)
-        .output();
-
-    match output {
-        Ok(out) => {
-            let result = String::from_utf8_lossy(&out.stdout).trim().to_string();
-            if result == "NOT_RUNNING" || result.is_empty() {
-                return NowPlayingInfo::default();
-            }
-            let parts: Vec<&str> = result.split("|||").collect();
-            if parts.len() >= 7 {
-                let is_playing = parts[0] == "true";
-                let local_artwork = if parts.len() > 7 && !parts[7].is_empty() {
-                    Some(parts[7].replace("\n", "").replace(" ", ""))
-                } else {
-                    None
-                };
-
-                let artist = parts[2].to_string();
-                let album = parts[3].to_string();
-
-                let artwork = local_artwork.or_else(|| {
-                    fetch_itunes_artwork(&artist, &album)
-                });
-
-                NowPlayingInfo {
-                    is_playing,
-                    title: Some(parts[1].to_string()),
-                    artist: Some(artist),
-                    album: Some(album),
-                    album_year: parts[4].parse().ok().filter(|&y| y > 0),
-                    duration: parts[5].parse().ok(),
-                    position: parts[6].parse().ok(),
-                    artwork,
-                }
-            } else {
-                NowPlayingInfo::default()
-            }
-        }
-        Err(_) => NowPlayingInfo::default(),
-    }
-}
-
-#[cfg(target_os = "macos")]
-fn fetch_itunes_artwork(artist: &str, album: &str) -> Option<String> {
-    use std::collections::HashMap;
-    use std::sync::Mutex;
-
-    lazy_static::lazy_static! {
-        static ref ARTWORK_CACHE: Mutex<HashMap<String, Option<String>>> = Mutex::new(HashMap::new());
-    }
-
-    let cache_key = format!("{}|{}", artist, album);
-
-    if let Ok(cache) = ARTWORK_CACHE.lock() {
-        if let Some(cached) = cache.get(&cache_key) {
-            return cached.clone();
-        }
-    }
-
-    let search_term = format!("{} {}", artist, album)
-        .replace(" ", "+")
-        .replace("&", "and");
-
-    let url = format!(
-        "https://itunes.apple.com/search?term={}&entity=album&limit=5",
-        search_term
-    );
-
-    let output = Command::new("curl")
-        .arg("-s")
-        .arg("-m")
-        .arg("3")
-        .arg(&url)
-        .output()
-        .ok()?;
-
-    let json_str = String::from_utf8_lossy(&output.stdout);
-
-    let artwork_url = extract_artwork_url(&json_str, album)?;
-    let high_res_url = artwork_url.replace("100x100bb", "300x300bb");
-
-    let img_output = Command::new("curl")
-        .arg("-s")
-        .arg("-m")
-        .arg("3")
-        .arg(&high_res_url)
-        .output()
-        .ok()?;
-
-    if img_output.stdout.is_empty() {
-        return None;
-    }
-
-    use base64::{Engine as _, engine::general_purpose};
-    let base64_img = general_purpose::STANDARD.encode(&img_output.stdout);
-
-    if let Ok(mut cache) = ARTWORK_CACHE.lock() {
-        cache.insert(cache_key, Some(base64_img.clone()));
-    }
-
-    Some(base64_img)
-}
-
-#[cfg(target_os = "macos")]
-fn extract_artwork_url(json: &str, _album: &str) -> Option<String> {
-    let parsed: serde_json::Value = serde_json::from_str(json).ok()?;
-    let results = parsed.get("results")?.as_array()?;
-
-    for result in results {
-        if let Some(url) = result.get("artworkUrl100").and_then(|v| v.as_str()) {
-            return Some(url.to_string());
-        }
-    }
-
-    None
-}
-
-#[cfg(not(target_os = "macos"))]
-fn query_apple_music() -> NowPlayingInfo {
-    NowPlayingInfo::default()
-}
+use clock::SystemClock;
+use data_bus::DataBus;
+use music::MusicProvider;
+use focus_music::FocusMusic;
+use timers::{TimerEvent, TimerService};
+use pomodoro::{PhaseChange, PomodoroEngine};
+use time_tracking::{EntryEdit, ReportPeriod, TimeEntry, TimeTracker};
+use listening_history::{ListeningHistory, SessionPlaylist, TrackStats};
+use reminders::{Firing, ReminderScheduler};
+use rollover::DayRollover;
+use notification_log::{DeliveryStatus, NotificationLog, NotificationRecord};
+use suppression::{Alert, Decision, DeferredReminder, DeferredReminders};
+use task_reminders::{ItemKind, TaskReminder, TaskReminderScheduler};
+use snooze::{AlertRef, Snooze, Snoozes};
+use stand::{StandAction, StandCycle};
+use system_events::SystemEvent;
+use stand_history::{StandEvent, StandEventKind, StandHistory, StandStats};
 
 #[tauri::command]
-fn get_now_playing() -> NowPlayingInfo {
-    query_apple_music()
+fn get_now_playing(music: State<'_, Arc<dyn MusicProvider>>) -> NowPlayingInfo {
+    music.now_playing()
 }
 
 #[tauri::command]
-fn open_apple_music() {
-    #[cfg(target_os = "macos")]
-    {
-        let _ = Command::new("open")
-            .arg("-a")
-            .arg("Music")
-            .spawn();
-    }
+fn open_apple_music(music: State<'_, Arc<dyn MusicProvider>>) {
+    music.open_player();
 }
 
 #[tauri::command]
-fn play_pause_music() {
-    #[cfg(target_os = "macos")]
-    {
-        let script = r#"
-            tell application "Music"
-                playpause
-            end tell
-        "#;
-        let _ = Command::new("osascript")
-            .arg("-e")
-            .arg(script)
-            .output();
-    }
+fn play_pause_music(music: State<'_, Arc<dyn MusicProvider>>) {
+    music.play_pause();
 }
 
 #[tauri::command]
-fn next_track() {
-    #[cfg(target_os = "macos")]
-    {
-        let script = r#"
-            tell application "Music"
-                next track
-            end tell
-        "#;
-        let _ = Command::new("osascript")
-            .arg("-e")
-            .arg(script)
-            .output();
-    }
+fn next_track(music: State<'_, Arc<dyn MusicProvider>>) {
+    music.next_track();
 }
 
 #[tauri::command]
-fn previous_track() {
-    #[cfg(target_os = "macos")]
-    {
-        let script = r#"
-            tell application "Music"
-                previous track
-            end tell
-        "#;
-        let _ = Command::new("osascript")
-            .arg("-e")
-            .arg(script)
-            .output();
-    }
+fn previous_track(music: State<'_, Arc<dyn MusicProvider>>) {
+    music.previous_track();
 }
 
 #[tauri::command]
-fn open_artist_in_music(artist: String) {
-    #[cfg(target_os = "macos")]
-    {
-        let encoded_artist = artist.replace(" ", "+").replace("&", "%26");
-        let search_url = format!(
-            "https://itunes.apple.com/search?term={}&entity=musicArtist&limit=1",
-            encoded_artist
-        );
-
-        if let Ok(output) = Command::new("curl")
-            .arg("-s")
-            .arg(&search_url)
-            .output()
-        {
-            if let Ok(json_str) = String::from_utf8(output.stdout) {
-                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&json_str) {
-                    if let Some(artist_id) = json["results"]
-                        .get(0)
-                        .and_then(|r| r["artistId"].as_u64())
-                    {
-                        let url = format!("music://music.apple.com/us/artist/{}", artist_id);
-                        let _ = Command::new("open").arg(&url).output();
-                        return;
-                    }
-                }
-            }
-        }
-
-        let fallback_url = format!("itmss://music.apple.com/search?term={}", encoded_artist);
-        let _ = Command::new("open").arg(&fallback_url).output();
-    }
+fn open_artist_in_music(music: State<'_, Arc<dyn MusicProvider>>, artist: String) {
+    music.open_artist(&artist);
 }
 
 #[tauri::command]
-fn open_album_in_music(album: String, artist: Option<String>) {
-    #[cfg(target_os = "macos")]
-    {
-        let search_term = if let Some(ref art) = artist {
-            format!("{} {}", album, art)
-        } else {
-            album.clone()
-        };
-        let encoded = search_term.replace(" ", "+").replace("&", "%26");
-        let search_url = format!(
-            "https://itunes.apple.com/search?term={}&entity=album&limit=1",
-            encoded
-        );
-
-        if let Ok(output) = Command::new("curl")
-            .arg("-s")
-            .arg(&search_url)
-            .output()
-        {
-            if let Ok(json_str) = String::from_utf8(output.stdout) {
-                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&json_str) {
-                    if let Some(collection_id) = json["results"]
-                        .get(0)
-                        .and_then(|r| r["collectionId"].as_u64())
-                    {
-                        let url = format!("music://music.apple.com/us/album/{}", collection_id);
-                        let _ = Command::new("open").arg(&url).output();
-                        return;
-                    }
-                }
-            }
-        }
-
-        let fallback_url = format!("itmss://music.apple.com/search?term={}", encoded);
-        let _ = Command::new("open").arg(&fallback_url).output();
-    }
+fn open_album_in_music(music: State<'_, Arc<dyn MusicProvider>>, album: String, artist: Option<String>) {
+    music.open_album(&album, artist.as_deref());
 }
 
 fn get_data_dir(app: &AppHandle) -> PathBuf {
     #[cfg(debug_assertions)]
     {
-        let dev_dir = std::env::current_dir().unwrap_or_default().join("dev-data");
+        let dev_dir = storage::dev_data_dir();
         if !dev_dir.exists() {
             fs::create_dir_all(&dev_dir).ok();
         }
@@ -361,7 +122,7 @@ fn get_data_dir(app: &AppHandle) -> PathBuf {
 }
 
 fn get_data_path(app: &AppHandle) -> PathBuf {
-    get_data_dir(app).join("data.json")
+    get_data_dir(app).join(storage::DATA_FILE)
 }
 
 fn get_images_path(app: &AppHandle) -> PathBuf {
@@ -384,7 +145,7 @@ fn create_daily_backup(app: &AppHandle) -> Result<(), String> {
             .map_err(|e| format!("Failed to create backups dir: {}", e))?;
     }
 
-    let today = Local::now().format("%Y-%m-%d").to_string();
+    let today = tz::now().format("%Y-%m-%d").to_string();
     let backup_filename = format!("data.backup.{}.json", today);
     let backup_path = backups_dir.join(&backup_filename);
 
@@ -458,231 +219,6 @@ fn try_load_from_backup(app: &AppHandle) -> Option<AppData> {
     None
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-pub struct PlanItem {
-    pub id: String,
-    pub text: String,
-    pub completed: bool,
-    #[serde(default)]
-    pub notes: String,
-    #[serde(default, rename = "targetExamples", skip_serializing_if = "Option::is_none")]
-    pub target_examples: Option<u32>,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-pub struct Section {
-    pub id: String,
-    pub title: String,
-    pub period: String,
-    pub items: Vec<PlanItem>,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-pub struct NotificationSettings {
-    pub enabled: bool,
-    pub daily_reminder: bool,
-    pub daily_time: String,
-    pub weekly_reminder: bool,
-    pub weekly_day: String,
-    pub weekly_time: String,
-    #[serde(default)]
-    pub stand_reminder_enabled: bool,
-    #[serde(default = "default_sit_duration")]
-    pub sit_duration_minutes: u32,
-    #[serde(default = "default_stand_duration")]
-    pub stand_duration_minutes: u32,
-    #[serde(default)]
-    pub stand_mode: String,  // "sitting" or "standing"
-    #[serde(default)]
-    pub stand_mode_changed_at: Option<String>,  // ISO timestamp
-}
-
-fn default_sit_duration() -> u32 { 45 }
-fn default_stand_duration() -> u32 { 10 }
-
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-pub struct QuickNote {
-    pub id: String,
-    pub text: String,
-    pub timestamp: String,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-pub struct BragDocEntry {
-    pub id: String,
-    pub title: String,
-    pub text: String,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub links: Option<Vec<String>>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub images: Option<Vec<String>>,
-    pub timestamp: String,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-pub struct DailyTask {
-    pub id: String,
-    pub text: String,
-    #[serde(default)]
-    pub description: String,
-    #[serde(default, rename = "goalId", skip_serializing_if = "Option::is_none")]
-    pub goal_id: Option<String>,
-    pub completed: bool,
-    #[serde(default, rename = "completedAt", skip_serializing_if = "Option::is_none")]
-    pub completed_at: Option<String>,
-    pub date: String,
-    #[serde(default, rename = "movedToDate", skip_serializing_if = "Option::is_none")]
-    pub moved_to_date: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub order: Option<i32>,
-    #[serde(default, rename = "isFrog", skip_serializing_if = "Option::is_none")]
-    pub is_frog: Option<bool>,
-    #[serde(default = "default_category")]
-    pub category: String,
-    #[serde(default, rename = "timeboxMinutes", skip_serializing_if = "Option::is_none")]
-    pub timebox_minutes: Option<i32>,
-}
-
-fn default_category() -> String {
-    "work".to_string()
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-pub struct ActiveTimer {
-    pub id: String,
-    #[serde(rename = "type")]
-    pub timer_type: String,
-    #[serde(default, rename = "taskId", skip_serializing_if = "Option::is_none")]
-    pub task_id: Option<String>,
-    #[serde(default, rename = "taskName", skip_serializing_if = "Option::is_none")]
-    pub task_name: Option<String>,
-    #[serde(rename = "endTime")]
-    pub end_time: String,
-    #[serde(rename = "durationMinutes")]
-    pub duration_minutes: i32,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-pub struct Todo {
-    pub id: String,
-    pub text: String,
-    #[serde(default)]
-    pub description: String,
-    #[serde(default, rename = "goalId", skip_serializing_if = "Option::is_none")]
-    pub goal_id: Option<String>,
-    #[serde(default, rename = "lastScheduledDate", skip_serializing_if = "Option::is_none")]
-    pub last_scheduled_date: Option<String>,
-    #[serde(default, rename = "createdAt")]
-    pub created_at: String,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-pub struct FeatureRequest {
-    pub id: String,
-    pub text: String,
-    #[serde(default, rename = "createdAt")]
-    pub created_at: String,
-    #[serde(default)]
-    pub completed: bool,
-    #[serde(default, rename = "completedAt", skip_serializing_if = "Option::is_none")]
-    pub completed_at: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-pub struct BugReport {
-    pub id: String,
-    pub text: String,
-    #[serde(default, rename = "createdAt")]
-    pub created_at: String,
-    #[serde(default)]
-    pub completed: bool,
-    #[serde(default, rename = "completedAt", skip_serializing_if = "Option::is_none")]
-    pub completed_at: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-pub struct Curiosity {
-    pub id: String,
-    pub title: String,
-    #[serde(default)]
-    pub description: String,
-    #[serde(default)]
-    pub completed: bool,
-    #[serde(default, rename = "completedAt", skip_serializing_if = "Option::is_none")]
-    pub completed_at: Option<String>,
-    #[serde(default, rename = "createdAt")]
-    pub created_at: String,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-pub struct Review {
-    pub id: String,
-    #[serde(rename = "prLink")]
-    pub pr_link: String,
-    pub title: String,
-    #[serde(default = "default_github_source")]
-    pub source: String,
-    #[serde(default)]
-    pub completed: bool,
-    #[serde(default, rename = "completedAt", skip_serializing_if = "Option::is_none")]
-    pub completed_at: Option<String>,
-    #[serde(default, rename = "createdAt")]
-    pub created_at: String,
-    pub date: String,
-}
-
-fn default_github_source() -> String {
-    "github".to_string()
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-pub struct AppData {
-    pub sections: Vec<Section>,
-    pub notifications: NotificationSettings,
-    #[serde(default, rename = "quickNotes")]
-    pub quick_notes: Vec<QuickNote>,
-    #[serde(default, rename = "bragDocs")]
-    pub brag_docs: Vec<BragDocEntry>,
-    #[serde(default, rename = "dailyTasks")]
-    pub daily_tasks: Vec<DailyTask>,
-    #[serde(default)]
-    pub todos: Vec<Todo>,
-    #[serde(default, rename = "featureRequests")]
-    pub feature_requests: Vec<FeatureRequest>,
-    #[serde(default, rename = "bugReports")]
-    pub bug_reports: Vec<BugReport>,
-    #[serde(default)]
-    pub curiosities: Vec<Curiosity>,
-    #[serde(default)]
-    pub reviews: Vec<Review>,
-    #[serde(default = "default_theme")]
-    pub theme: String,
-    #[serde(default, rename = "darkMode")]
-    pub dark_mode: bool,
-    #[serde(default, rename = "userName")]
-    pub user_name: Option<String>,
-    #[serde(default, rename = "onboardingComplete")]
-    pub onboarding_complete: bool,
-    #[serde(default = "default_true", rename = "appleMusicEnabled")]
-    pub apple_music_enabled: bool,
-    #[serde(default = "default_true", rename = "frogEnabled")]
-    pub frog_enabled: bool,
-    #[serde(default, rename = "atcDays", skip_serializing_if = "Vec::is_empty")]
-    pub atc_days: Vec<String>,
-    #[serde(default, rename = "activeTimer", skip_serializing_if = "Option::is_none")]
-    pub active_timer: Option<ActiveTimer>,
-    #[serde(default, rename = "activeTimers", skip_serializing_if = "Vec::is_empty")]
-    pub active_timers: Vec<ActiveTimer>,
-}
-
-fn default_theme() -> String {
-    "editorial".to_string()
-}
-
-fn default_true() -> bool {
-    true
-}
-
 #[tauri::command]
 fn load_data(app: AppHandle) -> Result<AppData, String> {
     let path = get_data_path(&app);
@@ -719,18 +255,14 @@ fn load_data(app: AppHandle) -> Result<AppData, String> {
 
 #[tauri::command]
 fn save_data(app: AppHandle, data: AppData) -> Result<(), String> {
-    let data_dir = get_data_dir(&app);
-    if !data_dir.exists() {
-        fs::create_dir_all(&data_dir)
-            .map_err(|e| format!("Failed to create data dir: {}", e))?;
+    if let Some(name) = data.timezone.as_deref().filter(|name| !name.is_empty()) {
+        tz::parse(name)?;
+    }
+    let previous = current_data(&app).ok();
+    save_data_internal(&app, &data)?;
+    if let Some(previous) = previous {
+        record_manual_stand_change(&app, &previous.notifications, &data.notifications);
     }
-
-    let content = serde_json::to_string_pretty(&data)
-        .map_err(|e| format!("Failed to serialize data: {}", e))?;
-
-    fs::write(get_data_path(&app), content)
-        .map_err(|e| format!("Failed to write data: {}", e))?;
-
     Ok(())
 }
 
@@ -821,337 +353,1237 @@ fn request_notification_permission(app: AppHandle) -> Result<String, String> {
 
 #[tauri::command]
 fn send_notification(title: String, body: String, app: AppHandle) -> Result<String, String> {
-    use tauri_plugin_notification::{NotificationExt, PermissionState};
-
-    let permission = app.notification().permission_state()
-        .map_err(|e| format!("Failed to check permission: {}", e))?;
-
-    if permission != PermissionState::Granted {
-        return Ok(format!("Permission not granted (state: {:?}). Please enable notifications for this app in System Settings > Notifications.", permission));
+    if !log_notification(&app, NotificationRecord::new("app", &title, &body), None)? {
+        return Ok("Permission not granted. Please enable notifications for this app in System Settings > Notifications.".to_string());
     }
 
-    app.notification()
-        .builder()
-        .title(&title)
-        .body(&body)
-        .sound("Ping")
-        .show()
-        .map_err(|e| format!("Failed to send notification: {}", e))?;
-
     Ok("sent".to_string())
 }
 
-#[derive(Default)]
-struct LastNotification {
-    daily_date: Option<String>,
-    weekly_date: Option<String>,
+fn start_reminder_scheduler(app: &tauri::App, data: Option<&AppData>) {
+    let fire_app = app.handle().clone();
+    let scheduler = ReminderScheduler::start(
+        Arc::new(SystemClock),
+        data.map(|d| d.notifications.clone()),
+        move |firing| on_reminder_fired(&fire_app, firing),
+    );
+    let updates = scheduler.clone();
+    app.state::<DataBus>().listen(move |data| updates.update(data.notifications.clone()));
+    app.manage(scheduler);
 }
 
-fn parse_time(time_str: &str) -> Option<(u32, u32)> {
-    let parts: Vec<&str> = time_str.split(':').collect();
-    if parts.len() == 2 {
-        let hour = parts[0].parse().ok()?;
-        let minute = parts[1].parse().ok()?;
-        Some((hour, minute))
-    } else {
-        None
-    }
+fn start_focus_music(app: &tauri::App, music: Arc<dyn MusicProvider>, data: Option<&AppData>) {
+    let focus_music = FocusMusic::start(music, data.map(|d| d.focus_music.clone()).unwrap_or_default());
+    let updates = focus_music.clone();
+    app.state::<DataBus>().listen(move |data| updates.update(data.focus_music.clone()));
+    app.manage(focus_music);
 }
 
-fn day_matches(day_str: &str, weekday: Weekday) -> bool {
-    match day_str.to_lowercase().as_str() {
-        "monday" => weekday == Weekday::Mon,
-        "tuesday" => weekday == Weekday::Tue,
-        "wednesday" => weekday == Weekday::Wed,
-        "thursday" => weekday == Weekday::Thu,
-        "friday" => weekday == Weekday::Fri,
-        "saturday" => weekday == Weekday::Sat,
-        "sunday" => weekday == Weekday::Sun,
-        _ => false,
-    }
+fn start_task_reminders(app: &tauri::App, data: Option<&AppData>) {
+    let fire_app = app.handle().clone();
+    let scheduler = TaskReminderScheduler::start(
+        Arc::new(SystemClock),
+        data,
+        move |reminder, late| on_task_reminder(&fire_app, reminder, late),
+    );
+    let updates = scheduler.clone();
+    app.state::<DataBus>().listen(move |data| updates.update(data.clone()));
+    app.manage(scheduler);
 }
 
-fn start_notification_scheduler(app_handle: AppHandle) {
-    let last_sent = Arc::new(Mutex::new(LastNotification::default()));
+fn start_day_rollover(app: &tauri::App) {
+    let roll_app = app.handle().clone();
+    let rollover = DayRollover::start(Arc::new(SystemClock), move |today| on_day_rollover(&roll_app, today));
+    app.manage(rollover);
+}
 
-    thread::spawn(move || {
-        loop {
-            thread::sleep(Duration::from_secs(30));
+/// Generates the recurring tasks coming up, applies the carry-forward
+/// policy for the day that just began and tells the webview what moved.
+fn on_day_rollover(app: &AppHandle, today: chrono::NaiveDate) {
+    let mut data = match load_data_internal(app) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("Failed to load data for the day rollover: {}", e);
+            return;
+        }
+    };
+    let generated = recurrence::materialize(&mut data, today);
+    let summary = (data.carry_forward.policy != CarryForwardPolicy::None)
+        .then(|| rollover::carry_forward(&mut data, today))
+        .filter(|summary| !summary.moved.is_empty());
+    if generated == 0 && summary.is_none() {
+        return;
+    }
+    if let Err(e) = save_data_internal(app, &data) {
+        eprintln!("Failed to save the day rollover: {}", e);
+        return;
+    }
+    let _ = app.emit("data-changed", ());
+    if let Some(summary) = summary {
+        eprintln!("Carried {} tasks forward to {}", summary.moved.len(), summary.date);
+        let _ = app.emit("tasks-carried-forward", summary);
+    }
+}
 
-            let data = match load_data_internal(&app_handle) {
-                Ok(d) => d,
-                Err(_) => continue,
-            };
+/// Creates or edits a recurring task. `task_id` turns an existing task into
+/// the first occurrence of a new series.
+#[tauri::command]
+fn save_task_template(app: AppHandle, template: TaskTemplate, task_id: Option<String>) -> Result<TaskTemplate, String> {
+    let mut data = load_data_internal(&app)?;
+    let today = tz::now_in(&data).date_naive();
+    let template = recurrence::save_template(&mut data, template, task_id.as_deref(), today)?;
+    save_data_internal(&app, &data)?;
+    let _ = app.emit("data-changed", ());
+    Ok(template)
+}
 
-            if !data.notifications.enabled {
-                continue;
-            }
+/// Ends a recurring task, deleting its open occurrences after today apart
+/// from `keep_task_id`.
+#[tauri::command]
+fn delete_task_template(app: AppHandle, id: String, keep_task_id: Option<String>) -> Result<(), String> {
+    let mut data = load_data_internal(&app)?;
+    let today = tz::now_in(&data).date_naive();
+    recurrence::delete_template(&mut data, &id, keep_task_id.as_deref(), today)?;
+    save_data_internal(&app, &data)?;
+    let _ = app.emit("data-changed", ());
+    Ok(())
+}
 
-            let now = Local::now();
-            let today = now.format("%Y-%m-%d").to_string();
-            let current_hour = now.hour();
-            let current_minute = now.minute();
-
-            let mut last = last_sent.lock().unwrap();
-
-            if data.notifications.daily_reminder {
-                if let Some((target_hour, target_minute)) = parse_time(&data.notifications.daily_time) {
-                    let should_send = current_hour == target_hour
-                        && current_minute >= target_minute
-                        && current_minute < target_minute + 5
-                        && last.daily_date.as_ref() != Some(&today);
-
-                    if should_send {
-                        let _ = send_notification_internal(
-                            &app_handle,
-                            "Daily Check-in",
-                            "Time to review your growth goals!",
-                        );
-                        last.daily_date = Some(today.clone());
-                    }
+/// Sends a task or todo reminder with "Done" and "Postpone" buttons. Returns
+/// `false` while a timebox holds it back so it is offered again.
+fn on_task_reminder(app: &AppHandle, reminder: TaskReminder, late: bool) -> bool {
+    let log_id = reminder.log_id();
+    let due = reminder.due.with_timezone(&chrono::Utc);
+    if app.try_state::<NotificationLog>().is_some_and(|log| log.has_fired(&log_id, due)) {
+        return true;
+    }
+
+    let title = match reminder.kind {
+        ItemKind::Task => "Task Reminder",
+        ItemKind::Todo => "Todo Reminder",
+    };
+    let body = if late {
+        format!("{} (due at {})", reminder.text, reminder.due.format("%-I:%M %p"))
+    } else {
+        reminder.text.clone()
+    };
+    let record = NotificationRecord::new("task", title, &body).for_occurrence(&log_id, due);
+
+    if let Ok(data) = current_data(app) {
+        match suppression::decide(&data, &current_timers(app, &data), Alert::Reminder { rule_id: &log_id }, tz::now()) {
+            Decision::Allow => {}
+            Decision::Suppress(reason) => {
+                if let Some(log) = app.try_state::<NotificationLog>() {
+                    log.record(record.suppressed(reason));
                 }
+                return true;
             }
+            Decision::Defer => return false,
+        }
+    }
 
-            if data.notifications.weekly_reminder {
-                if let Some((target_hour, target_minute)) = parse_time(&data.notifications.weekly_time) {
-                    let weekday = now.weekday();
-                    let should_send = day_matches(&data.notifications.weekly_day, weekday)
-                        && current_hour == target_hour
-                        && current_minute >= target_minute
-                        && current_minute < target_minute + 5
-                        && last.weekly_date.as_ref() != Some(&today);
-
-                    if should_send {
-                        let _ = send_notification_internal(
-                            &app_handle,
-                            "Weekly Reflection",
-                            "Take a moment to reflect on your week's progress.",
-                        );
-                        last.weekly_date = Some(today.clone());
-                    }
-                }
+    let (kind, id) = (reminder.kind, reminder.id.clone());
+    let status = match show_actionable_notification(
+        app,
+        title,
+        &body,
+        &[("complete", "Done"), ("postpone-15", "In 15 min"), ("postpone-60", "In 1 hour")],
+        move |app, action| {
+            let result = match action {
+                "complete" => complete_task_reminder_internal(app, kind, &id),
+                "postpone-15" => postpone_task_reminder_internal(app, kind, &id, 15).map(|_| ()),
+                "postpone-60" => postpone_task_reminder_internal(app, kind, &id, 60).map(|_| ()),
+                _ => Ok(()),
+            };
+            if let Err(e) = result {
+                eprintln!("Failed to handle task reminder action: {}", e);
             }
+        },
+    ) {
+        Ok(true) => DeliveryStatus::Delivered,
+        Ok(false) => DeliveryStatus::Denied,
+        Err(_) => DeliveryStatus::Failed,
+    };
+    if let Some(log) = app.try_state::<NotificationLog>() {
+        let mut record = record;
+        record.status = status;
+        log.record(record);
+    }
 
-            // Stand reminder check
-            if data.notifications.stand_reminder_enabled {
-                let current_mode = if data.notifications.stand_mode.is_empty() {
-                    "sitting"
-                } else {
-                    &data.notifications.stand_mode
-                };
-
-                let duration_minutes = if current_mode == "sitting" {
-                    data.notifications.sit_duration_minutes
-                } else {
-                    data.notifications.stand_duration_minutes
-                };
-
-                let should_switch = if let Some(ref changed_at) = data.notifications.stand_mode_changed_at {
-                    if let Ok(changed_time) = chrono::DateTime::parse_from_rfc3339(changed_at) {
-                        let elapsed = now.signed_duration_since(changed_time);
-                        elapsed.num_minutes() >= duration_minutes as i64
-                    } else {
-                        true // Invalid timestamp, trigger switch
-                    }
-                } else {
-                    true // No timestamp, start the cycle
-                };
-
-                if should_switch {
-                    let new_mode = if current_mode == "sitting" { "standing" } else { "sitting" };
-                    let (title, body) = if new_mode == "standing" {
-                        ("Time to Stand!", "Take a break and stand up for a bit.")
-                    } else {
-                        ("You Can Sit Now", "Good job standing! You can sit down now.")
-                    };
-
-                    // Send notification
-                    let _ = send_notification_internal(&app_handle, title, body);
-
-                    // Emit event to frontend for overlay
-                    let _ = app_handle.emit("alert-triggered", serde_json::json!({
-                        "type": "stand",
-                        "title": title,
-                        "body": body,
-                        "mode": new_mode
-                    }));
-
-                    // Show and focus window
-                    if let Some(window) = app_handle.get_webview_window("main") {
-                        let _ = window.show();
-                        let _ = window.set_focus();
-                    }
+    let _ = app.emit("task-reminder", serde_json::json!({ "reminder": reminder, "late": late }));
+    true
+}
+
+#[tauri::command]
+fn get_task_reminders(app: AppHandle) -> Result<Vec<TaskReminder>, String> {
+    Ok(task_reminders::pending(&load_data_internal(&app)?, &tz::zone()))
+}
+
+#[tauri::command]
+fn complete_task_reminder(app: AppHandle, kind: ItemKind, id: String) -> Result<(), String> {
+    complete_task_reminder_internal(&app, kind, &id)
+}
+
+fn complete_task_reminder_internal(app: &AppHandle, kind: ItemKind, id: &str) -> Result<(), String> {
+    let mut data = load_data_internal(app)?;
+    task_reminders::complete(&mut data, kind, id)?;
+    save_data_internal(app, &data)?;
+    let _ = app.emit("data-changed", ());
+    Ok(())
+}
+
+/// Moves a task or todo reminder `minutes` into the future. Returns the new
+/// reminder time.
+#[tauri::command]
+fn postpone_task_reminder(app: AppHandle, kind: ItemKind, id: String, minutes: u32) -> Result<String, String> {
+    postpone_task_reminder_internal(&app, kind, &id, minutes)
+}
+
+fn postpone_task_reminder_internal(app: &AppHandle, kind: ItemKind, id: &str, minutes: u32) -> Result<String, String> {
+    let mut data = load_data_internal(app)?;
+    let remind_at = task_reminders::postpone(&mut data, kind, id, minutes)?;
+    save_data_internal(app, &data)?;
+    let _ = app.emit("data-changed", ());
+    Ok(remind_at)
+}
+
+fn on_reminder_fired(app: &AppHandle, firing: Firing) {
+    match firing {
+        Firing::Due { rule, due } => {
+            let body = rule.body.clone();
+            if gate_reminder(app, &rule, due, &body) && send_reminder_notification(app, &rule, due, &body) {
+                reminder_follow_up(app, &rule, due, false);
+            }
+        }
+        Firing::Late(missed) => {
+            let when = match missed.count {
+                1 => format!("missed at {}", missed.last_due.format("%-I:%M %p")),
+                n => format!("missed {} times, last at {}", n, missed.last_due.format("%-I:%M %p")),
+            };
+            let body = if missed.rule.body.is_empty() {
+                when
+            } else {
+                format!("{} ({})", missed.rule.body, when)
+            };
+            if gate_reminder(app, &missed.rule, missed.last_due, &body)
+                && send_reminder_notification(app, &missed.rule, missed.last_due, &body)
+            {
+                reminder_follow_up(app, &missed.rule, missed.last_due, true);
+            }
+        }
+        Firing::Summary(missed) => {
+            let log = app.try_state::<NotificationLog>();
+            let missed: Vec<_> = missed
+                .into_iter()
+                .filter(|m| !log.as_ref().is_some_and(|log| log.has_fired(&m.rule.id, m.last_due.with_timezone(&chrono::Utc))))
+                .filter(|m| gate_reminder(app, &m.rule, m.last_due, &m.rule.body))
+                .collect();
+            if missed.is_empty() {
+                return;
+            }
 
-                    // Update data with new mode
-                    let mut updated_data = data.clone();
-                    updated_data.notifications.stand_mode = new_mode.to_string();
-                    updated_data.notifications.stand_mode_changed_at = Some(now.to_rfc3339());
-                    let _ = save_data_internal(&app_handle, &updated_data);
+            let titles: Vec<&str> = missed.iter().map(|m| m.rule.title.as_str()).collect();
+            let title = match missed.len() {
+                1 => "1 Missed Reminder".to_string(),
+                n => format!("{} Missed Reminders", n),
+            };
+            let status = match show_notification(app, &title, &titles.join(", ")) {
+                Ok(true) => DeliveryStatus::Delivered,
+                Ok(false) => DeliveryStatus::Denied,
+                Err(_) => DeliveryStatus::Failed,
+            };
+            // One record per rule so each occurrence is de-duplicated on its own.
+            if let Some(log) = &log {
+                for m in &missed {
+                    let mut record = NotificationRecord::new("reminder", &m.rule.title, &title)
+                        .for_occurrence(&m.rule.id, m.last_due.with_timezone(&chrono::Utc));
+                    record.status = status;
+                    log.record(record);
                 }
             }
+            let _ = app.emit("reminders-missed", missed.iter().map(|m| &m.rule).collect::<Vec<_>>());
         }
-    });
+    }
 }
 
-fn load_data_internal(app: &AppHandle) -> Result<AppData, String> {
-    let path = get_data_path(app);
-    if !path.exists() {
-        return Ok(AppData::default());
+/// Applies quiet hours, timebox suppression and the ATC profile to a reminder.
+/// Returns whether it may be sent now; otherwise it has been logged as
+/// suppressed or queued until the timebox ends.
+fn gate_reminder(app: &AppHandle, rule: &ReminderRule, due: chrono::DateTime<Tz>, body: &str) -> bool {
+    let Ok(data) = current_data(app) else {
+        return true;
+    };
+    match suppression::decide(&data, &current_timers(app, &data), Alert::Reminder { rule_id: &rule.id }, tz::now()) {
+        Decision::Allow => true,
+        Decision::Suppress(reason) => {
+            eprintln!("Reminder {} suppressed ({})", rule.id, reason);
+            if let Some(log) = app.try_state::<NotificationLog>() {
+                let record = NotificationRecord::new("reminder", &rule.title, body)
+                    .for_occurrence(&rule.id, due.with_timezone(&chrono::Utc))
+                    .suppressed(reason);
+                log.record_occurrence(record);
+            }
+            false
+        }
+        Decision::Defer => {
+            if let Some(deferred) = app.try_state::<DeferredReminders>() {
+                deferred.push(DeferredReminder { rule: rule.clone(), due, body: body.to_string() });
+            }
+            false
+        }
     }
-    let content = fs::read_to_string(&path)
-        .map_err(|e| format!("Failed to read data: {}", e))?;
-    serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse data: {}", e))
 }
 
-fn save_data_internal(app: &AppHandle, data: &AppData) -> Result<(), String> {
-    let data_dir = get_data_dir(app);
-    if !data_dir.exists() {
-        fs::create_dir_all(&data_dir)
-            .map_err(|e| format!("Failed to create data dir: {}", e))?;
-    }
-    let content = serde_json::to_string_pretty(data)
-        .map_err(|e| format!("Failed to serialize data: {}", e))?;
-    fs::write(get_data_path(app), content)
-        .map_err(|e| format!("Failed to write data: {}", e))?;
-    Ok(())
+/// Sends reminders held back by a timebox once no timebox is running.
+fn flush_deferred_reminders(app: &AppHandle) {
+    let Some(deferred) = app.try_state::<DeferredReminders>() else {
+        return;
+    };
+    let running = app.try_state::<TimerService>().is_some_and(|timers| suppression::timebox_running(&timers.list()));
+    if running {
+        return;
+    }
+    for reminder in deferred.take() {
+        if gate_reminder(app, &reminder.rule, reminder.due, &reminder.body)
+            && send_reminder_notification(app, &reminder.rule, reminder.due, &reminder.body)
+        {
+            reminder_follow_up(app, &reminder.rule, reminder.due, true);
+        }
+    }
 }
 
-fn send_notification_internal(app: &AppHandle, title: &str, body: &str) -> Result<(), String> {
-    use tauri_plugin_notification::{NotificationExt, PermissionState};
+/// Live timers from the timer service, falling back to the saved copy.
+fn current_timers(app: &AppHandle, data: &AppData) -> Vec<ActiveTimer> {
+    app.try_state::<TimerService>()
+        .map(|timers| timers.list())
+        .unwrap_or_else(|| data.active_timers.clone())
+}
 
-    let permission = app.notification().permission_state()
-        .map_err(|e| format!("Failed to check permission: {}", e))?;
+/// Tells the frontend a reminder fired and runs its action. Late reminders
+/// don't pull the window forward on their own.
+fn reminder_follow_up(app: &AppHandle, rule: &ReminderRule, due: chrono::DateTime<Tz>, late: bool) {
+    let _ = app.emit("reminder-fired", serde_json::json!({ "rule": rule, "due": due.to_rfc3339(), "late": late }));
 
-    if permission != PermissionState::Granted {
-        return Ok(());
+    if let Some(ReminderAction::OpenView { view }) = &rule.action {
+        if !late {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }
+        let _ = app.emit("navigate", serde_json::json!({ "view": view }));
     }
-
-    app.notification()
-        .builder()
-        .title(title)
-        .body(body)
-        .sound("Ping")
-        .show()
-        .map_err(|e| format!("Failed to send notification: {}", e))?;
-
-    Ok(())
 }
 
 #[tauri::command]
-fn send_delayed_notification(title: String, body: String, delay_secs: u64, app: AppHandle) {
-    thread::spawn(move || {
-        thread::sleep(Duration::from_secs(delay_secs));
-        let _ = send_notification_internal(&app, &title, &body);
-    });
+fn get_upcoming_reminders(app: AppHandle) -> Result<Vec<reminders::UpcomingReminder>, String> {
+    let data = load_data_internal(&app)?;
+    Ok(reminders::upcoming(&data.notifications, &tz::now()))
 }
 
+/// Validates a rule and lists its next few fire times, for the rule editor.
 #[tauri::command]
-fn show_main_window(app: AppHandle) {
-    if let Some(main) = app.get_webview_window("main") {
-        main.show().unwrap();
-        main.set_focus().unwrap();
+fn preview_reminder(rule: ReminderRule, count: Option<usize>) -> Result<Vec<String>, String> {
+    reminders::validate(&rule)?;
+    let mut times = Vec::new();
+    let mut cursor = tz::now();
+    while times.len() < count.unwrap_or(5).min(50) {
+        match reminders::next_fire(&rule, &cursor)? {
+            Some(next) => {
+                times.push(next.to_rfc3339());
+                cursor = next;
+            }
+            None => break,
+        }
     }
+    Ok(times)
 }
 
-#[cfg(target_os = "macos")]
-fn start_now_playing_listener(app_handle: AppHandle) {
-    use objc::runtime::{Class, Object, Sel};
-    use objc::{msg_send, sel, sel_impl};
-    use std::sync::Once;
+#[tauri::command]
+fn get_timezones() -> tz::Timezones {
+    tz::list()
+}
 
-    type Id = *mut Object;
+/// Ticks the sit/stand cycle every 30 seconds, which also polls for idleness.
+/// The data comes from the bus rather than the data file.
+fn start_stand_reminders(app_handle: AppHandle, data: Option<AppData>) {
+    const TICK: Duration = Duration::from_secs(30);
 
-    static REGISTER_MUSIC_OBSERVER_CLASS: Once = Once::new();
-    static mut MUSIC_APP_HANDLE: Option<AppHandle> = None;
+    let cycle = Arc::new(StandCycle::new(idle::detector()));
+    app_handle.manage(cycle.clone());
+    let updates = app_handle.state::<DataBus>().subscribe();
 
-    unsafe {
-        MUSIC_APP_HANDLE = Some(app_handle);
-    }
+    thread::spawn(move || {
+        let stand_history = app_handle.state::<StandHistory>();
+        let snoozes = app_handle.state::<Snoozes>();
+        let mut latest = data.map(Arc::new);
+        let mut next_tick = Instant::now() + TICK;
+        loop {
+            match updates.recv_timeout(next_tick.saturating_duration_since(Instant::now())) {
+                Ok(data) => {
+                    latest = Some(data);
+                    continue;
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+            next_tick = Instant::now() + TICK;
+            let Some(data) = latest.clone() else {
+                continue;
+            };
 
-    extern "C" fn handle_music_notification(_this: &Object, _sel: Sel, _notification: Id) {
-        unsafe {
-            if let Some(ref app) = MUSIC_APP_HANDLE {
-                let info = query_apple_music();
-                let _ = app.emit("now-playing-changed", &info);
+            let now = tz::now();
+            // Hold the switch (rather than skipping it) so it happens once
+            // quiet hours or the timebox end. A snoozed prompt comes back
+            // through the snooze worker instead.
+            let allowed = suppression::decide(&data, &current_timers(&app_handle, &data), Alert::Stand, now) == Decision::Allow
+                && !snoozes.stand_snoozed();
+
+            match cycle.tick(&data.notifications, now, allowed) {
+                StandAction::None => {}
+                StandAction::Resume { mode, changed_at, away_since } => {
+                    stand_history.record(StandEvent::resumed(&mode, away_since));
+                    let mut updated_data = (*data).clone();
+                    updated_data.notifications.stand_mode = mode;
+                    updated_data.notifications.stand_mode_changed_at = Some(changed_at.to_rfc3339());
+                    if save_data_internal(&app_handle, &updated_data).is_ok() {
+                        let _ = app_handle.emit("data-changed", ());
+                    }
+                }
+                StandAction::Switch { mode: new_mode } => switch_stand_mode(&app_handle, &data, new_mode, now),
             }
         }
-    }
-
-    REGISTER_MUSIC_OBSERVER_CLASS.call_once(|| {
-        let superclass = Class::get("NSObject").unwrap();
-        let mut decl = objc::declare::ClassDecl::new("GrowingMusicObserver", superclass).unwrap();
+    });
+}
 
-        unsafe {
-            decl.add_method(
-                sel!(handleMusicNotification:),
-                handle_music_notification as extern "C" fn(&Object, Sel, Id),
-            );
-        }
+/// Switches to `new_mode` and alerts the user.
+fn switch_stand_mode(app: &AppHandle, data: &AppData, new_mode: &str, now: chrono::DateTime<Tz>) {
+    let (title, body) = if new_mode == "standing" {
+        ("Time to Stand!", "Take a break and stand up for a bit.")
+    } else {
+        ("You Can Sit Now", "Good job standing! You can sit down now.")
+    };
 
-        decl.register();
-    });
+    // Update data with new mode before the frontend reloads it
+    let mut updated_data = data.clone();
+    updated_data.notifications.stand_mode = new_mode.to_string();
+    updated_data.notifications.stand_mode_changed_at = Some(now.to_rfc3339());
+    let _ = save_data_internal(app, &updated_data);
+    if let Some(history) = app.try_state::<StandHistory>() {
+        history.record(StandEvent::new(StandEventKind::Prompt, Some(new_mode)));
+    }
 
-    unsafe {
-        let observer_class = Class::get("GrowingMusicObserver").unwrap();
-        let observer: Id = msg_send![observer_class, new];
+    // Send notification
+    let alert = AlertRef::Stand { mode: new_mode.to_string() };
+    let _ = log_notification(app, NotificationRecord::new("stand", title, body), Some(&alert));
+
+    // Emit event to frontend for overlay
+    let _ = app.emit("alert-triggered", serde_json::json!({
+        "type": "stand",
+        "title": title,
+        "body": body,
+        "mode": new_mode
+    }));
+
+    // Show and focus window
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
 
-        let notification_center_class = Class::get("NSDistributedNotificationCenter").unwrap();
-        let notification_center: Id = msg_send![notification_center_class, defaultCenter];
+fn start_snoozes(app: AppHandle) {
+    let snoozes = app.state::<Snoozes>().inner().clone();
+    snoozes.run(move |alert| on_snooze_ended(&app, alert));
+}
 
-        let nsstring_class = Class::get("NSString").unwrap();
-        let notification_name: Id = msg_send![nsstring_class, stringWithUTF8String: b"com.apple.Music.playerInfo\0".as_ptr()];
+/// Brings back a snoozed alert, subject to the same quiet hours and timebox
+/// rules as when it first fired.
+fn on_snooze_ended(app: &AppHandle, alert: AlertRef) {
+    let Ok(data) = load_data_internal(app) else {
+        return;
+    };
+    let now = tz::now();
+    let kind = match &alert {
+        AlertRef::Stand { .. } => Alert::Stand,
+        AlertRef::Reminder { rule_id, .. } => Alert::Reminder { rule_id },
+    };
+    match suppression::decide(&data, &current_timers(app, &data), kind, now) {
+        Decision::Allow => {}
+        Decision::Suppress(reason) => {
+            eprintln!("Snoozed alert suppressed ({})", reason);
+            if let AlertRef::Reminder { rule_id, title, body } = &alert {
+                if let Some(log) = app.try_state::<NotificationLog>() {
+                    log.record(NotificationRecord::new("reminder", title, body).for_rule(rule_id).suppressed(reason));
+                }
+            }
+            return;
+        }
+        Decision::Defer => {
+            // Try again shortly; the timebox may have ended by then.
+            let _ = app.state::<Snoozes>().snooze(alert, 1);
+            return;
+        }
+    }
 
-        let _: () = msg_send![
-            notification_center,
-            addObserver: observer
-            selector: sel!(handleMusicNotification:)
-            name: notification_name
-            object: std::ptr::null::<Object>()
-        ];
+    match &alert {
+        AlertRef::Stand { mode } => {
+            let settings = &data.notifications;
+            if settings.enabled && settings.stand_reminder_enabled {
+                switch_stand_mode(app, &data, mode, now);
+            }
+        }
+        AlertRef::Reminder { rule_id, title, body } => {
+            // The rule may have been deleted or disabled while snoozed.
+            let Some(rule) = reminders::effective_rules(&data.notifications)
+                .into_iter()
+                .find(|r| &r.id == rule_id && r.enabled)
+            else {
+                return;
+            };
+            let record = NotificationRecord::new("reminder", title, body).for_rule(rule_id);
+            let _ = log_notification(app, record, Some(&alert));
+            reminder_follow_up(app, &rule, now, false);
+        }
+    }
+}
 
-        eprintln!("Apple Music listener registered");
-        let _ = observer;
+
+/// Records stand mode changes made from the settings screen. Only changes
+/// newer than the saved one count, so a stale copy of the data saved by the
+/// webview isn't mistaken for the user switching back.
+fn record_manual_stand_change(app: &AppHandle, previous: &NotificationSettings, current: &NotificationSettings) {
+    let Some(history) = app.try_state::<StandHistory>() else {
+        return;
+    };
+    let active = |s: &NotificationSettings| s.enabled && s.stand_reminder_enabled;
+    if active(previous) && !active(current) {
+        history.record(StandEvent::new(StandEventKind::Stopped, None));
+        return;
+    }
+    if !active(current) {
+        return;
+    }
+
+    let changed_at = |s: &NotificationSettings| {
+        s.stand_mode_changed_at
+            .as_deref()
+            .and_then(storage::parse_time)
+    };
+    let newer = match (changed_at(previous), changed_at(current)) {
+        (Some(before), Some(after)) => after > before,
+        (None, Some(_)) => true,
+        _ => false,
+    };
+    let mode = stand::current_mode(current);
+    if !active(previous) || (newer && mode != stand::current_mode(previous)) {
+        history.record(StandEvent::new(StandEventKind::Manual, Some(mode)));
     }
 }
 
-#[cfg(not(target_os = "macos"))]
-fn start_now_playing_listener(_app_handle: AppHandle) {
-    eprintln!("Apple Music listener not supported on this platform");
+fn load_data_internal(app: &AppHandle) -> Result<AppData, String> {
+    storage::read_app_data(&get_data_path(app))
+}
+
+/// The saved data as the bus last saw it, so code running on every timer
+/// event or reminder doesn't re-read the data file.
+fn current_data(app: &AppHandle) -> Result<Arc<AppData>, String> {
+    match app.try_state::<DataBus>() {
+        Some(bus) => bus.latest_or_load(|| load_data_internal(app)),
+        None => load_data_internal(app).map(Arc::new),
+    }
+}
+
+/// Writes `data`, taking the active timers from the timer service since the
+/// backend owns them; copies coming from the webview may be stale. The write
+/// waits for the data file lock in case the CLI is editing the file. The
+/// saved copy is then handed to the background workers.
+fn save_data_internal(app: &AppHandle, data: &AppData) -> Result<(), String> {
+    // The CLI only holds the lock for a single read and write.
+    const LOCK_WAIT: Duration = Duration::from_secs(2);
+
+    let mut data = data.clone();
+    if let Some(timers) = app.try_state::<TimerService>() {
+        data.active_timers = timers.list();
+        data.active_timer = None;
+    }
+    {
+        let _lock = storage::DataFileLock::acquire_within(&get_data_dir(app), LOCK_WAIT)?;
+        storage::write_app_data(&get_data_path(app), &data)?;
+    }
+    if let Some(bus) = app.try_state::<DataBus>() {
+        bus.publish(data);
+    }
+    Ok(())
+}
+
+fn handle_api_request(app: &AppHandle, request: Request) -> Result<serde_json::Value, String> {
+    if let Request::StartTimer { minutes, task_id, name } = &request {
+        let data = load_data_internal(app)?;
+        let timer = actions::new_timer(&data, *minutes, task_id.as_deref(), name.as_deref())?;
+        let timer = app.state::<TimerService>().add(timer);
+        return serde_json::to_value(timer).map_err(|e| format!("Failed to serialize timer: {}", e));
+    }
+
+    let mut data = load_data_internal(app)?;
+    let outcome = actions::execute(&mut data, &request)?;
+    if outcome.modified {
+        save_data_internal(app, &data)?;
+        let _ = app.emit("data-changed", ());
+    }
+    Ok(outcome.result)
+}
+
+fn handle_deep_link(app: &AppHandle, link: &str) {
+    match deep_link::parse(link).and_then(|request| handle_api_request(app, request)) {
+        Ok(_) => eprintln!("Handled deep link: {}", link),
+        Err(e) => {
+            eprintln!("Rejected deep link {}: {}", link, e);
+            let _ = send_notification_internal(app, "app", "Couldn't open link", &e);
+        }
+    }
+}
+
+fn start_deep_link_handler(app: &tauri::App) {
+    use tauri_plugin_deep_link::DeepLinkExt;
+
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = app.deep_link().register_all() {
+        eprintln!("Failed to register deep link scheme: {}", e);
+    }
+
+    let handle = app.handle().clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            handle_deep_link(&handle, url.as_str());
+        }
+    });
+
+    // Cold start on Linux/Windows: the link arrives as a launch argument.
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        for url in urls {
+            handle_deep_link(app.handle(), url.as_str());
+        }
+    }
+}
+
+fn start_local_api(app_handle: AppHandle) {
+    let data_dir = get_data_dir(&app_handle);
+    let handler_app = app_handle.clone();
+    if let Err(e) = local_api::serve(&data_dir, move |request| handle_api_request(&handler_app, request)) {
+        eprintln!("Failed to start local API: {}", e);
+    }
+}
+
+/// Shows a notification and records it in the notification log. `source`
+/// says which part of the app sent it.
+fn send_notification_internal(app: &AppHandle, source: &str, title: &str, body: &str) -> Result<(), String> {
+    log_notification(app, NotificationRecord::new(source, title, body), None).map(|_| ())
+}
+
+/// Sends one occurrence of a reminder rule unless the log shows it was already
+/// sent, e.g. just before a restart. Returns whether it was sent.
+fn send_reminder_notification(app: &AppHandle, rule: &ReminderRule, due: chrono::DateTime<Tz>, body: &str) -> bool {
+    let due = due.with_timezone(&chrono::Utc);
+    if app.try_state::<NotificationLog>().is_some_and(|log| log.has_fired(&rule.id, due)) {
+        return false;
+    }
+    // A new occurrence replaces an earlier one still snoozed.
+    let alert = AlertRef::Reminder { rule_id: rule.id.clone(), title: rule.title.clone(), body: body.to_string() };
+    if let Some(snoozes) = app.try_state::<Snoozes>() {
+        snoozes.cancel(&alert);
+    }
+    let record = NotificationRecord::new("reminder", &rule.title, body).for_occurrence(&rule.id, due);
+    let _ = log_notification(app, record, Some(&alert));
+    true
+}
+
+/// Returns whether the notification was shown (`false` if not permitted).
+/// Notifications for an `alert` get snooze and acknowledge buttons where the
+/// platform supports them.
+fn log_notification(app: &AppHandle, mut record: NotificationRecord, alert: Option<&AlertRef>) -> Result<bool, String> {
+    let result = match alert {
+        Some(alert) => show_alert_notification(app, &record.title, &record.body, alert),
+        None => show_notification(app, &record.title, &record.body),
+    };
+    match &result {
+        Ok(true) => record.status = DeliveryStatus::Delivered,
+        Ok(false) => record.status = DeliveryStatus::Denied,
+        Err(e) => {
+            record.status = DeliveryStatus::Failed;
+            record.error = Some(e.clone());
+        }
+    }
+    if let Some(log) = app.try_state::<NotificationLog>() {
+        log.record(record);
+    }
+    result
+}
+
+/// Whether the user allows the app to show notifications.
+fn notifications_allowed(app: &AppHandle) -> Result<bool, String> {
+    use tauri_plugin_notification::{NotificationExt, PermissionState};
+
+    let permission = app.notification().permission_state()
+        .map_err(|e| format!("Failed to check permission: {}", e))?;
+    Ok(permission == PermissionState::Granted)
+}
+
+fn show_notification(app: &AppHandle, title: &str, body: &str) -> Result<bool, String> {
+    use tauri_plugin_notification::NotificationExt;
+
+    if !notifications_allowed(app)? {
+        return Ok(false);
+    }
+
+    app.notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .sound("Ping")
+        .show()
+        .map_err(|e| format!("Failed to send notification: {}", e))?;
+
+    Ok(true)
+}
+
+/// Shows a notification with "Snooze" and "Got it" buttons where the
+/// platform supports them.
+fn show_alert_notification(app: &AppHandle, title: &str, body: &str, alert: &AlertRef) -> Result<bool, String> {
+    let alert = alert.clone();
+    show_actionable_notification(
+        app,
+        title,
+        body,
+        &[("snooze-5", "Snooze 5 min"), ("snooze-10", "Snooze 10 min"), ("ack", "Got it")],
+        move |app, action| {
+            let result = match action {
+                "snooze-5" => snooze_alert_internal(app, alert, 5).map(|_| ()),
+                "snooze-10" => snooze_alert_internal(app, alert, 10).map(|_| ()),
+                "ack" => acknowledge_alert_internal(app, &alert),
+                _ => Ok(()),
+            };
+            if let Err(e) = result {
+                eprintln!("Failed to handle notification action: {}", e);
+            }
+        },
+    )
+}
+
+/// Shows a notification with buttons through `notify-send`, which waits for
+/// the user's choice on a background thread and passes the chosen action to
+/// `on_action`. Falls back to a plain notification if `notify-send` is
+/// missing or too old to support actions. Like a plain notification, nothing
+/// is shown when notifications aren't allowed.
+#[cfg(target_os = "linux")]
+fn show_actionable_notification<F>(app: &AppHandle, title: &str, body: &str, actions: &[(&str, &str)], on_action: F) -> Result<bool, String>
+where
+    F: FnOnce(&AppHandle, &str) + Send + 'static,
+{
+    use std::process::Stdio;
+
+    if !notifications_allowed(app)? {
+        return Ok(false);
+    }
+
+    let mut args = vec!["--app-name=Growing".to_string(), "--wait".to_string()];
+    args.extend(actions.iter().map(|(key, label)| format!("--action={}={}", key, label)));
+    args.extend([title.to_string(), body.to_string()]);
+    let child = Command::new("notify-send")
+        .args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+    let Ok(child) = child else {
+        return show_notification(app, title, body);
+    };
+
+    let app = app.clone();
+    let (title, body) = (title.to_string(), body.to_string());
+    thread::spawn(move || {
+        let Ok(output) = child.wait_with_output() else {
+            return;
+        };
+        if !output.status.success() {
+            let _ = show_notification(&app, &title, &body);
+            return;
+        }
+        let action = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !action.is_empty() {
+            on_action(&app, &action);
+        }
+    });
+    Ok(true)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn show_actionable_notification<F>(app: &AppHandle, title: &str, body: &str, _actions: &[(&str, &str)], _on_action: F) -> Result<bool, String>
+where
+    F: FnOnce(&AppHandle, &str) + Send + 'static,
+{
+    show_notification(app, title, body)
+}
+
+/// Snoozes a stand or reminder alert for `minutes`. A snoozed stand prompt
+/// puts the user back in the mode they were in until it comes back.
+#[tauri::command]
+fn snooze_alert(app: AppHandle, alert: AlertRef, minutes: u32) -> Result<Snooze, String> {
+    snooze_alert_internal(&app, alert, minutes)
+}
+
+fn snooze_alert_internal(app: &AppHandle, alert: AlertRef, minutes: u32) -> Result<Snooze, String> {
+    let snooze = app.state::<Snoozes>().snooze(alert, minutes)?;
+    if let AlertRef::Stand { mode } = &snooze.alert {
+        let mut data = load_data_internal(app)?;
+        let previous = if mode == "standing" { "sitting" } else { "standing" };
+        data.notifications.stand_mode = previous.to_string();
+        data.notifications.stand_mode_changed_at = Some(tz::now().to_rfc3339());
+        save_data_internal(app, &data)?;
+        app.state::<StandHistory>().record(StandEvent::new(StandEventKind::Snoozed, Some(previous)));
+        let _ = app.emit("data-changed", ());
+    }
+    Ok(snooze)
+}
+
+/// Called when the user dismisses a stand or reminder alert.
+#[tauri::command]
+fn acknowledge_alert(app: AppHandle, alert: AlertRef) -> Result<(), String> {
+    acknowledge_alert_internal(&app, &alert)
+}
+
+fn acknowledge_alert_internal(app: &AppHandle, alert: &AlertRef) -> Result<(), String> {
+    app.state::<Snoozes>().cancel(alert);
+    match alert {
+        AlertRef::Stand { .. } => {
+            let data = load_data_internal(app)?;
+            let mode = stand::current_mode(&data.notifications).to_string();
+            app.state::<StandHistory>().record(StandEvent::new(StandEventKind::Acknowledged, Some(&mode)));
+        }
+        AlertRef::Reminder { rule_id, .. } => {
+            app.state::<NotificationLog>().acknowledge(rule_id);
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn get_snoozes(snoozes: State<'_, Snoozes>) -> Vec<Snooze> {
+    snoozes.list()
+}
+
+#[tauri::command]
+fn cancel_snooze(snoozes: State<'_, Snoozes>, id: String) -> Result<(), String> {
+    if snoozes.cancel_id(&id) {
+        Ok(())
+    } else {
+        Err(format!("No snooze with id {}", id))
+    }
+}
+
+#[tauri::command]
+fn get_notification_log(
+    log: State<'_, NotificationLog>,
+    source: Option<String>,
+    rule_id: Option<String>,
+    limit: Option<usize>,
+) -> Vec<NotificationRecord> {
+    log.entries(source.as_deref(), rule_id.as_deref(), limit.unwrap_or(100))
+}
+
+#[tauri::command]
+fn get_stand_history(history: State<'_, StandHistory>, from: String, to: String) -> Result<Vec<StandEvent>, String> {
+    let (from, to) = parse_date_range(&from, &to)?;
+    Ok(history.events(from, to))
+}
+
+#[tauri::command]
+fn get_stand_stats(app: AppHandle, history: State<'_, StandHistory>, from: String, to: String) -> Result<StandStats, String> {
+    let (from, to) = parse_date_range(&from, &to)?;
+    let data = load_data_internal(&app)?;
+    Ok(history.stats(&data.notifications, from, to, chrono::Utc::now()))
+}
+
+/// Parses an inclusive `YYYY-MM-DD` range of at most a year.
+fn parse_date_range(from: &str, to: &str) -> Result<(chrono::NaiveDate, chrono::NaiveDate), String> {
+    let parse = |date: &str| {
+        chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| format!("Invalid date (expected YYYY-MM-DD): {}", date))
+    };
+    let (from, to) = (parse(from)?, parse(to)?);
+    if from > to || (to - from).num_days() > 366 {
+        return Err("Date range must run forwards and span at most a year".to_string());
+    }
+    Ok((from, to))
+}
+
+/// Moves Markdown task list lines in descriptions of data from before
+/// checklists into checklists. Runs once; the data records that it did.
+fn migrate_checklists(app: &AppHandle) {
+    let Ok(mut data) = load_data_internal(app) else {
+        return;
+    };
+    let Some(changed) = checklist::migrate_once(&mut data) else {
+        return;
+    };
+    match save_data_internal(app, &data) {
+        Ok(()) if changed > 0 => eprintln!("Moved task list lines of {} tasks and todos into checklists", changed),
+        Ok(()) => {}
+        Err(e) => eprintln!("Failed to save migrated checklists: {}", e),
+    }
+}
+
+fn start_timer_service(app: &tauri::App) {
+    let mut data = load_data_internal(app.handle()).unwrap_or_default();
+    let mut restored = std::mem::take(&mut data.active_timers);
+    if restored.is_empty() {
+        if let Some(mut legacy) = data.active_timer.take() {
+            if legacy.id.is_empty() {
+                legacy.id = Uuid::new_v4().to_string();
+            }
+            restored.push(legacy);
+        }
+    }
+
+    let handle = app.handle().clone();
+    let service = TimerService::new(Arc::new(SystemClock), restored, move |event| on_timer_event(&handle, event));
+    app.manage(service.clone());
+
+    // Must be in place before the first expiry pass so restored pomodoro
+    // phases advance instead of raising a plain timer alert.
+    let data_dir = get_data_dir(app.handle());
+    app.manage(PomodoroEngine::load(data_dir.join(pomodoro::POMODORO_FILE)));
+    app.manage(TimeTracker::load(data_dir.join(time_tracking::TIME_ENTRIES_FILE)));
+
+    service.run();
+}
+
+fn on_timer_event(app: &AppHandle, event: &TimerEvent) {
+    if let Err(e) = current_data(app).and_then(|data| save_data_internal(app, &data)) {
+        eprintln!("Failed to persist timers: {}", e);
+    }
+
+    // Before the pomodoro engine, which cancels expired phase timers and would
+    // otherwise close their entries at "now" instead of the deadline.
+    if let Some(tracker) = app.try_state::<TimeTracker>() {
+        tracker.on_timer_event(event);
+    }
+
+    if let Some(focus_music) = app.try_state::<FocusMusic>() {
+        focus_music.on_timer_event(event, app.state::<TimerService>().list());
+    }
+
+    if let TimerEvent::Started { timer } | TimerEvent::Resumed { timer } = event {
+        if let Some(history) = app.try_state::<ListeningHistory>() {
+            history.on_timer_started(timer);
+        }
+    }
+
+    if event.timer().timer_type == "task" && !matches!(event, TimerEvent::Started { .. } | TimerEvent::Resumed { .. }) {
+        flush_deferred_reminders(app);
+    }
+
+    if event.timer().timer_type == pomodoro::TIMER_TYPE {
+        if let Some(engine) = app.try_state::<PomodoroEngine>() {
+            if let Some(change) = engine.on_timer_event(&app.state::<TimerService>(), event) {
+                on_pomodoro_change(app, &change);
+            }
+        }
+    } else if let TimerEvent::Expired { timer, late } = event {
+        let (title, body) = timer_expired_message(timer, *late);
+        let _ = send_notification_internal(app, "timer", title, &body);
+        let _ = app.emit("timer-expired", timer);
+
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+
+    let _ = app.emit("data-changed", ());
+}
+
+fn timer_expired_message(timer: &ActiveTimer, late: bool) -> (&'static str, String) {
+    let mut body = match (timer.timer_type.as_str(), timer.task_name.as_deref()) {
+        ("task", Some(name)) => format!("Your timebox for \"{}\" has ended.", name),
+        ("task", None) => "Your task timebox has ended.".to_string(),
+        (_, Some(name)) => format!("Your timer \"{}\" has ended.", name),
+        (_, None) => "Your timer has ended.".to_string(),
+    };
+    if late {
+        let ended = timers::end_time(timer).with_timezone(&tz::zone());
+        body.push_str(&format!(" (It ran out at {}.)", ended.format("%H:%M")));
+    }
+    ("Time's Up", body)
+}
+
+fn on_pomodoro_change(app: &AppHandle, change: &PhaseChange) {
+    let message = match change {
+        PhaseChange::Started { session } => {
+            let minutes = session.phase.minutes(&session.settings);
+            match session.phase {
+                pomodoro::Phase::Work => Some((
+                    "Back to Work",
+                    match &session.task_name {
+                        Some(task) => format!("Focus on \"{}\" for {} minutes.", task, minutes),
+                        None => format!("Focus for {} minutes.", minutes),
+                    },
+                )),
+                pomodoro::Phase::ShortBreak => Some(("Short Break", format!("Nice work! Take {} minutes.", minutes))),
+                pomodoro::Phase::LongBreak => Some((
+                    "Long Break",
+                    format!("You finished {} pomodoros. Take {} minutes.", session.completed_work, minutes),
+                )),
+            }
+        }
+        PhaseChange::Waiting { next, .. } => Some((
+            "Pomodoro Done",
+            match next {
+                pomodoro::Phase::Work => "Start your next pomodoro when you're ready.".to_string(),
+                _ => "Start your break when you're ready.".to_string(),
+            },
+        )),
+        PhaseChange::Finished { session } => Some((
+            "Pomodoro Set Complete",
+            format!("You finished {} pomodoros. Great focus!", session.completed_work),
+        )),
+        PhaseChange::Stopped { .. } => None,
+    };
+
+    if let Some((title, body)) = message {
+        let _ = send_notification_internal(app, "pomodoro", title, &body);
+    }
+    if let Err(e) = app.emit("pomodoro-changed", change) {
+        eprintln!("Failed to emit pomodoro change for session {}: {}", change.session().id, e);
+    }
+}
+
+#[tauri::command]
+fn get_pomodoro_status(engine: State<'_, PomodoroEngine>) -> Option<pomodoro::PomodoroSession> {
+    engine.status()
+}
+
+#[tauri::command]
+fn start_pomodoro(
+    app: AppHandle,
+    engine: State<'_, PomodoroEngine>,
+    timers: State<'_, TimerService>,
+    task_id: Option<String>,
+) -> Result<pomodoro::PomodoroSession, String> {
+    let data = load_data_internal(&app)?;
+    let task = match task_id {
+        Some(id) => {
+            let task = &data.daily_tasks[actions::find_task(&data, &id)?];
+            Some((task.id.clone(), task.text.clone()))
+        }
+        None => None,
+    };
+    let session = engine.start(&timers, &data.pomodoro, task)?;
+    let _ = app.emit("pomodoro-changed", PhaseChange::Started { session: session.clone() });
+    Ok(session)
+}
+
+#[tauri::command]
+fn stop_pomodoro(app: AppHandle, engine: State<'_, PomodoroEngine>, timers: State<'_, TimerService>) -> Result<(), String> {
+    let session = engine.stop(&timers)?;
+    on_pomodoro_change(&app, &PhaseChange::Stopped { session });
+    Ok(())
+}
+
+#[tauri::command]
+fn skip_pomodoro_phase(app: AppHandle, engine: State<'_, PomodoroEngine>, timers: State<'_, TimerService>) -> Result<(), String> {
+    let change = engine.skip(&timers)?;
+    on_pomodoro_change(&app, &change);
+    Ok(())
+}
+
+#[tauri::command]
+fn advance_pomodoro(app: AppHandle, engine: State<'_, PomodoroEngine>, timers: State<'_, TimerService>) -> Result<(), String> {
+    let change = engine.advance(&timers)?;
+    on_pomodoro_change(&app, &change);
+    Ok(())
+}
+
+#[tauri::command]
+fn get_focus_stats(engine: State<'_, PomodoroEngine>, from: String, to: String) -> Result<Vec<pomodoro::DailyFocusStats>, String> {
+    let (from, to) = parse_date_range(&from, &to)?;
+    Ok(engine.stats(from, to))
+}
+
+#[tauri::command]
+fn get_time_entries(
+    tracker: State<'_, TimeTracker>,
+    task_id: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<TimeEntry>, String> {
+    let parse = |date: Option<String>| {
+        date.map(|date| {
+            chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .map_err(|_| format!("Invalid date (expected YYYY-MM-DD): {}", date))
+        })
+        .transpose()
+    };
+    Ok(tracker.entries(task_id.as_deref(), parse(from)?, parse(to)?))
+}
+
+#[tauri::command]
+fn get_task_time_totals(tracker: State<'_, TimeTracker>, task_ids: Option<Vec<String>>) -> Vec<time_tracking::TaskTotal> {
+    tracker.totals(task_ids.as_deref())
+}
+
+#[tauri::command]
+fn get_time_report(
+    app: AppHandle,
+    tracker: State<'_, TimeTracker>,
+    period: ReportPeriod,
+) -> Result<time_tracking::TimeReport, String> {
+    let data = load_data_internal(&app)?;
+    tracker.report(&data, &period)
+}
+
+#[tauri::command]
+fn add_time_entry(
+    app: AppHandle,
+    tracker: State<'_, TimeTracker>,
+    task_id: String,
+    start: String,
+    stop: String,
+    note: Option<String>,
+) -> Result<TimeEntry, String> {
+    let data = load_data_internal(&app)?;
+    let task_id = data.daily_tasks[actions::find_task(&data, &task_id)?].id.clone();
+    tracker.add_manual(task_id, &start, &stop, note.as_deref().unwrap_or_default())
+}
+
+#[tauri::command]
+fn update_time_entry(
+    app: AppHandle,
+    tracker: State<'_, TimeTracker>,
+    id: String,
+    mut edit: EntryEdit,
+) -> Result<TimeEntry, String> {
+    if let Some(task_id) = edit.task_id.take() {
+        let data = load_data_internal(&app)?;
+        edit.task_id = Some(data.daily_tasks[actions::find_task(&data, &task_id)?].id.clone());
+    }
+    tracker.edit(&id, edit)
+}
+
+#[tauri::command]
+fn delete_time_entry(tracker: State<'_, TimeTracker>, id: String) -> Result<TimeEntry, String> {
+    tracker.delete(&id)
+}
+
+#[tauri::command]
+fn get_timers(timers: State<'_, TimerService>) -> Vec<ActiveTimer> {
+    timers.list()
+}
+
+#[tauri::command]
+fn start_timer(
+    app: AppHandle,
+    timers: State<'_, TimerService>,
+    minutes: i32,
+    task_id: Option<String>,
+    name: Option<String>,
+) -> Result<ActiveTimer, String> {
+    let data = load_data_internal(&app)?;
+    let timer = actions::new_timer(&data, minutes, task_id.as_deref(), name.as_deref())?;
+    Ok(timers.add(timer))
+}
+
+#[tauri::command]
+fn pause_timer(timers: State<'_, TimerService>, id: String) -> Result<ActiveTimer, String> {
+    timers.pause(&id)
+}
+
+#[tauri::command]
+fn resume_timer(timers: State<'_, TimerService>, id: String) -> Result<ActiveTimer, String> {
+    timers.resume(&id)
+}
+
+#[tauri::command]
+fn extend_timer(timers: State<'_, TimerService>, id: String, minutes: i32) -> Result<ActiveTimer, String> {
+    timers.extend(&id, minutes)
+}
+
+#[tauri::command]
+fn cancel_timer(timers: State<'_, TimerService>, id: Option<String>) -> Result<(), String> {
+    timers.cancel(id.as_deref()).map(|_| ())
+}
+
+#[tauri::command]
+fn show_main_window(app: AppHandle) {
+    if let Some(main) = app.get_webview_window("main") {
+        main.show().unwrap();
+        main.set_focus().unwrap();
+    }
+}
+
+/// Most played tracks between two dates, by listening time; `focus_only`
+/// counts only what played during focus timers.
+#[tauri::command]
+fn get_top_tracks(
+    history: State<'_, ListeningHistory>,
+    from: String,
+    to: String,
+    focus_only: bool,
+    limit: Option<usize>,
+) -> Result<Vec<TrackStats>, String> {
+    let (from, to) = parse_date_range(&from, &to)?;
+    Ok(history.top_tracks(from, to, focus_only, limit.unwrap_or(10)))
+}
+
+#[tauri::command]
+fn get_session_playlists(
+    history: State<'_, ListeningHistory>,
+    from: String,
+    to: String,
+) -> Result<Vec<SessionPlaylist>, String> {
+    let (from, to) = parse_date_range(&from, &to)?;
+    Ok(history.session_playlists(from, to))
+}
+
+fn on_now_playing(app: &AppHandle, info: &NowPlayingInfo) {
+    if let Some(history) = app.try_state::<ListeningHistory>() {
+        let running = app.try_state::<TimerService>().map(|timers| timers.list()).unwrap_or_default();
+        history.on_now_playing(info, &running);
+    }
+    let _ = app.emit("now-playing-changed", info);
 }
 
 #[cfg(target_os = "macos")]
-fn start_wake_listener(app_handle: AppHandle) {
+fn start_now_playing_listener(app_handle: AppHandle) {
     use objc::runtime::{Class, Object, Sel};
     use objc::{msg_send, sel, sel_impl};
     use std::sync::Once;
 
     type Id = *mut Object;
 
-    static REGISTER_OBSERVER_CLASS: Once = Once::new();
-    static mut APP_HANDLE: Option<AppHandle> = None;
+    static REGISTER_MUSIC_OBSERVER_CLASS: Once = Once::new();
+    static mut MUSIC_APP_HANDLE: Option<AppHandle> = None;
 
     unsafe {
-        APP_HANDLE = Some(app_handle);
+        MUSIC_APP_HANDLE = Some(app_handle);
     }
 
-    extern "C" fn handle_wake_notification(_this: &Object, _sel: Sel, _notification: Id) {
-        eprintln!("System wake detected!");
+    extern "C" fn handle_music_notification(_this: &Object, _sel: Sel, _notification: Id) {
         unsafe {
-            if let Some(ref app) = APP_HANDLE {
-                let _ = app.emit("system-wake", ());
+            if let Some(ref app) = MUSIC_APP_HANDLE {
+                let info = app.state::<Arc<dyn MusicProvider>>().now_playing();
+                on_now_playing(app, &info);
             }
         }
     }
 
-    REGISTER_OBSERVER_CLASS.call_once(|| {
+    REGISTER_MUSIC_OBSERVER_CLASS.call_once(|| {
         let superclass = Class::get("NSObject").unwrap();
-        let mut decl = objc::declare::ClassDecl::new("GrowingWakeObserver", superclass).unwrap();
+        let mut decl = objc::declare::ClassDecl::new("GrowingMusicObserver", superclass).unwrap();
 
         unsafe {
             decl.add_method(
-                sel!(handleWakeNotification:),
-                handle_wake_notification as extern "C" fn(&Object, Sel, Id),
+                sel!(handleMusicNotification:),
+                handle_music_notification as extern "C" fn(&Object, Sel, Id),
             );
         }
 
@@ -1159,35 +1591,88 @@ fn start_wake_listener(app_handle: AppHandle) {
     });
 
     unsafe {
-        let observer_class = Class::get("GrowingWakeObserver").unwrap();
+        let observer_class = Class::get("GrowingMusicObserver").unwrap();
         let observer: Id = msg_send![observer_class, new];
 
-        let workspace_class = Class::get("NSWorkspace").unwrap();
-        let workspace: Id = msg_send![workspace_class, sharedWorkspace];
-        let notification_center: Id = msg_send![workspace, notificationCenter];
+        let notification_center_class = Class::get("NSDistributedNotificationCenter").unwrap();
+        let notification_center: Id = msg_send![notification_center_class, defaultCenter];
 
         let nsstring_class = Class::get("NSString").unwrap();
-        let notification_name: Id = msg_send![nsstring_class, stringWithUTF8String: b"NSWorkspaceDidWakeNotification\0".as_ptr()];
+        let notification_name: Id = msg_send![nsstring_class, stringWithUTF8String: b"com.apple.Music.playerInfo\0".as_ptr()];
 
         let _: () = msg_send![
             notification_center,
             addObserver: observer
-            selector: sel!(handleWakeNotification:)
+            selector: sel!(handleMusicNotification:)
             name: notification_name
             object: std::ptr::null::<Object>()
         ];
 
-        eprintln!("Wake listener registered");
-
-        // Keep observer alive for the lifetime of the app
-        // The pointer is intentionally leaked since we want it to persist
+        eprintln!("Apple Music listener registered");
         let _ = observer;
     }
 }
 
+/// Players elsewhere are polled; the frontend hears about track changes and
+/// play/pause, not about the position moving on.
 #[cfg(not(target_os = "macos"))]
-fn start_wake_listener(_app_handle: AppHandle) {
-    eprintln!("Wake listener not supported on this platform");
+fn start_now_playing_listener(app_handle: AppHandle) {
+    const POLL: Duration = Duration::from_secs(5);
+
+    thread::spawn(move || {
+        let music = app_handle.state::<Arc<dyn MusicProvider>>();
+        eprintln!("Polling {} music provider", music.name());
+        let mut watch = music::TrackWatch::new(music.as_ref());
+        loop {
+            thread::sleep(POLL);
+            if let Some(info) = watch.poll(music.as_ref()) {
+                on_now_playing(&app_handle, &info);
+            }
+        }
+    });
+}
+
+fn start_system_events(app_handle: AppHandle) {
+    let source = system_events::source();
+    eprintln!("Listening for sleep, wake and screen lock with {}", source.name());
+    source.start(Arc::new(move |event| on_system_event(&app_handle, event)));
+}
+
+fn on_system_event(app: &AppHandle, event: SystemEvent) {
+    eprintln!("System event: {:?}", event);
+    let stand = app.try_state::<Arc<StandCycle>>();
+    match event {
+        SystemEvent::Wake => {
+            if let Some(timers) = app.try_state::<TimerService>() {
+                timers.wake();
+            }
+            if let Some(reminders) = app.try_state::<ReminderScheduler>() {
+                reminders.wake();
+            }
+            if let Some(reminders) = app.try_state::<TaskReminderScheduler>() {
+                reminders.wake();
+            }
+            if let Some(rollover) = app.try_state::<DayRollover>() {
+                rollover.wake();
+            }
+            if let Some(stand) = stand {
+                stand.wake();
+            }
+            let _ = app.emit("system-wake", ());
+        }
+        SystemEvent::Lock => {
+            if let Some(stand) = stand {
+                stand.screen_locked(tz::now());
+            }
+        }
+        SystemEvent::Unlock => {
+            if let Some(stand) = stand {
+                stand.screen_unlocked();
+            }
+        }
+        SystemEvent::Sleep => {}
+    }
+    let _ = app.emit("system-event", event);
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -1197,10 +1682,18 @@ pub fn run() {
 
     #[cfg(not(debug_assertions))]
     {
-        builder = builder.plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.show();
-                let _ = window.set_focus();
+        builder = builder.plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+            // Linux and Windows launch a second instance for each deep link;
+            // handle it here instead of just raising the window.
+            let links = deep_link::links_in_args(&args);
+            for link in &links {
+                handle_deep_link(app, link);
+            }
+            if links.is_empty() {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
             }
         }));
     }
@@ -1212,6 +1705,22 @@ pub fn run() {
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_autostart::init(tauri_plugin_autostart::MacosLauncher::LaunchAgent, None))
         .plugin(tauri_plugin_window_state::Builder::new().build())
+        .plugin(tauri_plugin_deep_link::init())
+        .register_uri_scheme_protocol("artwork", |ctx, request| {
+            let path = ctx
+                .app_handle()
+                .try_state::<Arc<ArtworkCache>>()
+                .and_then(|cache| cache.path(request.uri().path().trim_start_matches('/')));
+            let image = path.as_ref().and_then(|path| fs::read(path).ok().map(|bytes| (path, bytes)));
+            match image {
+                Some((path, bytes)) => tauri::http::Response::builder()
+                    .header("Content-Type", artwork_cache::content_type(path))
+                    .header("Cache-Control", "max-age=31536000, immutable")
+                    .body(bytes),
+                None => tauri::http::Response::builder().status(404).body(Vec::new()),
+            }
+            .unwrap_or_default()
+        })
         .setup(|app| {
             // Create daily backup on startup
             if let Err(e) = create_daily_backup(&app.handle()) {
@@ -1254,9 +1763,44 @@ pub fn run() {
                 })
                 .build(app)?;
 
-            start_notification_scheduler(app.handle().clone());
-            start_wake_listener(app.handle().clone());
+            app.manage(NotificationLog::load(get_data_dir(app.handle()).join(notification_log::NOTIFICATION_LOG_FILE)));
+            app.manage(StandHistory::load(get_data_dir(app.handle()).join(stand_history::STAND_HISTORY_FILE)));
+            app.manage(Snoozes::load(get_data_dir(app.handle()).join(snooze::SNOOZE_FILE)));
+            app.manage(ListeningHistory::load(get_data_dir(app.handle()).join(listening_history::LISTENING_HISTORY_FILE)));
+            app.manage(DataBus::default());
+            // Registered first so the schedulers see the new zone when they
+            // get the data that changed it.
+            app.state::<DataBus>().listen(|data| {
+                if tz::configure(data.timezone.as_deref()) {
+                    eprintln!("Scheduling in {}", tz::zone());
+                }
+            });
+            start_timer_service(app);
+            app.manage(DeferredReminders::default());
+            migrate_checklists(app.handle());
+            // Read once; the workers get every later save through the bus.
+            let data = match load_data_internal(app.handle()) {
+                Ok(data) => Some(data),
+                Err(e) => {
+                    eprintln!("Failed to load data for the schedulers: {}", e);
+                    None
+                }
+            };
+            tz::configure(data.as_ref().and_then(|data| data.timezone.as_deref()));
+            start_reminder_scheduler(app, data.as_ref());
+            start_task_reminders(app, data.as_ref());
+            start_day_rollover(app);
+            let artwork = Arc::new(ArtworkCache::load(get_data_dir(app.handle()).join(artwork_cache::ARTWORK_DIR)));
+            app.manage(artwork.clone());
+            let music = music::provider(artwork);
+            app.manage(music.clone());
+            start_focus_music(app, music, data.as_ref());
+            start_stand_reminders(app.handle().clone(), data);
+            start_snoozes(app.handle().clone());
+            start_system_events(app.handle().clone());
             start_now_playing_listener(app.handle().clone());
+            start_local_api(app.handle().clone());
+            start_deep_link_handler(app);
 
             Ok(())
         })
@@ -1276,7 +1820,41 @@ pub fn run() {
             get_image,
             delete_image,
             send_notification,
-            send_delayed_notification,
+            get_timers,
+            start_timer,
+            pause_timer,
+            resume_timer,
+            extend_timer,
+            cancel_timer,
+            get_pomodoro_status,
+            start_pomodoro,
+            stop_pomodoro,
+            skip_pomodoro_phase,
+            advance_pomodoro,
+            get_focus_stats,
+            get_upcoming_reminders,
+            get_timezones,
+            get_task_reminders,
+            save_task_template,
+            delete_task_template,
+            complete_task_reminder,
+            postpone_task_reminder,
+            preview_reminder,
+            get_notification_log,
+            snooze_alert,
+            acknowledge_alert,
+            get_snoozes,
+            cancel_snooze,
+            get_stand_history,
+            get_stand_stats,
+            get_time_entries,
+            get_task_time_totals,
+            get_time_report,
+            add_time_entry,
+            update_time_entry,
+            delete_time_entry,
+            get_top_tracks,
+            get_session_playlists,
             check_notification_permission,
             request_notification_permission,
             get_now_playing,
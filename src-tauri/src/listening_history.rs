@@ -0,0 +1,531 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::ActiveTimer;
+use crate::music::NowPlayingInfo;
+use crate::timers::is_focus;
+use crate::storage::{self, format_time, parse_time};
+use crate::tz;
+
+pub const LISTENING_HISTORY_FILE: &str = "listening_history.json";
+
+/// Plays kept at most; the oldest go first.
+const MAX_PLAYS: usize = 5000;
+
+/// Pausing a track and resuming it within this long continues the same play
+/// instead of counting it twice.
+const RESUME_WINDOW: Duration = Duration::minutes(10);
+
+/// A timer that ran while a track played.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TimerLink {
+    #[serde(rename = "timerId")]
+    pub timer_id: String,
+    #[serde(rename = "timerType")]
+    pub timer_type: String,
+    #[serde(default, rename = "taskId", skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    #[serde(default, rename = "taskName", skip_serializing_if = "Option::is_none")]
+    pub task_name: Option<String>,
+    /// The pomodoro session, for phase timers.
+    #[serde(default, rename = "sessionId", skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Whether the timer was for working: a focus timer, a task timebox or a
+    /// pomodoro work phase.
+    pub focus: bool,
+}
+
+impl TimerLink {
+    fn new(timer: &ActiveTimer) -> Self {
+        Self {
+            timer_id: timer.id.clone(),
+            timer_type: timer.timer_type.clone(),
+            task_id: timer.task_id.clone(),
+            task_name: timer.task_name.clone(),
+            session_id: timer.session_id.clone(),
+            focus: is_focus(timer),
+        }
+    }
+
+    /// Timers of one pomodoro session share a playlist.
+    fn session(&self) -> &str {
+        self.session_id.as_deref().unwrap_or(&self.timer_id)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Play {
+    pub id: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artist: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub album: Option<String>,
+    /// Length of the track in seconds, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f64>,
+    pub start: String,
+    /// `None` while the track is playing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub timers: Vec<TimerLink>,
+}
+
+impl Play {
+    fn is_track(&self, info: &NowPlayingInfo) -> bool {
+        info.title.as_deref() == Some(self.title.as_str()) && info.artist == self.artist && info.album == self.album
+    }
+
+    fn is_focus(&self) -> bool {
+        self.timers.iter().any(|link| link.focus)
+    }
+
+    fn link(&mut self, timer: &ActiveTimer) {
+        if !self.timers.iter().any(|link| link.timer_id == timer.id) {
+            self.timers.push(TimerLink::new(timer));
+        }
+    }
+
+    /// Time spent listening. Pauses resumed within the window count towards
+    /// the play, so it is capped at the track's length.
+    fn seconds(&self, now: DateTime<Utc>) -> i64 {
+        let Some(start) = parse_time(&self.start) else {
+            return 0;
+        };
+        let end = self.end.as_deref().and_then(parse_time).unwrap_or(now);
+        let seconds = (end - start).num_seconds().max(0);
+        match self.duration {
+            Some(duration) => seconds.min(duration.round() as i64),
+            None => seconds,
+        }
+    }
+}
+
+/// Title, artist and album.
+type TrackKey = (String, Option<String>, Option<String>);
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct HistoryStore {
+    #[serde(default)]
+    plays: Vec<Play>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TrackStats {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artist: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub album: Option<String>,
+    pub plays: u32,
+    pub minutes: i64,
+}
+
+/// What played during one timer, or one pomodoro session.
+#[derive(Debug, Serialize, Clone)]
+pub struct SessionPlaylist {
+    /// The pomodoro session id, or the timer id for other timers.
+    #[serde(rename = "sessionId")]
+    pub session_id: String,
+    #[serde(rename = "timerType")]
+    pub timer_type: String,
+    #[serde(rename = "taskId", skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    #[serde(rename = "taskName", skip_serializing_if = "Option::is_none")]
+    pub task_name: Option<String>,
+    pub focus: bool,
+    pub plays: Vec<Play>,
+}
+
+/// Tracks played, from the music listener's now playing updates, each linked
+/// to the timers that ran while it played. Stored in `listening_history.json`.
+pub struct ListeningHistory {
+    path: PathBuf,
+    store: Mutex<HistoryStore>,
+}
+
+impl ListeningHistory {
+    pub fn load(path: PathBuf) -> Self {
+        let mut store: HistoryStore = storage::read_json(&path);
+        // The app quit while these played; all that's known is they were
+        // started, so they are taken to have played through at most.
+        let now = Utc::now();
+        for play in store.plays.iter_mut().filter(|play| play.end.is_none()) {
+            let start = parse_time(&play.start).unwrap_or(now);
+            let length = Duration::seconds(play.duration.unwrap_or(0.0).round() as i64);
+            play.end = Some(format_time((start + length).min(now).max(start)));
+        }
+        Self { path, store: Mutex::new(store) }
+    }
+
+    /// Records a now playing update. `running` are the timers running at the
+    /// time, linked to a track that starts.
+    pub fn on_now_playing(&self, info: &NowPlayingInfo, running: &[ActiveTimer]) {
+        let now = Utc::now();
+        let playing = info.is_playing && info.title.as_deref().is_some_and(|title| !title.is_empty());
+        {
+            let store = self.lock();
+            let current = store.plays.last();
+            let unchanged = match current {
+                Some(play) if play.end.is_none() => playing && play.is_track(info),
+                _ => !playing,
+            };
+            if unchanged {
+                return;
+            }
+        }
+
+        self.update(|store| {
+            if let Some(play) = store.plays.last_mut().filter(|play| play.end.is_none()) {
+                play.end = Some(format_time(now));
+            }
+            if !playing {
+                return;
+            }
+
+            let resumed = store.plays.last_mut().filter(|play| {
+                play.is_track(info)
+                    && play.end.as_deref().and_then(parse_time).is_some_and(|end| now - end < RESUME_WINDOW)
+            });
+            let play = match resumed {
+                Some(play) => {
+                    play.end = None;
+                    play
+                }
+                None => {
+                    store.plays.push(Play {
+                        id: Uuid::new_v4().to_string(),
+                        title: info.title.clone().unwrap_or_default(),
+                        artist: info.artist.clone(),
+                        album: info.album.clone(),
+                        duration: info.duration,
+                        start: format_time(now),
+                        end: None,
+                        timers: Vec::new(),
+                    });
+                    let excess = store.plays.len().saturating_sub(MAX_PLAYS);
+                    store.plays.drain(..excess);
+                    store.plays.last_mut().expect("play was just added")
+                }
+            };
+            for timer in running.iter().filter(|timer| timer.paused_at.is_none() && timer.expired_at.is_none()) {
+                play.link(timer);
+            }
+        });
+    }
+
+    /// Links the track playing, if any, to a timer that started or resumed.
+    pub fn on_timer_started(&self, timer: &ActiveTimer) {
+        let linked = self
+            .lock()
+            .plays
+            .last()
+            .is_none_or(|play| play.end.is_some() || play.timers.iter().any(|link| link.timer_id == timer.id));
+        if linked {
+            return;
+        }
+        self.update(|store| {
+            if let Some(play) = store.plays.last_mut() {
+                play.link(timer);
+            }
+        });
+    }
+
+    /// Most played tracks started between `from` and `to`, by listening
+    /// time. `focus_only` counts only plays during focus timers.
+    pub fn top_tracks(&self, from: NaiveDate, to: NaiveDate, focus_only: bool, limit: usize) -> Vec<TrackStats> {
+        let now = Utc::now();
+        let mut tracks: HashMap<TrackKey, (u32, i64)> = HashMap::new();
+        for play in self.plays_between(from, to).iter().filter(|play| !focus_only || play.is_focus()) {
+            let stats = tracks
+                .entry((play.title.clone(), play.artist.clone(), play.album.clone()))
+                .or_default();
+            stats.0 += 1;
+            stats.1 += play.seconds(now);
+        }
+
+        let mut tracks: Vec<TrackStats> = tracks
+            .into_iter()
+            .map(|((title, artist, album), (plays, seconds))| TrackStats {
+                title,
+                artist,
+                album,
+                plays,
+                minutes: (seconds + 30) / 60,
+            })
+            .collect();
+        tracks.sort_by(|a, b| {
+            b.minutes
+                .cmp(&a.minutes)
+                .then(b.plays.cmp(&a.plays))
+                .then_with(|| a.title.cmp(&b.title))
+        });
+        tracks.truncate(limit);
+        tracks
+    }
+
+    /// What played during each timer or pomodoro session with plays started
+    /// between `from` and `to`, earliest session first.
+    pub fn session_playlists(&self, from: NaiveDate, to: NaiveDate) -> Vec<SessionPlaylist> {
+        let mut playlists: Vec<SessionPlaylist> = Vec::new();
+        for play in self.plays_between(from, to) {
+            for link in &play.timers {
+                let index = match playlists.iter().position(|playlist| playlist.session_id == link.session()) {
+                    Some(index) => index,
+                    None => {
+                        playlists.push(SessionPlaylist {
+                            session_id: link.session().to_string(),
+                            timer_type: link.timer_type.clone(),
+                            task_id: link.task_id.clone(),
+                            task_name: link.task_name.clone(),
+                            focus: false,
+                            plays: Vec::new(),
+                        });
+                        playlists.len() - 1
+                    }
+                };
+                let playlist = &mut playlists[index];
+                playlist.focus |= link.focus;
+                if !playlist.plays.iter().any(|p| p.id == play.id) {
+                    playlist.plays.push(play.clone());
+                }
+            }
+        }
+        playlists
+    }
+
+    fn plays_between(&self, from: NaiveDate, to: NaiveDate) -> Vec<Play> {
+        let zone = tz::zone();
+        self.lock()
+            .plays
+            .iter()
+            .filter(|play| {
+                parse_time(&play.start)
+                    .map(|start| start.with_timezone(&zone).date_naive())
+                    .is_some_and(|day| day >= from && day <= to)
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HistoryStore> {
+        self.store.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn update<T>(&self, f: impl FnOnce(&mut HistoryStore) -> T) -> T {
+        let mut store = self.lock();
+        let result = f(&mut store);
+        if let Err(e) = storage::write_json(&self.path, &*store) {
+            eprintln!("Failed to save listening history: {}", e);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::music::{MockMusic, MusicProvider};
+
+    fn load_with(plays: Vec<Play>) -> (ListeningHistory, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("growing-listening-{}", Uuid::new_v4()));
+        let path = dir.join(LISTENING_HISTORY_FILE);
+        storage::write_json(&path, &HistoryStore { plays }).unwrap();
+        (ListeningHistory::load(path), dir)
+    }
+
+    fn ago(minutes: i64) -> DateTime<Utc> {
+        Utc::now() - Duration::minutes(minutes)
+    }
+
+    /// A finished play of the mock player's track `n`.
+    fn play(n: u32, start: DateTime<Utc>, minutes: i64, timers: Vec<TimerLink>) -> Play {
+        Play {
+            id: Uuid::new_v4().to_string(),
+            title: format!("Track {}", n),
+            artist: Some("Mock Artist".to_string()),
+            album: Some("Mock Album".to_string()),
+            duration: Some(600.0),
+            start: format_time(start),
+            end: Some(format_time(start + Duration::minutes(minutes))),
+            timers,
+        }
+    }
+
+    fn timer(id: &str, timer_type: &str, session: Option<&str>, phase: Option<&str>) -> ActiveTimer {
+        ActiveTimer {
+            id: id.to_string(),
+            timer_type: timer_type.to_string(),
+            session_id: session.map(str::to_string),
+            pomodoro_phase: phase.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn link(timer: &ActiveTimer) -> TimerLink {
+        TimerLink::new(timer)
+    }
+
+    fn titles(history: &ListeningHistory) -> Vec<(String, bool)> {
+        history.lock().plays.iter().map(|p| (p.title.clone(), p.end.is_none())).collect()
+    }
+
+    /// A range of days around today wide enough for any zone.
+    fn around_today() -> (NaiveDate, NaiveDate) {
+        let today = Utc::now().date_naive();
+        (today - Duration::days(2), today + Duration::days(2))
+    }
+
+    #[test]
+    fn pausing_and_resuming_continues_the_play() {
+        let (history, dir) = load_with(Vec::new());
+        let music = MockMusic::default();
+        let focus = timer("f", "focus", None, None);
+        history.on_now_playing(&music.now_playing(), std::slice::from_ref(&focus));
+        // Repeated updates for the same track change nothing.
+        history.on_now_playing(&music.now_playing(), &[]);
+        assert_eq!(titles(&history), [("Track 1".to_string(), true)]);
+
+        music.play_pause();
+        history.on_now_playing(&music.now_playing(), &[]);
+        assert_eq!(titles(&history), [("Track 1".to_string(), false)]);
+        music.play_pause();
+        history.on_now_playing(&music.now_playing(), &[]);
+        assert_eq!(titles(&history), [("Track 1".to_string(), true)]);
+
+        music.next_track();
+        let paused = ActiveTimer { paused_at: Some(format_time(ago(1))), ..timer("p", "focus", None, None) };
+        history.on_now_playing(&music.now_playing(), &[focus.clone(), paused]);
+        assert_eq!(titles(&history), [("Track 1".to_string(), false), ("Track 2".to_string(), true)]);
+        // Only timers that are counting down are linked.
+        let links: Vec<String> = history.lock().plays[1].timers.iter().map(|l| l.timer_id.clone()).collect();
+        assert_eq!(links, ["f"]);
+
+        // A timer starting mid-track is linked to it, once.
+        let pomodoro = timer("w", "pomodoro", Some("s"), Some("work"));
+        history.on_timer_started(&pomodoro);
+        history.on_timer_started(&pomodoro);
+        assert_eq!(history.lock().plays[1].timers.len(), 2);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn a_track_resumed_after_the_window_is_a_new_play() {
+        let recent = play(1, ago(20), 15, Vec::new());
+        let (history, dir) = load_with(vec![recent.clone()]);
+        let music = MockMusic::default();
+        history.on_now_playing(&music.now_playing(), &[]);
+        assert_eq!(history.lock().plays.len(), 1);
+        assert_eq!(history.lock().plays[0].id, recent.id);
+        let _ = std::fs::remove_dir_all(dir);
+
+        let stale = play(1, ago(30), 15, Vec::new());
+        let (history, dir) = load_with(vec![stale]);
+        history.on_now_playing(&music.now_playing(), &[]);
+        assert_eq!(titles(&history), [("Track 1".to_string(), false), ("Track 1".to_string(), true)]);
+        let _ = std::fs::remove_dir_all(dir);
+
+        // Another track in between ends the chance to resume.
+        let (history, dir) = load_with(vec![play(1, ago(6), 2, Vec::new()), play(2, ago(4), 2, Vec::new())]);
+        history.on_now_playing(&music.now_playing(), &[]);
+        assert_eq!(history.lock().plays.len(), 3);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn plays_left_open_are_closed_on_load() {
+        let open = |start: DateTime<Utc>, duration: Option<f64>| Play {
+            end: None,
+            duration,
+            ..play(1, start, 0, Vec::new())
+        };
+        let (history, dir) = load_with(vec![open(ago(60), Some(180.0)), open(ago(1), Some(600.0)), open(ago(30), None)]);
+        let plays = history.lock().plays.clone();
+        let spans: Vec<i64> = plays
+            .iter()
+            .map(|p| (parse_time(p.end.as_deref().unwrap()).unwrap() - parse_time(&p.start).unwrap()).num_seconds())
+            .collect();
+        // Played through, cut off at now, and of unknown length.
+        assert_eq!(spans[0], 180);
+        assert!((59..=61).contains(&spans[1]), "{:?}", spans);
+        assert_eq!(spans[2], 0);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn the_history_keeps_the_latest_plays() {
+        let plays: Vec<Play> = (0..MAX_PLAYS as u32).map(|n| play(n + 10, ago(3000), 1, Vec::new())).collect();
+        let (history, dir) = load_with(plays);
+        history.on_now_playing(&MockMusic::default().now_playing(), &[]);
+        let plays = history.lock().plays.clone();
+        assert_eq!(plays.len(), MAX_PLAYS);
+        assert_eq!((plays[0].title.as_str(), plays[MAX_PLAYS - 1].title.as_str()), ("Track 11", "Track 1"));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn top_tracks_by_listening_time() {
+        let focus = link(&timer("f", "focus", None, None));
+        let short_break = link(&timer("b", "pomodoro", Some("s"), Some("shortBreak")));
+        let (history, dir) = load_with(vec![
+            play(1, ago(100), 3, vec![focus.clone()]),
+            play(1, ago(90), 3, vec![focus.clone()]),
+            play(2, ago(80), 5, vec![short_break.clone()]),
+            play(3, ago(70), 4, Vec::new()),
+            // Time past the end of the track isn't counted.
+            Play { duration: Some(60.0), ..play(4, ago(60), 30, vec![focus]) },
+            play(5, ago(60 * 24 * 10), 30, Vec::new()),
+        ]);
+        let (from, to) = around_today();
+        let top = |focus_only, limit| -> Vec<(String, u32, i64)> {
+            history
+                .top_tracks(from, to, focus_only, limit)
+                .into_iter()
+                .map(|t| (t.title, t.plays, t.minutes))
+                .collect()
+        };
+        let row = |n: u32, plays, minutes| (format!("Track {}", n), plays, minutes);
+        assert_eq!(top(false, 10), [row(1, 2, 6), row(2, 1, 5), row(3, 1, 4), row(4, 1, 1)]);
+        assert_eq!(top(false, 2), [row(1, 2, 6), row(2, 1, 5)]);
+        // Breaks and plays without a timer aren't focus time.
+        assert_eq!(top(true, 10), [row(1, 2, 6), row(4, 1, 1)]);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn playlists_group_a_pomodoro_session_together() {
+        let work = link(&timer("w1", "pomodoro", Some("s"), Some("work")));
+        let rest = link(&timer("b1", "pomodoro", Some("s"), Some("shortBreak")));
+        let work_again = link(&timer("w2", "pomodoro", Some("s"), Some("work")));
+        let timebox = TimerLink { task_id: Some("t".to_string()), ..link(&timer("tb", "task", None, None)) };
+        let (history, dir) = load_with(vec![
+            play(1, ago(60), 5, vec![work.clone()]),
+            play(2, ago(50), 5, vec![work, timebox.clone()]),
+            play(3, ago(40), 5, vec![rest]),
+            play(4, ago(30), 5, vec![work_again]),
+            play(5, ago(20), 5, vec![timebox]),
+            play(6, ago(10), 5, Vec::new()),
+        ]);
+        let (from, to) = around_today();
+        let playlists: Vec<(String, bool, Option<String>, Vec<String>)> = history
+            .session_playlists(from, to)
+            .into_iter()
+            .map(|p| (p.session_id, p.focus, p.task_id, p.plays.into_iter().map(|play| play.title).collect()))
+            .collect();
+        let tracks = |ns: &[u32]| ns.iter().map(|n| format!("Track {}", n)).collect::<Vec<_>>();
+        assert_eq!(
+            playlists,
+            [
+                ("s".to_string(), true, None, tracks(&[1, 2, 3, 4])),
+                ("tb".to_string(), true, Some("t".to_string()), tracks(&[2, 5])),
+            ]
+        );
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
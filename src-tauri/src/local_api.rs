@@ -0,0 +1,227 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::actions::Request;
+
+/// Written to the data dir while the app is running so local tools can find
+/// the API. Only readable by the current user on Unix.
+pub const ENDPOINT_FILE: &str = "api.json";
+
+const MAX_REQUEST_BYTES: u64 = 1024 * 1024;
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Endpoint {
+    pub port: u16,
+    pub token: String,
+    pub pid: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ApiRequest {
+    token: String,
+    request: Request,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ApiResponse {
+    ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    result: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+pub enum ClientError {
+    /// Nothing accepted the connection, so the app is not running; callers
+    /// may fall back to editing the data file directly.
+    Unavailable(String),
+    /// The app rejected the request, or the exchange broke off after
+    /// connecting. The request may have been applied, so it isn't retried
+    /// against the file.
+    Failed(String),
+}
+
+pub fn endpoint_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(ENDPOINT_FILE)
+}
+
+/// Binds a loopback listener on an ephemeral port, publishes it in
+/// `api.json` and serves one JSON request per line on a background thread.
+/// Requests are handled one at a time so mutations never interleave.
+pub fn serve<F>(data_dir: &Path, handler: F) -> Result<(), String>
+where
+    F: Fn(Request) -> Result<serde_json::Value, String> + Send + 'static,
+{
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .map_err(|e| format!("Failed to bind local API: {}", e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read local API address: {}", e))?
+        .port();
+
+    let endpoint = Endpoint {
+        port,
+        token: Uuid::new_v4().simple().to_string(),
+        pid: std::process::id(),
+    };
+    write_endpoint(data_dir, &endpoint)?;
+
+    let token = endpoint.token;
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    if let Err(e) = handle_connection(stream, &token, &handler) {
+                        eprintln!("Local API connection failed: {}", e);
+                    }
+                }
+                Err(e) => eprintln!("Local API accept failed: {}", e),
+            }
+        }
+    });
+
+    eprintln!("Local API listening on 127.0.0.1:{}", port);
+    Ok(())
+}
+
+fn handle_connection<F>(stream: TcpStream, token: &str, handler: &F) -> std::io::Result<()>
+where
+    F: Fn(Request) -> Result<serde_json::Value, String>,
+{
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    let mut line = String::new();
+    BufReader::new((&stream).take(MAX_REQUEST_BYTES)).read_line(&mut line)?;
+
+    let response = match serde_json::from_str::<ApiRequest>(&line) {
+        Ok(req) if req.token == token => match handler(req.request) {
+            Ok(result) => ApiResponse { ok: true, result: Some(result), error: None },
+            Err(e) => ApiResponse { ok: false, result: None, error: Some(e) },
+        },
+        Ok(_) => ApiResponse { ok: false, result: None, error: Some("Invalid API token".to_string()) },
+        Err(e) => ApiResponse { ok: false, result: None, error: Some(format!("Invalid request: {}", e)) },
+    };
+
+    let mut body = serde_json::to_string(&response).unwrap_or_else(|_| "{\"ok\":false}".to_string());
+    body.push('\n');
+    (&stream).write_all(body.as_bytes())
+}
+
+fn write_endpoint(data_dir: &Path, endpoint: &Endpoint) -> Result<(), String> {
+    fs::create_dir_all(data_dir)
+        .map_err(|e| format!("Failed to create data dir: {}", e))?;
+    let content = serde_json::to_string(endpoint)
+        .map_err(|e| format!("Failed to serialize API endpoint: {}", e))?;
+
+    let path = endpoint_path(data_dir);
+    let _ = fs::remove_file(&path);
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&path)
+        .map_err(|e| format!("Failed to write API endpoint: {}", e))?;
+    file.write_all(content.as_bytes())
+        .map_err(|e| format!("Failed to write API endpoint: {}", e))
+}
+
+pub fn read_endpoint(data_dir: &Path) -> Option<Endpoint> {
+    let content = fs::read_to_string(endpoint_path(data_dir)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Sends a single request to the running app.
+pub fn send(endpoint: &Endpoint, request: &Request) -> Result<serde_json::Value, ClientError> {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, endpoint.port));
+    let stream = TcpStream::connect_timeout(&addr, Duration::from_secs(1))
+        .map_err(|e| ClientError::Unavailable(format!("App is not reachable: {}", e)))?;
+    stream
+        .set_read_timeout(Some(IO_TIMEOUT))
+        .and_then(|_| stream.set_write_timeout(Some(IO_TIMEOUT)))
+        .map_err(|e| ClientError::Failed(e.to_string()))?;
+
+    let payload = ApiRequest {
+        token: endpoint.token.clone(),
+        request: request.clone(),
+    };
+    let mut body = serde_json::to_string(&payload)
+        .map_err(|e| ClientError::Failed(format!("Failed to serialize request: {}", e)))?;
+    body.push('\n');
+    (&stream)
+        .write_all(body.as_bytes())
+        .map_err(|e| ClientError::Failed(format!("Failed to send request: {}", e)))?;
+
+    let mut line = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut line)
+        .map_err(|e| ClientError::Failed(format!("Failed to read response: {}", e)))?;
+    let response: ApiResponse = serde_json::from_str(&line)
+        .map_err(|e| ClientError::Failed(format!("Invalid response from app: {}", e)))?;
+
+    if response.ok {
+        Ok(response.result.unwrap_or(serde_json::Value::Null))
+    } else {
+        Err(ClientError::Failed(response.error.unwrap_or_else(|| "Request failed".to_string())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(port: u16) -> Endpoint {
+        Endpoint { port, token: "token".to_string(), pid: 0 }
+    }
+
+    #[test]
+    fn nothing_listening_is_unavailable() {
+        let port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap().local_addr().unwrap().port();
+        let result = send(&endpoint(port), &Request::Export);
+        assert!(matches!(result, Err(ClientError::Unavailable(_))));
+    }
+
+    #[test]
+    fn a_connection_dropped_after_accepting_is_failed() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            drop(stream);
+        });
+        let result = send(&endpoint(port), &Request::Export);
+        assert!(matches!(result, Err(ClientError::Failed(_))));
+    }
+
+    #[test]
+    fn requests_round_trip_through_the_handler() {
+        let dir = std::env::temp_dir().join(format!("growing-api-{}", Uuid::new_v4()));
+        serve(&dir, |request| match request {
+            Request::AddNote { text } => Ok(serde_json::json!({ "text": text })),
+            _ => Err("Not supported".to_string()),
+        })
+        .unwrap();
+        let endpoint = read_endpoint(&dir).unwrap();
+
+        let result = send(&endpoint, &Request::AddNote { text: "hi".to_string() });
+        assert_eq!(result.ok(), Some(serde_json::json!({ "text": "hi" })));
+        assert!(matches!(send(&endpoint, &Request::Export), Err(ClientError::Failed(e)) if e == "Not supported"));
+
+        let wrong_token = Endpoint { token: "nope".to_string(), ..endpoint };
+        assert!(matches!(send(&wrong_token, &Request::Export), Err(ClientError::Failed(e)) if e == "Invalid API token"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,466 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PlanItem {
+    pub id: String,
+    pub text: String,
+    pub completed: bool,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default, rename = "targetExamples", skip_serializing_if = "Option::is_none")]
+    pub target_examples: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Section {
+    pub id: String,
+    pub title: String,
+    pub period: String,
+    pub items: Vec<PlanItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+    pub daily_reminder: bool,
+    pub daily_time: String,
+    pub weekly_reminder: bool,
+    pub weekly_day: String,
+    pub weekly_time: String,
+    #[serde(default)]
+    pub stand_reminder_enabled: bool,
+    #[serde(default = "default_sit_duration")]
+    pub sit_duration_minutes: u32,
+    #[serde(default = "default_stand_duration")]
+    pub stand_duration_minutes: u32,
+    #[serde(default)]
+    pub stand_mode: String,  // "sitting" or "standing"
+    #[serde(default)]
+    pub stand_mode_changed_at: Option<String>,  // ISO timestamp
+    /// Minutes without keyboard or mouse input after which the stand cycle
+    /// stops counting; 0 turns idle detection off.
+    #[serde(default = "default_stand_idle_minutes")]
+    pub stand_idle_minutes: u32,
+    /// What happens on return from idle or sleep: "reset" starts a fresh
+    /// sitting period, "pause" resumes the cycle where it left off.
+    #[serde(default = "default_stand_idle_action")]
+    pub stand_idle_action: String,
+    /// User-defined reminders. `None` until the user edits them, in which case
+    /// the daily and weekly settings above stand in as two rules.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reminders: Option<Vec<ReminderRule>>,
+    /// What to do with reminders that came due while the machine was asleep:
+    /// "late" (fire each once), "summary" (one combined notification) or "skip".
+    #[serde(default = "default_missed_reminders")]
+    pub missed_reminders: String,
+    /// Times when reminders and stand alerts stay silent.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub quiet_hours: Vec<QuietHours>,
+    /// Hold back reminders while a task timebox is running.
+    #[serde(default = "default_true")]
+    pub suppress_during_timebox: bool,
+    /// Deliver held-back reminders when the timebox ends instead of dropping them.
+    #[serde(default)]
+    pub defer_during_timebox: bool,
+    /// Replaces quiet hours and mutes alerts on days marked as ATC (on call).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub atc_profile: Option<ReminderProfile>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct QuietHours {
+    /// Weekday names; empty means every day.
+    #[serde(default)]
+    pub days: Vec<String>,
+    /// `HH:MM`. A window ending before it starts runs past midnight.
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ReminderProfile {
+    #[serde(default)]
+    pub quiet_hours: Vec<QuietHours>,
+    #[serde(default = "default_true")]
+    pub stand_reminders: bool,
+    /// Ids of reminder rules that stay silent on these days.
+    #[serde(default)]
+    pub muted_reminders: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ReminderRule {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+    /// Cron expression (`0 9 * * MON-FRI`) or RRULE (`FREQ=WEEKLY;BYDAY=MO;BYHOUR=9`).
+    pub schedule: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// First day the rule can fire (YYYY-MM-DD); anchors RRULE intervals.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<ReminderAction>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReminderAction {
+    OpenView { view: String },
+}
+
+fn default_sit_duration() -> u32 { 45 }
+fn default_stand_duration() -> u32 { 10 }
+fn default_missed_reminders() -> String { "late".to_string() }
+fn default_stand_idle_minutes() -> u32 { 5 }
+fn default_stand_idle_action() -> String { "reset".to_string() }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct QuickNote {
+    pub id: String,
+    pub text: String,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct BragDocEntry {
+    pub id: String,
+    pub title: String,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub links: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub images: Option<Vec<String>>,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct DailyTask {
+    pub id: String,
+    pub text: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default, rename = "goalId", skip_serializing_if = "Option::is_none")]
+    pub goal_id: Option<String>,
+    pub completed: bool,
+    #[serde(default, rename = "completedAt", skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
+    pub date: String,
+    #[serde(default, rename = "movedToDate", skip_serializing_if = "Option::is_none")]
+    pub moved_to_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<i32>,
+    #[serde(default, rename = "isFrog", skip_serializing_if = "Option::is_none")]
+    pub is_frog: Option<bool>,
+    #[serde(default = "default_category")]
+    pub category: String,
+    #[serde(default, rename = "timeboxMinutes", skip_serializing_if = "Option::is_none")]
+    pub timebox_minutes: Option<i32>,
+    /// When to send a reminder for the task. If the task has since been
+    /// rescheduled to a later day, the reminder follows it at the same time
+    /// of day.
+    #[serde(default, rename = "remindAt", skip_serializing_if = "Option::is_none")]
+    pub remind_at: Option<String>,
+    /// The recurring task this is an occurrence of.
+    #[serde(default, rename = "templateId", skip_serializing_if = "Option::is_none")]
+    pub template_id: Option<String>,
+    /// The day the series scheduled this occurrence for, which stays put
+    /// when the task is moved to another day.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub occurrence: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checklist: Vec<ChecklistItem>,
+}
+
+/// A step of a task or todo, ticked off on its own.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ChecklistItem {
+    pub id: String,
+    pub text: String,
+    #[serde(default)]
+    pub completed: bool,
+    #[serde(default, rename = "completedAt", skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
+}
+
+/// A task that repeats. Occurrences are generated as `DailyTask`s for the
+/// coming days, by `rule` or a number of days after the last one was done.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TaskTemplate {
+    pub id: String,
+    pub text: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default, rename = "goalId", skip_serializing_if = "Option::is_none")]
+    pub goal_id: Option<String>,
+    #[serde(default = "default_category")]
+    pub category: String,
+    #[serde(default, rename = "timeboxMinutes", skip_serializing_if = "Option::is_none")]
+    pub timebox_minutes: Option<i32>,
+    /// Reminder time of day (`HH:MM`) for each occurrence.
+    #[serde(default, rename = "remindTime", skip_serializing_if = "Option::is_none")]
+    pub remind_time: Option<String>,
+    /// RRULE subset or cron expression picking the days, as for reminder
+    /// rules. Times in it are ignored.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub rule: String,
+    #[serde(default, rename = "skipWeekends")]
+    pub skip_weekends: bool,
+    /// Schedules the next occurrence this many days after the last one was
+    /// completed, instead of by `rule`.
+    #[serde(default, rename = "everyDaysAfterCompletion", skip_serializing_if = "Option::is_none")]
+    pub every_days_after_completion: Option<u32>,
+    /// First day of the series, which also anchors rule intervals.
+    #[serde(rename = "startDate")]
+    pub start_date: String,
+    /// Last day occurrences were generated for. Days up to it are never
+    /// generated again, so a deleted occurrence stays deleted.
+    #[serde(default, rename = "generatedThrough", skip_serializing_if = "Option::is_none")]
+    pub generated_through: Option<String>,
+}
+
+fn default_category() -> String {
+    "work".to_string()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ActiveTimer {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub timer_type: String,
+    #[serde(default, rename = "taskId", skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    #[serde(default, rename = "taskName", skip_serializing_if = "Option::is_none")]
+    pub task_name: Option<String>,
+    #[serde(rename = "endTime")]
+    pub end_time: String,
+    #[serde(rename = "durationMinutes")]
+    pub duration_minutes: i32,
+    #[serde(default, rename = "startedAt", skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    #[serde(default, rename = "pausedAt", skip_serializing_if = "Option::is_none")]
+    pub paused_at: Option<String>,
+    #[serde(default, rename = "remainingSeconds", skip_serializing_if = "Option::is_none")]
+    pub remaining_seconds: Option<i64>,
+    #[serde(default, rename = "expiredAt", skip_serializing_if = "Option::is_none")]
+    pub expired_at: Option<String>,
+    #[serde(default, rename = "sessionId", skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(default, rename = "pomodoroPhase", skip_serializing_if = "Option::is_none")]
+    pub pomodoro_phase: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PomodoroSettings {
+    #[serde(default = "default_work_minutes", rename = "workMinutes")]
+    pub work_minutes: u32,
+    #[serde(default = "default_short_break_minutes", rename = "shortBreakMinutes")]
+    pub short_break_minutes: u32,
+    #[serde(default = "default_long_break_minutes", rename = "longBreakMinutes")]
+    pub long_break_minutes: u32,
+    /// Work sessions before the long break that ends a set.
+    #[serde(default = "default_pomodoro_cycles")]
+    pub cycles: u32,
+    #[serde(default = "default_true", rename = "autoAdvance")]
+    pub auto_advance: bool,
+}
+
+impl Default for PomodoroSettings {
+    fn default() -> Self {
+        Self {
+            work_minutes: default_work_minutes(),
+            short_break_minutes: default_short_break_minutes(),
+            long_break_minutes: default_long_break_minutes(),
+            cycles: default_pomodoro_cycles(),
+            auto_advance: true,
+        }
+    }
+}
+
+fn default_work_minutes() -> u32 { 25 }
+fn default_short_break_minutes() -> u32 { 5 }
+fn default_long_break_minutes() -> u32 { 15 }
+fn default_pomodoro_cycles() -> u32 { 4 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Todo {
+    pub id: String,
+    pub text: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default, rename = "goalId", skip_serializing_if = "Option::is_none")]
+    pub goal_id: Option<String>,
+    #[serde(default, rename = "lastScheduledDate", skip_serializing_if = "Option::is_none")]
+    pub last_scheduled_date: Option<String>,
+    #[serde(default, rename = "createdAt")]
+    pub created_at: String,
+    #[serde(default, rename = "remindAt", skip_serializing_if = "Option::is_none")]
+    pub remind_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checklist: Vec<ChecklistItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FeatureRequest {
+    pub id: String,
+    pub text: String,
+    #[serde(default, rename = "createdAt")]
+    pub created_at: String,
+    #[serde(default)]
+    pub completed: bool,
+    #[serde(default, rename = "completedAt", skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct BugReport {
+    pub id: String,
+    pub text: String,
+    #[serde(default, rename = "createdAt")]
+    pub created_at: String,
+    #[serde(default)]
+    pub completed: bool,
+    #[serde(default, rename = "completedAt", skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Curiosity {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub completed: bool,
+    #[serde(default, rename = "completedAt", skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
+    #[serde(default, rename = "createdAt")]
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Review {
+    pub id: String,
+    #[serde(rename = "prLink")]
+    pub pr_link: String,
+    pub title: String,
+    #[serde(default = "default_github_source")]
+    pub source: String,
+    #[serde(default)]
+    pub completed: bool,
+    #[serde(default, rename = "completedAt", skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
+    #[serde(default, rename = "createdAt")]
+    pub created_at: String,
+    pub date: String,
+}
+
+fn default_github_source() -> String {
+    "github".to_string()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AppData {
+    pub sections: Vec<Section>,
+    pub notifications: NotificationSettings,
+    #[serde(default, rename = "quickNotes")]
+    pub quick_notes: Vec<QuickNote>,
+    #[serde(default, rename = "bragDocs")]
+    pub brag_docs: Vec<BragDocEntry>,
+    #[serde(default, rename = "dailyTasks")]
+    pub daily_tasks: Vec<DailyTask>,
+    #[serde(default)]
+    pub todos: Vec<Todo>,
+    #[serde(default, rename = "featureRequests")]
+    pub feature_requests: Vec<FeatureRequest>,
+    #[serde(default, rename = "bugReports")]
+    pub bug_reports: Vec<BugReport>,
+    #[serde(default)]
+    pub curiosities: Vec<Curiosity>,
+    #[serde(default)]
+    pub reviews: Vec<Review>,
+    #[serde(default = "default_theme")]
+    pub theme: String,
+    #[serde(default, rename = "darkMode")]
+    pub dark_mode: bool,
+    #[serde(default, rename = "userName")]
+    pub user_name: Option<String>,
+    #[serde(default, rename = "onboardingComplete")]
+    pub onboarding_complete: bool,
+    #[serde(default = "default_true", rename = "appleMusicEnabled")]
+    pub apple_music_enabled: bool,
+    #[serde(default = "default_true", rename = "frogEnabled")]
+    pub frog_enabled: bool,
+    #[serde(default, rename = "atcDays", skip_serializing_if = "Vec::is_empty")]
+    pub atc_days: Vec<String>,
+    #[serde(default, rename = "activeTimer", skip_serializing_if = "Option::is_none")]
+    pub active_timer: Option<ActiveTimer>,
+    #[serde(default, rename = "activeTimers", skip_serializing_if = "Vec::is_empty")]
+    pub active_timers: Vec<ActiveTimer>,
+    #[serde(default)]
+    pub pomodoro: PomodoroSettings,
+    /// IANA zone (`Europe/Berlin`) schedules and dates are computed in.
+    /// Unset follows the system zone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Music started and paused along with timers, per timer type.
+    #[serde(default, rename = "focusMusic", skip_serializing_if = "Vec::is_empty")]
+    pub focus_music: Vec<FocusMusicRule>,
+    /// Which unfinished tasks move to the new day when the date changes.
+    #[serde(default, rename = "carryForward")]
+    pub carry_forward: CarryForwardSettings,
+    #[serde(default, rename = "taskTemplates", skip_serializing_if = "Vec::is_empty")]
+    pub task_templates: Vec<TaskTemplate>,
+    /// One-time migrations already applied to this data, so they run once
+    /// rather than on every launch.
+    #[serde(default, rename = "schemaVersion")]
+    pub schema_version: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum CarryForwardPolicy {
+    /// Unfinished tasks stay on their day until carried forward by hand.
+    #[default]
+    None,
+    All,
+    FrogOnly,
+    /// Tasks in one of `categories`.
+    Categories,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CarryForwardSettings {
+    #[serde(default)]
+    pub policy: CarryForwardPolicy,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+}
+
+/// What the music player does when a kind of timer runs.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FocusMusicRule {
+    /// "focus", "task" or "pomodoro".
+    #[serde(rename = "timerType")]
+    pub timer_type: String,
+    #[serde(default)]
+    pub enabled: bool,
+    /// Playlist or station started with the timer. Empty plays whatever the
+    /// player has queued.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub playlist: String,
+    /// Pause when the timer is paused, ends or a break starts, and resume
+    /// when it picks up again or the next work phase starts.
+    #[serde(default = "default_true", rename = "pauseOnEnd")]
+    pub pause_on_end: bool,
+}
+
+fn default_theme() -> String {
+    "editorial".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
@@ -0,0 +1,968 @@
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::artwork_cache::{self, ArtworkCache};
+#[cfg(target_os = "macos")]
+use crate::itunes;
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+use crate::itunes::ITunesClient;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct NowPlayingInfo {
+    pub is_playing: bool,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub album_year: Option<i32>,
+    pub duration: Option<f64>,
+    pub position: Option<f64>,
+    /// Base64 image, only for artwork that couldn't go into the cache
+    /// (streams without an artist and album).
+    pub artwork: Option<String>,
+    /// Where the webview loads the cached artwork from.
+    pub artwork_url: Option<String>,
+}
+
+impl NowPlayingInfo {
+    /// Whether both describe the same track in the same play state, ignoring
+    /// how far into it the player is.
+    pub fn same_track(&self, other: &NowPlayingInfo) -> bool {
+        self.is_playing == other.is_playing
+            && self.title == other.title
+            && self.artist == other.artist
+            && self.album == other.album
+    }
+}
+
+/// A music player the now playing bar can show and control.
+pub trait MusicProvider: Send + Sync {
+    /// Identifies the provider in logs.
+    fn name(&self) -> &'static str;
+    fn now_playing(&self) -> NowPlayingInfo;
+    /// Whether something is playing, without gathering the rest of
+    /// `now_playing` (artwork especially).
+    fn is_playing(&self) -> bool {
+        self.now_playing().is_playing
+    }
+    fn play_pause(&self);
+    fn next_track(&self);
+    fn previous_track(&self);
+    /// Brings the player to the front, launching it if needed.
+    fn open_player(&self);
+    fn open_artist(&self, artist: &str);
+    fn open_album(&self, album: &str, artist: Option<&str>);
+    /// Starts a playlist or station: a playlist name for Apple Music, a URI
+    /// the player understands (`spotify:playlist:...`) for MPRIS players.
+    fn play_playlist(&self, playlist: &str);
+}
+
+/// Picks the provider for this platform: Apple Music on macOS, whichever
+/// MPRIS player is active on Linux. `GROWING_MUSIC_PROVIDER` overrides the
+/// choice with `applescript`, `mpris` or `mock`, the last for trying out the
+/// now playing bar without a player. Artwork goes through `artwork`.
+pub fn provider(artwork: Arc<ArtworkCache>) -> Arc<dyn MusicProvider> {
+    match std::env::var("GROWING_MUSIC_PROVIDER").as_deref() {
+        Ok("mock") => return Arc::new(MockMusic::default()),
+        #[cfg(target_os = "macos")]
+        Ok("applescript") => return Arc::new(AppleScriptMusic::new(artwork)),
+        #[cfg(target_os = "linux")]
+        Ok("mpris") => return Arc::new(MprisMusic::new(artwork)),
+        Ok(other) => eprintln!("Music provider {} is not available here, using the default", other),
+        Err(_) => {}
+    }
+    platform_provider(artwork)
+}
+
+#[cfg(target_os = "macos")]
+fn platform_provider(artwork: Arc<ArtworkCache>) -> Arc<dyn MusicProvider> {
+    Arc::new(AppleScriptMusic::new(artwork))
+}
+
+#[cfg(target_os = "linux")]
+fn platform_provider(artwork: Arc<ArtworkCache>) -> Arc<dyn MusicProvider> {
+    Arc::new(MprisMusic::new(artwork))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn platform_provider(_artwork: Arc<ArtworkCache>) -> Arc<dyn MusicProvider> {
+    Arc::new(NoMusic)
+}
+
+/// Puts the track's artwork in the cache and points `artwork_url` at it:
+/// what `embedded` gets from the player, else whatever `fetch` finds for
+/// the artist and album. Neither runs for albums already cached. Tracks
+/// without both keep embedded artwork inline, as there is nothing to file
+/// it under.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn attach_artwork<E, F>(cache: &ArtworkCache, info: &mut NowPlayingInfo, embedded: E, fetch: F)
+where
+    E: FnOnce() -> Option<Vec<u8>>,
+    F: FnOnce(&str, &str) -> Option<Vec<u8>>,
+{
+    use base64::{Engine as _, engine::general_purpose};
+
+    let (Some(artist), Some(album)) = (info.artist.clone(), info.album.clone()) else {
+        info.artwork = embedded().map(|bytes| general_purpose::STANDARD.encode(bytes));
+        return;
+    };
+    info.artwork = None;
+    info.artwork_url = cache
+        .resolve(&artist, &album, embedded, || fetch(&artist, &album))
+        .map(|file| artwork_cache::url(&file));
+}
+
+/// Follows a player that is polled rather than telling us about changes.
+#[cfg(not(target_os = "macos"))]
+pub struct TrackWatch {
+    last: NowPlayingInfo,
+}
+
+#[cfg(not(target_os = "macos"))]
+impl TrackWatch {
+    pub fn new(music: &dyn MusicProvider) -> Self {
+        Self { last: music.now_playing() }
+    }
+
+    /// Reads the player again. Returns what it reports when the track or
+    /// play state changed since the last read, not when only the position
+    /// moved on.
+    pub fn poll(&mut self, music: &dyn MusicProvider) -> Option<NowPlayingInfo> {
+        let info = music.now_playing();
+        let changed = !info.same_track(&self.last);
+        self.last = info;
+        changed.then(|| self.last.clone())
+    }
+}
+
+/// A pretend player holding one track in memory. The controls change what it
+/// reports, so the now playing bar can be exercised without a real player.
+pub struct MockMusic {
+    info: Mutex<NowPlayingInfo>,
+    track: Mutex<u32>,
+}
+
+impl MockMusic {
+    pub fn new(info: NowPlayingInfo) -> Self {
+        Self { info: Mutex::new(info), track: Mutex::new(1) }
+    }
+
+    #[cfg(test)]
+    fn update<F: FnOnce(&mut NowPlayingInfo)>(&self, change: F) {
+        change(&mut self.info.lock().unwrap_or_else(|e| e.into_inner()));
+    }
+
+    fn skip(&self, by: i32) {
+        let mut track = self.track.lock().unwrap_or_else(|e| e.into_inner());
+        *track = track.saturating_add_signed(by).max(1);
+        let mut info = self.info.lock().unwrap_or_else(|e| e.into_inner());
+        info.title = Some(format!("Track {}", *track));
+        info.position = Some(0.0);
+    }
+}
+
+impl Default for MockMusic {
+    fn default() -> Self {
+        Self::new(NowPlayingInfo {
+            is_playing: true,
+            title: Some("Track 1".to_string()),
+            artist: Some("Mock Artist".to_string()),
+            album: Some("Mock Album".to_string()),
+            album_year: Some(2024),
+            duration: Some(180.0),
+            position: Some(0.0),
+            artwork: None,
+            artwork_url: None,
+        })
+    }
+}
+
+impl MusicProvider for MockMusic {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn now_playing(&self) -> NowPlayingInfo {
+        self.info.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn play_pause(&self) {
+        let mut info = self.info.lock().unwrap_or_else(|e| e.into_inner());
+        info.is_playing = !info.is_playing;
+    }
+
+    fn next_track(&self) {
+        self.skip(1);
+    }
+
+    fn previous_track(&self) {
+        self.skip(-1);
+    }
+
+    fn open_player(&self) {
+        eprintln!("Mock music: open player");
+    }
+
+    fn open_artist(&self, artist: &str) {
+        eprintln!("Mock music: open artist {}", artist);
+    }
+
+    fn open_album(&self, album: &str, _artist: Option<&str>) {
+        eprintln!("Mock music: open album {}", album);
+    }
+
+    fn play_playlist(&self, playlist: &str) {
+        *self.track.lock().unwrap_or_else(|e| e.into_inner()) = 1;
+        let mut info = self.info.lock().unwrap_or_else(|e| e.into_inner());
+        info.is_playing = true;
+        info.title = Some("Track 1".to_string());
+        info.album = Some(playlist.to_string());
+        info.position = Some(0.0);
+    }
+}
+
+/// Apple Music, driven through AppleScript.
+#[cfg(target_os = "macos")]
+pub struct AppleScriptMusic {
+    artwork: Arc<ArtworkCache>,
+    itunes: ITunesClient,
+}
+
+#[cfg(target_os = "macos")]
+impl AppleScriptMusic {
+    fn new(artwork: Arc<ArtworkCache>) -> Self {
+        Self { artwork, itunes: ITunesClient::new() }
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl MusicProvider for AppleScriptMusic {
+    fn name(&self) -> &'static str {
+        "applescript"
+    }
+
+    fn now_playing(&self) -> NowPlayingInfo {
+        query_apple_music(&self.artwork, &self.itunes)
+    }
+
+    fn play_pause(&self) {
+        run_script(
+            r#"
+            tell application "Music"
+                playpause
+            end tell
+        "#,
+        );
+    }
+
+    fn next_track(&self) {
+        run_script(
+            r#"
+            tell application "Music"
+                next track
+            end tell
+        "#,
+        );
+    }
+
+    fn previous_track(&self) {
+        run_script(
+            r#"
+            tell application "Music"
+                previous track
+            end tell
+        "#,
+        );
+    }
+
+    fn open_player(&self) {
+        let _ = Command::new("open")
+            .arg("-a")
+            .arg("Music")
+            .spawn();
+    }
+
+    fn open_artist(&self, artist: &str) {
+        let url = match self.itunes.artist_id(artist) {
+            Some(artist_id) => format!("music://music.apple.com/us/artist/{}", artist_id),
+            None => itunes::music_search_url(artist),
+        };
+        let _ = Command::new("open").arg(&url).output();
+    }
+
+    fn open_album(&self, album: &str, artist: Option<&str>) {
+        let url = match self.itunes.album_id(album, artist) {
+            Some(collection_id) => format!("music://music.apple.com/us/album/{}", collection_id),
+            None => match artist {
+                Some(artist) => itunes::music_search_url(&format!("{} {}", album, artist)),
+                None => itunes::music_search_url(album),
+            },
+        };
+        let _ = Command::new("open").arg(&url).output();
+    }
+
+    fn is_playing(&self) -> bool {
+        let script = r#"
+            if application "Music" is not running then return "stopped"
+            tell application "Music" to return player state as string
+        "#;
+        Command::new("osascript")
+            .arg("-e")
+            .arg(script)
+            .output()
+            .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "playing")
+    }
+
+    fn play_playlist(&self, playlist: &str) {
+        // Passed as an argument so the name needs no escaping.
+        let script = r#"
+            on run argv
+                tell application "Music" to play playlist (item 1 of argv)
+            end run
+        "#;
+        match Command::new("osascript").arg("-e").arg(script).arg(playlist).output() {
+            Ok(output) if !output.status.success() => eprintln!(
+                "Failed to play playlist {}: {}",
+                playlist,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            Err(e) => eprintln!("Failed to play playlist {}: {}", playlist, e),
+            Ok(_) => {}
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn run_script(script: &str) {
+    let _ = Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output();
+}
+
+#[cfg(target_os = "macos")]
+fn query_apple_music(cache: &ArtworkCache, itunes: &ITunesClient) -> NowPlayingInfo {
+    let script = r#"
+        tell application "System Events"
+            if not (exists process "Music") then
+                return "{\"state\":\"stopped\"}"
+            end if
+        end tell
+        tell application "Music"
+            set currentState to player state
+            if currentState is stopped then
+                return "{\"state\":\"stopped\"}"
+            end if
+            set stateName to "paused"
+            if currentState is playing then set stateName to "playing"
+            set trackName to ""
+            set trackArtist to ""
+            set trackAlbum to ""
+            set trackYear to ""
+            set trackDuration to ""
+            set trackPosition to ""
+            set streamTitle to ""
+            try
+                set trackName to name of current track
+            end try
+            try
+                set trackArtist to artist of current track
+            end try
+            try
+                set trackAlbum to album of current track
+            end try
+            try
+                set trackYear to (year of current track) as string
+            end try
+            try
+                set trackDuration to (duration of current track) as string
+            end try
+            try
+                set trackPosition to player position as string
+            end try
+            try
+                set streamTitle to current stream title
+            end try
+        end tell
+        return "{" & my jsonField("state", stateName) & "," & my jsonField("title", trackName) & "," & my jsonField("artist", trackArtist) & "," & my jsonField("album", trackAlbum) & "," & my jsonField("year", trackYear) & "," & my jsonField("duration", trackDuration) & "," & my jsonField("position", trackPosition) & "," & my jsonField("stream", streamTitle) & "}"
+
+        on jsonField(fieldName, fieldValue)
+            if fieldValue is missing value then set fieldValue to ""
+            return "\"" & fieldName & "\":\"" & my escapeJson(fieldValue as string) & "\""
+        end jsonField
+
+        on escapeJson(theText)
+            set theText to my replaceText(theText, "\\", "\\\\")
+            set theText to my replaceText(theText, "\"", "\\\"")
+            set theText to my replaceText(theText, return, "\\r")
+            set theText to my replaceText(theText, linefeed, "\\n")
+            set theText to my replaceText(theText, tab, "\\t")
+            return theText
+        end escapeJson
+
+        on replaceText(theText, searchText, replacementText)
+            set AppleScript's text item delimiters to searchText
+            set textParts to text items of theText
+            set AppleScript's text item delimiters to replacementText
+            set theText to textParts as string
+            set AppleScript's text item delimiters to ""
+            return theText
+        end replaceText
+    "#;
+
+    let output = match Command::new("osascript").arg("-e").arg(script).output() {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("Failed to run osascript: {}", e);
+            return NowPlayingInfo::default();
+        }
+    };
+    let mut info = match parse_apple_music(&String::from_utf8_lossy(&output.stdout)) {
+        Ok(info) => info,
+        Err(e) => {
+            eprintln!("{}", e);
+            return NowPlayingInfo::default();
+        }
+    };
+
+    // Artwork is only filed under the album when there is an artist too.
+    let album = info.album.clone().filter(|_| info.artist.is_some()).unwrap_or_default();
+    attach_artwork(cache, &mut info, || current_artwork(&album), |artist, album| itunes.album_artwork(artist, album));
+    info
+}
+
+/// The current track's artwork, read separately from its details as
+/// extracting it is slow and cached albums don't need it. Nothing if
+/// `album` is given and the track moved on to another album in between.
+#[cfg(target_os = "macos")]
+fn current_artwork(album: &str) -> Option<Vec<u8>> {
+    use base64::{Engine as _, engine::general_purpose};
+
+    let script = r#"
+        on run argv
+            tell application "Music"
+                try
+                    set expectedAlbum to item 1 of argv
+                    if expectedAlbum is not "" and (album of current track) is not expectedAlbum then return ""
+                    if (count of artworks of current track) is 0 then return ""
+                    set rawData to raw data of artwork 1 of current track
+                on error
+                    return ""
+                end try
+            end tell
+            return my encodeBase64(rawData)
+        end run
+
+        on encodeBase64(theData)
+            set tempFile to (path to temporary items folder as text) & "growing_artwork.tmp"
+            try
+                set fileRef to open for access file tempFile with write permission
+                set eof fileRef to 0
+                write theData to fileRef
+                close access fileRef
+                set base64Result to do shell script "base64 -i " & quoted form of POSIX path of tempFile
+                do shell script "rm -f " & quoted form of POSIX path of tempFile
+                return base64Result
+            on error
+                try
+                    close access file tempFile
+                end try
+                return ""
+            end try
+        end encodeBase64
+    "#;
+    let output = Command::new("osascript").arg("-e").arg(script).arg(album).output().ok()?;
+    let artwork: String = String::from_utf8_lossy(&output.stdout).chars().filter(|c| !c.is_whitespace()).collect();
+    general_purpose::STANDARD.decode(artwork).ok().filter(|bytes| !bytes.is_empty())
+}
+
+/// What the Apple Music script reports. Every value comes as text, so the
+/// script only has to know how to escape strings.
+#[cfg(any(target_os = "macos", test))]
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct AppleMusicReport {
+    state: String,
+    title: String,
+    artist: String,
+    album: String,
+    year: String,
+    duration: String,
+    position: String,
+    /// What a radio station or stream is playing right now.
+    stream: String,
+}
+
+/// Reads the JSON written by the Apple Music script. Empty output or a
+/// stopped player is nothing playing. Streams and podcasts often lack an
+/// artist, album or duration; those come out as `None`.
+#[cfg(any(target_os = "macos", test))]
+pub fn parse_apple_music(output: &str) -> Result<NowPlayingInfo, String> {
+    let output = output.trim();
+    if output.is_empty() {
+        return Ok(NowPlayingInfo::default());
+    }
+    // Control characters the script didn't escape would make the JSON invalid.
+    let cleaned: String = output.chars().map(|c| if c.is_control() { ' ' } else { c }).collect();
+    let report: AppleMusicReport =
+        serde_json::from_str(&cleaned).map_err(|e| format!("Unreadable Apple Music status: {}", e))?;
+    if report.state != "playing" && report.state != "paused" {
+        return Ok(NowPlayingInfo::default());
+    }
+
+    let text = |value: &str| Some(value.trim().to_string()).filter(|v| !v.is_empty());
+    let stream = text(&report.stream);
+    let (title, album) = match stream {
+        // For a station the track is the station; what plays is the stream title.
+        Some(stream) => (Some(stream), text(&report.album).or_else(|| text(&report.title))),
+        None => (text(&report.title), text(&report.album)),
+    };
+    Ok(NowPlayingInfo {
+        is_playing: report.state == "playing",
+        title,
+        artist: text(&report.artist),
+        album,
+        album_year: report.year.trim().parse().ok().filter(|&y| y > 0),
+        duration: parse_seconds(&report.duration).filter(|&d| d > 0.0),
+        position: parse_seconds(&report.position),
+        artwork: None,
+        artwork_url: None,
+    })
+}
+
+/// AppleScript writes reals with the locale's decimal separator.
+#[cfg(any(target_os = "macos", test))]
+fn parse_seconds(value: &str) -> Option<f64> {
+    value
+        .trim()
+        .replace(',', ".")
+        .parse::<f64>()
+        .ok()
+        .filter(|s| s.is_finite() && *s >= 0.0)
+}
+
+/// Any player implementing MPRIS (Spotify, Rhythmbox, VLC, browsers...),
+/// reached over the session bus with `busctl`. The one playing wins; failing
+/// that, the first one paused. Tracks the player has no artwork for get the
+/// album's from iTunes.
+#[cfg(target_os = "linux")]
+pub struct MprisMusic {
+    artwork: Arc<ArtworkCache>,
+    itunes: ITunesClient,
+    /// The last track's artwork, so it is read and cached once per track
+    /// rather than on every poll.
+    last_artwork: Mutex<Option<TrackArtwork>>,
+}
+
+/// Artwork found for a track, keyed by the player's track id and art URL
+/// (some players fill the URL in after the track starts).
+#[cfg(target_os = "linux")]
+struct TrackArtwork {
+    key: (String, String),
+    artwork: Option<String>,
+    artwork_url: Option<String>,
+}
+
+#[cfg(target_os = "linux")]
+const MPRIS_PREFIX: &str = "org.mpris.MediaPlayer2.";
+#[cfg(target_os = "linux")]
+const MPRIS_PATH: &str = "/org/mpris/MediaPlayer2";
+#[cfg(target_os = "linux")]
+const MPRIS_PLAYER: &str = "org.mpris.MediaPlayer2.Player";
+
+#[cfg(target_os = "linux")]
+impl MprisMusic {
+    fn new(artwork: Arc<ArtworkCache>) -> Self {
+        Self { artwork, itunes: ITunesClient::new(), last_artwork: Mutex::new(None) }
+    }
+
+    /// The player to show and control, with all its player properties.
+    fn active_player(&self) -> Option<(String, serde_json::Value)> {
+        let players: Vec<(String, serde_json::Value)> = mpris_players()
+            .into_iter()
+            .filter_map(|player| {
+                let properties = player_properties(&player)?;
+                Some((player, properties))
+            })
+            .collect();
+        ["Playing", "Paused"].iter().find_map(|wanted| {
+            players
+                .iter()
+                .find(|(_, properties)| properties["PlaybackStatus"]["data"].as_str() == Some(wanted))
+                .cloned()
+        })
+    }
+
+    /// Sends `method` to the active player, or to any player if none is.
+    /// `args` starts with the D-Bus signature of the arguments, if any.
+    fn control(&self, interface: &str, method: &str, args: &[&str]) {
+        let Some(player) = self.active_player().map(|(player, _)| player).or_else(|| mpris_players().into_iter().next()) else {
+            return;
+        };
+        let _ = Command::new("busctl")
+            .args(["--user", "call", &player, MPRIS_PATH, interface, method])
+            .args(args)
+            .output();
+    }
+
+    /// Fills in the artwork for the track, from the last poll if it's the
+    /// same track.
+    fn attach_artwork(&self, info: &mut NowPlayingInfo, track_id: Option<String>, art_url: Option<String>) {
+        let key = (track_id.unwrap_or_default(), art_url.clone().unwrap_or_default());
+        let mut last = self.last_artwork.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(last) = last.as_ref().filter(|last| last.key == key && !key.0.is_empty()) {
+            info.artwork = last.artwork.clone();
+            info.artwork_url = last.artwork_url.clone();
+            return;
+        }
+        let embedded = || art_url.and_then(|url| local_artwork(&url));
+        attach_artwork(&self.artwork, info, embedded, |artist, album| self.itunes.album_artwork(artist, album));
+        *last = Some(TrackArtwork { key, artwork: info.artwork.clone(), artwork_url: info.artwork_url.clone() });
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl MusicProvider for MprisMusic {
+    fn name(&self) -> &'static str {
+        "mpris"
+    }
+
+    fn now_playing(&self) -> NowPlayingInfo {
+        let Some((_, properties)) = self.active_player() else {
+            return NowPlayingInfo::default();
+        };
+        let (mut info, track_id, art_url) = parse_mpris(&properties);
+        self.attach_artwork(&mut info, track_id, art_url);
+        info
+    }
+
+    fn play_pause(&self) {
+        self.control(MPRIS_PLAYER, "PlayPause", &[]);
+    }
+
+    fn next_track(&self) {
+        self.control(MPRIS_PLAYER, "Next", &[]);
+    }
+
+    fn previous_track(&self) {
+        self.control(MPRIS_PLAYER, "Previous", &[]);
+    }
+
+    fn open_player(&self) {
+        self.control("org.mpris.MediaPlayer2", "Raise", &[]);
+    }
+
+    // MPRIS has no way to browse a player's library, so these just bring
+    // the player forward.
+    fn open_artist(&self, _artist: &str) {
+        self.open_player();
+    }
+
+    fn open_album(&self, _album: &str, _artist: Option<&str>) {
+        self.open_player();
+    }
+
+    fn is_playing(&self) -> bool {
+        self.active_player()
+            .is_some_and(|(_, properties)| properties["PlaybackStatus"]["data"] == "Playing")
+    }
+
+    fn play_playlist(&self, playlist: &str) {
+        self.control(MPRIS_PLAYER, "OpenUri", &["s", playlist]);
+    }
+}
+
+/// Reads a player's properties, as `busctl` prints the reply to `GetAll`,
+/// into what's playing, the track id and the art URL.
+#[cfg(target_os = "linux")]
+fn parse_mpris(properties: &serde_json::Value) -> (NowPlayingInfo, Option<String>, Option<String>) {
+    let metadata = &properties["Metadata"]["data"];
+    let field = |key: &str| metadata.get(key).map(|value| &value["data"]);
+    let text = |key: &str| field(key).and_then(|v| v.as_str()).map(str::to_string);
+
+    let info = NowPlayingInfo {
+        is_playing: properties["PlaybackStatus"]["data"] == "Playing",
+        title: text("xesam:title"),
+        // A list of artists.
+        artist: field("xesam:artist").and_then(|v| match v {
+            serde_json::Value::Array(names) => {
+                Some(names.iter().filter_map(|n| n.as_str()).collect::<Vec<_>>().join(", "))
+            }
+            other => other.as_str().map(str::to_string),
+        }),
+        album: text("xesam:album"),
+        album_year: text("xesam:contentCreated").and_then(|date| date.get(..4)?.parse().ok()),
+        // Microseconds.
+        duration: field("mpris:length").and_then(|v| v.as_f64()).map(|us| us / 1_000_000.0),
+        position: properties["Position"]["data"].as_f64().map(|us| us / 1_000_000.0),
+        artwork: None,
+        artwork_url: None,
+    };
+    (info, text("mpris:trackid"), text("mpris:artUrl"))
+}
+
+/// Bus names of the running MPRIS players.
+#[cfg(target_os = "linux")]
+fn mpris_players() -> Vec<String> {
+    let Some(reply) = busctl(&["call", "org.freedesktop.DBus", "/org/freedesktop/DBus", "org.freedesktop.DBus", "ListNames"]) else {
+        return Vec::new();
+    };
+    reply["data"][0]
+        .as_array()
+        .map(|names| {
+            names
+                .iter()
+                .filter_map(|name| name.as_str())
+                .filter(|name| name.starts_with(MPRIS_PREFIX))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Every property of the player's `Player` interface in one call, keyed by
+/// name, each value with its `type` and `data`.
+#[cfg(target_os = "linux")]
+fn player_properties(player: &str) -> Option<serde_json::Value> {
+    let args = ["call", player, MPRIS_PATH, "org.freedesktop.DBus.Properties", "GetAll", "s", MPRIS_PLAYER];
+    busctl(&args).map(|mut reply| reply["data"][0].take()).filter(|properties| properties.is_object())
+}
+
+/// Runs `busctl` on the session bus and parses its JSON output.
+#[cfg(target_os = "linux")]
+fn busctl(args: &[&str]) -> Option<serde_json::Value> {
+    let output = Command::new("busctl")
+        .args(["--user", "--json=short"])
+        .args(args)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    serde_json::from_slice(&output.stdout).ok()
+}
+
+/// Players hand out artwork as a `file://` URL, often in a temporary
+/// directory, so the image is read and cached.
+#[cfg(target_os = "linux")]
+fn local_artwork(url: &str) -> Option<Vec<u8>> {
+    let path = url::Url::parse(url).ok().filter(|u| u.scheme() == "file")?.to_file_path().ok()?;
+    std::fs::read(path).ok()
+}
+
+/// For platforms without a supported player.
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+struct NoMusic;
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+impl MusicProvider for NoMusic {
+    fn name(&self) -> &'static str {
+        "none"
+    }
+
+    fn now_playing(&self) -> NowPlayingInfo {
+        NowPlayingInfo::default()
+    }
+
+    fn play_pause(&self) {}
+
+    fn next_track(&self) {}
+
+    fn previous_track(&self) {}
+
+    fn open_player(&self) {}
+
+    fn open_artist(&self, _artist: &str) {}
+
+    fn open_album(&self, _album: &str, _artist: Option<&str>) {}
+
+    fn play_playlist(&self, _playlist: &str) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apple_music(fields: &[(&str, &str)]) -> NowPlayingInfo {
+        let report: serde_json::Map<String, serde_json::Value> =
+            fields.iter().map(|(k, v)| (k.to_string(), serde_json::Value::from(*v))).collect();
+        parse_apple_music(&serde_json::Value::Object(report).to_string()).unwrap()
+    }
+
+    #[test]
+    fn reads_a_playing_track() {
+        let info = apple_music(&[
+            ("state", "playing"),
+            ("title", "Song"),
+            ("artist", "Artist"),
+            ("album", "Album"),
+            ("year", "2019"),
+            ("duration", "215,5"),
+            ("position", "12.25"),
+            ("stream", ""),
+        ]);
+        assert_eq!(
+            info,
+            NowPlayingInfo {
+                is_playing: true,
+                title: Some("Song".to_string()),
+                artist: Some("Artist".to_string()),
+                album: Some("Album".to_string()),
+                album_year: Some(2019),
+                duration: Some(215.5),
+                position: Some(12.25),
+                artwork: None,
+                artwork_url: None,
+            }
+        );
+    }
+
+    #[test]
+    fn keeps_odd_titles_intact() {
+        let titles = [
+            "Don't Stop \"Believin'\"",
+            "C:\\Music\\",
+            "Ünïcödé — 東京 🎵",
+            "a ||| b ||| c",
+            "{\"state\":\"stopped\"}",
+            "one, two: three; four",
+        ];
+        for title in titles {
+            let info = apple_music(&[("state", "paused"), ("title", title), ("artist", "x|||y")]);
+            assert!(!info.is_playing);
+            assert_eq!(info.title.as_deref(), Some(title));
+            assert_eq!(info.artist.as_deref(), Some("x|||y"));
+        }
+        // Tabs and line breaks the script didn't escape become spaces.
+        let info = parse_apple_music("{\"state\":\"playing\",\"title\":\"Line\tone\nLine two\"}").unwrap();
+        assert_eq!(info.title.as_deref(), Some("Line one Line two"));
+    }
+
+    #[test]
+    fn missing_fields_and_artwork_are_none() {
+        let info = parse_apple_music(r#"{"state":"playing"}"#).unwrap();
+        assert_eq!(info, NowPlayingInfo { is_playing: true, ..Default::default() });
+
+        let info = apple_music(&[
+            ("state", "playing"),
+            ("title", "  "),
+            ("year", "0"),
+            ("duration", "missing value"),
+            ("position", "-1"),
+        ]);
+        assert_eq!(info, NowPlayingInfo { is_playing: true, ..Default::default() });
+    }
+
+    #[test]
+    fn radio_streams_show_what_is_playing() {
+        // A station: the track is the station and the stream title is the song.
+        let info = apple_music(&[("state", "playing"), ("title", "Radio One"), ("stream", "Artist - Song"), ("duration", "")]);
+        assert_eq!(info.title.as_deref(), Some("Artist - Song"));
+        assert_eq!(info.album.as_deref(), Some("Radio One"));
+        assert_eq!((info.artist, info.duration), (None, None));
+
+        // A podcast episode: no artist or year, but an album (the show).
+        let info = apple_music(&[("state", "playing"), ("title", "Episode 12"), ("album", "The Show"), ("duration", "3600")]);
+        assert_eq!(info.title.as_deref(), Some("Episode 12"));
+        assert_eq!(info.album.as_deref(), Some("The Show"));
+        assert_eq!((info.artist, info.album_year, info.duration), (None, None, Some(3600.0)));
+    }
+
+    #[test]
+    fn nothing_plays_when_stopped_or_silent() {
+        assert_eq!(parse_apple_music(""), Ok(NowPlayingInfo::default()));
+        assert_eq!(parse_apple_music(" \n"), Ok(NowPlayingInfo::default()));
+        assert_eq!(parse_apple_music(r#"{"state":"stopped"}"#), Ok(NowPlayingInfo::default()));
+        assert_eq!(parse_apple_music(r#"{"state":"fast forwarding","title":"x"}"#), Ok(NowPlayingInfo::default()));
+        assert!(parse_apple_music("execution error: Music got an error (-1728)").is_err());
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn the_watch_reports_track_and_play_state_changes() {
+        let music = MockMusic::default();
+        let mut watch = TrackWatch::new(&music);
+        assert_eq!(watch.poll(&music), None);
+
+        music.next_track();
+        let info = watch.poll(&music).unwrap();
+        assert_eq!(info.title.as_deref(), Some("Track 2"));
+        assert_eq!(watch.poll(&music), None);
+
+        music.play_pause();
+        assert!(!watch.poll(&music).unwrap().is_playing);
+        music.play_pause();
+        assert!(watch.poll(&music).unwrap().is_playing);
+
+        music.play_playlist("Deep Focus");
+        let info = watch.poll(&music).unwrap();
+        assert_eq!((info.title.as_deref(), info.album.as_deref()), (Some("Track 1"), Some("Deep Focus")));
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn the_watch_ignores_the_position_moving_on() {
+        let music = MockMusic::default();
+        let mut watch = TrackWatch::new(&music);
+        music.update(|info| info.position = Some(42.0));
+        assert_eq!(watch.poll(&music), None);
+        // Going back from the first track starts it over: same track.
+        music.previous_track();
+        assert_eq!(watch.poll(&music), None);
+        music.update(|info| info.artist = Some("Someone Else".to_string()));
+        assert_eq!(watch.poll(&music).unwrap().artist.as_deref(), Some("Someone Else"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn reads_mpris_properties() {
+        let properties = serde_json::json!({
+            "PlaybackStatus": {"type": "s", "data": "Playing"},
+            "Position": {"type": "x", "data": 61_500_000},
+            "Metadata": {"type": "a{sv}", "data": {
+                "mpris:trackid": {"type": "o", "data": "/org/mpris/MediaPlayer2/Track/7"},
+                "mpris:length": {"type": "x", "data": 240_000_000},
+                "mpris:artUrl": {"type": "s", "data": "file:///tmp/cover.png"},
+                "xesam:title": {"type": "s", "data": "Song"},
+                "xesam:artist": {"type": "as", "data": ["A", "B"]},
+                "xesam:album": {"type": "s", "data": "Record"},
+                "xesam:contentCreated": {"type": "s", "data": "1999-05-01"}
+            }}
+        });
+        let (info, track_id, art_url) = parse_mpris(&properties);
+        assert!(info.is_playing);
+        assert_eq!(info.title.as_deref(), Some("Song"));
+        assert_eq!(info.artist.as_deref(), Some("A, B"));
+        assert_eq!(info.album_year, Some(1999));
+        assert_eq!((info.duration, info.position), (Some(240.0), Some(61.5)));
+        assert_eq!(track_id.as_deref(), Some("/org/mpris/MediaPlayer2/Track/7"));
+        assert_eq!(art_url.as_deref(), Some("file:///tmp/cover.png"));
+
+        let (info, track_id, _) = parse_mpris(&serde_json::json!({"PlaybackStatus": {"type": "s", "data": "Paused"}}));
+        assert_eq!(info, NowPlayingInfo::default());
+        assert_eq!(track_id, None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn mpris_artwork_is_read_once_per_track() {
+        let dir = std::env::temp_dir().join(format!("growing-mpris-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cover = dir.join("cover.png");
+        std::fs::write(&cover, b"first").unwrap();
+        let url = url::Url::from_file_path(&cover).unwrap().to_string();
+        let music = MprisMusic::new(Arc::new(ArtworkCache::load(dir.join("cache"))));
+
+        let read = |track: &str| {
+            let mut info = NowPlayingInfo::default();
+            music.attach_artwork(&mut info, Some(track.to_string()), Some(url.clone()));
+            info.artwork
+        };
+        use base64::{Engine as _, engine::general_purpose};
+        assert_eq!(read("1"), Some(general_purpose::STANDARD.encode("first")));
+        std::fs::write(&cover, b"second").unwrap();
+        assert_eq!(read("1"), Some(general_purpose::STANDARD.encode("first")));
+        assert_eq!(read("2"), Some(general_purpose::STANDARD.encode("second")));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
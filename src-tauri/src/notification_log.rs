@@ -0,0 +1,283 @@
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::storage::{self, format_time, parse_time};
+
+pub const NOTIFICATION_LOG_FILE: &str = "notification_log.json";
+
+/// Records are kept for this long, and never more than `MAX_ENTRIES` of them.
+const RETENTION: Duration = Duration::days(30);
+const MAX_ENTRIES: usize = 2000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DeliveryStatus {
+    Delivered,
+    /// Notifications are not permitted for the app.
+    Denied,
+    Failed,
+    /// Held back by quiet hours, a timebox or the on-call profile.
+    Suppressed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NotificationRecord {
+    pub id: String,
+    /// What sent it: "reminder", "task", "timer", "pomodoro", "stand" or "app".
+    pub source: String,
+    #[serde(default, rename = "ruleId", skip_serializing_if = "Option::is_none")]
+    pub rule_id: Option<String>,
+    /// The scheduled occurrence a reminder was sent for.
+    #[serde(default, rename = "dueAt", skip_serializing_if = "Option::is_none")]
+    pub due_at: Option<String>,
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+    #[serde(rename = "firedAt")]
+    pub fired_at: String,
+    pub status: DeliveryStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Why a suppressed notification was held back.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// When the user acknowledged the alert.
+    #[serde(default, rename = "acknowledgedAt", skip_serializing_if = "Option::is_none")]
+    pub acknowledged_at: Option<String>,
+}
+
+impl NotificationRecord {
+    pub fn new(source: &str, title: &str, body: &str) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            source: source.to_string(),
+            rule_id: None,
+            due_at: None,
+            title: title.to_string(),
+            body: body.to_string(),
+            fired_at: format_time(Utc::now()),
+            status: DeliveryStatus::Delivered,
+            error: None,
+            reason: None,
+            acknowledged_at: None,
+        }
+    }
+
+    pub fn suppressed(mut self, reason: &str) -> Self {
+        self.status = DeliveryStatus::Suppressed;
+        self.reason = Some(reason.to_string());
+        self
+    }
+
+    pub fn for_rule(mut self, rule_id: &str) -> Self {
+        self.rule_id = Some(rule_id.to_string());
+        self
+    }
+
+    pub fn for_occurrence(mut self, rule_id: &str, due: DateTime<Utc>) -> Self {
+        self.due_at = Some(format_time(due));
+        self.for_rule(rule_id)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct LogStore {
+    #[serde(default)]
+    entries: Vec<NotificationRecord>,
+}
+
+/// Every notification the backend sends, persisted in
+/// `notification_log.json`. Reminder occurrences are looked up here before
+/// firing so a restart never sends the same reminder twice.
+pub struct NotificationLog {
+    path: PathBuf,
+    store: Mutex<LogStore>,
+}
+
+impl NotificationLog {
+    pub fn load(path: PathBuf) -> Self {
+        let store = storage::read_json(&path);
+        Self { path, store: Mutex::new(store) }
+    }
+
+    pub fn record(&self, record: NotificationRecord) {
+        let mut store = self.lock();
+        store.entries.push(record);
+
+        let cutoff = format_time(Utc::now() - RETENTION);
+        store.entries.retain(|r| r.fired_at >= cutoff);
+        if store.entries.len() > MAX_ENTRIES {
+            let excess = store.entries.len() - MAX_ENTRIES;
+            store.entries.drain(..excess);
+        }
+
+        if let Err(e) = storage::write_json(&self.path, &*store) {
+            eprintln!("Failed to save notification log: {}", e);
+        }
+    }
+
+    /// Records a notification for a reminder occurrence unless one was already
+    /// logged, so an occurrence held back again after a restart is listed
+    /// once. Returns whether it was recorded.
+    pub fn record_occurrence(&self, record: NotificationRecord) -> bool {
+        let already = match (&record.rule_id, &record.due_at) {
+            (Some(rule_id), Some(due)) => parse_time(due).is_some_and(|due| self.has_fired(rule_id, due)),
+            _ => false,
+        };
+        if !already {
+            self.record(record);
+        }
+        !already
+    }
+
+    /// Whether the occurrence of `rule_id` due at `due` was already sent.
+    /// Failed attempts don't count, so they are retried.
+    pub fn has_fired(&self, rule_id: &str, due: DateTime<Utc>) -> bool {
+        let due = format_time(due);
+        self.lock().entries.iter().rev().any(|r| {
+            r.rule_id.as_deref() == Some(rule_id)
+                && r.due_at.as_deref() == Some(due.as_str())
+                && r.status != DeliveryStatus::Failed
+        })
+    }
+
+    /// Marks the latest delivered notification for `rule_id` as acknowledged.
+    /// Returns whether there was one.
+    pub fn acknowledge(&self, rule_id: &str) -> bool {
+        let mut store = self.lock();
+        let Some(record) = store
+            .entries
+            .iter_mut()
+            .rev()
+            .find(|r| r.rule_id.as_deref() == Some(rule_id) && r.status == DeliveryStatus::Delivered)
+        else {
+            return false;
+        };
+        record.acknowledged_at = Some(format_time(Utc::now()));
+        if let Err(e) = storage::write_json(&self.path, &*store) {
+            eprintln!("Failed to save notification log: {}", e);
+        }
+        true
+    }
+
+    /// Newest first, optionally limited to one source or rule.
+    pub fn entries(&self, source: Option<&str>, rule_id: Option<&str>, limit: usize) -> Vec<NotificationRecord> {
+        self.lock()
+            .entries
+            .iter()
+            .rev()
+            .filter(|r| source.is_none_or(|s| r.source == s))
+            .filter(|r| rule_id.is_none_or(|id| r.rule_id.as_deref() == Some(id)))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LogStore> {
+        self.store.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log() -> (NotificationLog, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("growing-notification-log-{}", Uuid::new_v4()));
+        (NotificationLog::load(dir.join(NOTIFICATION_LOG_FILE)), dir)
+    }
+
+    fn due() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-03-02T09:00:00Z").unwrap().with_timezone(&Utc)
+    }
+
+    fn with_status(status: DeliveryStatus) -> NotificationRecord {
+        let mut record = NotificationRecord::new("reminder", "Stretch", "").for_occurrence("r", due());
+        record.status = status;
+        record
+    }
+
+    #[test]
+    fn an_occurrence_counts_as_fired_unless_it_failed() {
+        let (log, dir) = log();
+        assert!(!log.has_fired("r", due()));
+        log.record(with_status(DeliveryStatus::Failed));
+        assert!(!log.has_fired("r", due()));
+
+        for status in [DeliveryStatus::Delivered, DeliveryStatus::Denied, DeliveryStatus::Suppressed] {
+            let (log, dir) = self::log();
+            log.record(with_status(status));
+            assert!(log.has_fired("r", due()), "{:?}", status);
+            assert!(!log.has_fired("r", due() + Duration::days(1)));
+            assert!(!log.has_fired("other", due()));
+            let _ = std::fs::remove_dir_all(dir);
+        }
+
+        // The log survives a restart, which is what stops a second send.
+        log.record(with_status(DeliveryStatus::Delivered));
+        assert!(NotificationLog::load(dir.join(NOTIFICATION_LOG_FILE)).has_fired("r", due()));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn a_suppressed_occurrence_is_logged_once() {
+        let (log, dir) = log();
+        assert!(log.record_occurrence(with_status(DeliveryStatus::Failed)));
+        assert!(log.record_occurrence(NotificationRecord::new("reminder", "Stretch", "").for_occurrence("r", due()).suppressed("quiet hours")));
+        assert!(!log.record_occurrence(NotificationRecord::new("reminder", "Stretch", "").for_occurrence("r", due()).suppressed("quiet hours")));
+        // Records that aren't for an occurrence are always kept.
+        assert!(log.record_occurrence(NotificationRecord::new("reminder", "Stretch", "").for_rule("r")));
+        assert!(log.record_occurrence(NotificationRecord::new("reminder", "Stretch", "").for_rule("r")));
+        assert_eq!(log.entries(None, Some("r"), 10).len(), 4);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn old_and_excess_records_are_dropped() {
+        let (_, dir) = log();
+        let path = dir.join(NOTIFICATION_LOG_FILE);
+        let at = |age: Duration, title: &str| NotificationRecord {
+            fired_at: format_time(Utc::now() - age),
+            ..NotificationRecord::new("app", title, "")
+        };
+        let mut entries = vec![at(RETENTION + Duration::hours(1), "expired")];
+        entries.extend((0..MAX_ENTRIES).map(|i| at(Duration::minutes(1), &i.to_string())));
+        storage::write_json(&path, &LogStore { entries }).unwrap();
+
+        let log = NotificationLog::load(path.clone());
+        log.record(at(RETENTION + Duration::minutes(1), "late"));
+        log.record(at(Duration::zero(), "latest"));
+        let entries = log.entries(None, None, usize::MAX);
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries[0].title, "latest");
+        // The oldest recent record made room for the new one.
+        assert_eq!(entries[MAX_ENTRIES - 1].title, "1");
+        assert!(entries.iter().all(|r| r.title != "expired" && r.title != "late"));
+        assert_eq!(NotificationLog::load(path).entries(None, None, usize::MAX).len(), MAX_ENTRIES);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn acknowledging_marks_the_latest_delivered_record() {
+        let (log, dir) = log();
+        assert!(!log.acknowledge("r"));
+        log.record(NotificationRecord::new("reminder", "first", "").for_rule("r"));
+        log.record(NotificationRecord::new("reminder", "second", "").for_rule("r"));
+        log.record(NotificationRecord::new("reminder", "held", "").for_rule("r").suppressed("quiet hours"));
+        assert!(log.acknowledge("r"));
+
+        let acknowledged: Vec<(String, bool)> = log
+            .entries(Some("reminder"), Some("r"), 10)
+            .into_iter()
+            .map(|r| (r.title, r.acknowledged_at.is_some()))
+            .collect();
+        let expected = [("held", false), ("second", true), ("first", false)];
+        assert_eq!(acknowledged, expected.map(|(title, ack)| (title.to_string(), ack)));
+        assert!(!log.acknowledge("other"));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
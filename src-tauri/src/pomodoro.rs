@@ -0,0 +1,572 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{ActiveTimer, PomodoroSettings};
+use crate::storage::{self, format_time, parse_time};
+use crate::timers::{TimerEvent, TimerService};
+use crate::tz;
+
+pub const POMODORO_FILE: &str = "pomodoro.json";
+pub const TIMER_TYPE: &str = "pomodoro";
+
+const MAX_LOG_ENTRIES: usize = 5000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Phase {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+impl Phase {
+    fn as_str(self) -> &'static str {
+        match self {
+            Phase::Work => "work",
+            Phase::ShortBreak => "shortBreak",
+            Phase::LongBreak => "longBreak",
+        }
+    }
+
+    pub fn minutes(self, settings: &PomodoroSettings) -> u32 {
+        match self {
+            Phase::Work => settings.work_minutes,
+            Phase::ShortBreak => settings.short_break_minutes,
+            Phase::LongBreak => settings.long_break_minutes,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PomodoroSession {
+    pub id: String,
+    #[serde(default, rename = "taskId", skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    #[serde(default, rename = "taskName", skip_serializing_if = "Option::is_none")]
+    pub task_name: Option<String>,
+    pub phase: Phase,
+    /// Work phases finished in the current set.
+    #[serde(rename = "completedWork")]
+    pub completed_work: u32,
+    pub settings: PomodoroSettings,
+    /// Timer running the current phase; `None` while waiting for the user to
+    /// start the next phase (auto-advance off).
+    #[serde(default, rename = "timerId", skip_serializing_if = "Option::is_none")]
+    pub timer_id: Option<String>,
+    #[serde(rename = "phaseStartedAt")]
+    pub phase_started_at: String,
+    #[serde(rename = "startedAt")]
+    pub started_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PomodoroLogEntry {
+    pub id: String,
+    #[serde(rename = "sessionId")]
+    pub session_id: String,
+    pub phase: Phase,
+    #[serde(default, rename = "taskId", skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    #[serde(rename = "startedAt")]
+    pub started_at: String,
+    #[serde(rename = "endedAt")]
+    pub ended_at: String,
+    #[serde(rename = "plannedMinutes")]
+    pub planned_minutes: u32,
+    /// False when the phase was skipped or the session stopped early.
+    pub completed: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct PomodoroStore {
+    #[serde(default)]
+    session: Option<PomodoroSession>,
+    #[serde(default)]
+    log: Vec<PomodoroLogEntry>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct DailyFocusStats {
+    pub date: String,
+    #[serde(rename = "focusMinutes")]
+    pub focus_minutes: i64,
+    #[serde(rename = "breakMinutes")]
+    pub break_minutes: i64,
+    #[serde(rename = "completedPomodoros")]
+    pub completed_pomodoros: u32,
+    #[serde(rename = "interruptedPomodoros")]
+    pub interrupted_pomodoros: u32,
+}
+
+/// What happened to the session after a timer event, for notifications.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PhaseChange {
+    Started { session: PomodoroSession },
+    Waiting { session: PomodoroSession, next: Phase },
+    Finished { session: PomodoroSession },
+    Stopped { session: PomodoroSession },
+}
+
+impl PhaseChange {
+    pub fn session(&self) -> &PomodoroSession {
+        match self {
+            PhaseChange::Started { session }
+            | PhaseChange::Waiting { session, .. }
+            | PhaseChange::Finished { session }
+            | PhaseChange::Stopped { session } => session,
+        }
+    }
+}
+
+/// Pomodoro sessions on top of the timer service: each phase runs as an
+/// `ActiveTimer` of type `pomodoro`, and the engine advances the session when
+/// that timer expires. The current session and the phase log are persisted in
+/// `pomodoro.json`.
+///
+/// The engine never holds its own lock while calling into the timer service,
+/// since timer changes call straight back into `on_timer_event`.
+pub struct PomodoroEngine {
+    path: PathBuf,
+    store: Mutex<PomodoroStore>,
+}
+
+impl PomodoroEngine {
+    pub fn load(path: PathBuf) -> Self {
+        let store = storage::read_json(&path);
+        Self { path, store: Mutex::new(store) }
+    }
+
+    pub fn status(&self) -> Option<PomodoroSession> {
+        self.lock().session.clone()
+    }
+
+    pub fn start(
+        &self,
+        timers: &TimerService,
+        settings: &PomodoroSettings,
+        task: Option<(String, String)>,
+    ) -> Result<PomodoroSession, String> {
+        let settings = validate(settings)?;
+        if self.lock().session.is_some() {
+            return Err("A pomodoro session is already running".to_string());
+        }
+
+        let now = Utc::now();
+        let (task_id, task_name) = task.unzip();
+        let mut session = PomodoroSession {
+            id: Uuid::new_v4().to_string(),
+            task_id,
+            task_name,
+            phase: Phase::Work,
+            completed_work: 0,
+            settings,
+            timer_id: None,
+            phase_started_at: format_time(now),
+            started_at: format_time(now),
+        };
+        let timer = phase_timer(&session, now);
+        session.timer_id = Some(timer.id.clone());
+
+        self.update(|store| store.session = Some(session.clone()));
+        timers.add(timer);
+        Ok(session)
+    }
+
+    /// Ends the session, logging the current phase as interrupted.
+    pub fn stop(&self, timers: &TimerService) -> Result<PomodoroSession, String> {
+        let session = self
+            .update(|store| {
+                let session = store.session.take()?;
+                if session.timer_id.is_some() {
+                    push_log(store, &session, Utc::now(), false);
+                }
+                Some(session)
+            })
+            .ok_or("No pomodoro session is running")?;
+
+        if let Some(timer_id) = &session.timer_id {
+            let _ = timers.cancel(Some(timer_id));
+        }
+        Ok(session)
+    }
+
+    /// Ends the current phase early and moves on to the next one.
+    pub fn skip(&self, timers: &TimerService) -> Result<PhaseChange, String> {
+        let timer_id = self.lock().session.as_ref().map(|s| s.timer_id.clone());
+        match timer_id {
+            None => Err("No pomodoro session is running".to_string()),
+            Some(None) => Err("The next phase hasn't started yet".to_string()),
+            Some(Some(timer_id)) => self
+                .finish_phase(timers, &timer_id, false, true)
+                .ok_or_else(|| "No pomodoro session is running".to_string()),
+        }
+    }
+
+    /// Starts the next phase of a session waiting on the user.
+    pub fn advance(&self, timers: &TimerService) -> Result<PhaseChange, String> {
+        let waiting = self.lock().session.as_ref().map(|s| s.timer_id.is_none());
+        match waiting {
+            None => Err("No pomodoro session is running".to_string()),
+            Some(false) => Err("The current phase is still running".to_string()),
+            Some(true) => self
+                .start_waiting_phase(timers)
+                .ok_or_else(|| "No pomodoro session is running".to_string()),
+        }
+    }
+
+    pub fn on_timer_event(&self, timers: &TimerService, event: &TimerEvent) -> Option<PhaseChange> {
+        match event {
+            TimerEvent::Expired { timer, .. } if self.is_current(timer) => {
+                let auto_advance = self.lock().session.as_ref()?.settings.auto_advance;
+                self.finish_phase(timers, &timer.id, true, auto_advance)
+            }
+            TimerEvent::Cancelled { timer } if self.is_current(timer) => {
+                let session = self.update(|store| {
+                    let session = store.session.take()?;
+                    push_log(store, &session, Utc::now(), false);
+                    Some(session)
+                })?;
+                Some(PhaseChange::Stopped { session })
+            }
+            _ => None,
+        }
+    }
+
+    /// Focus statistics per local day, oldest first, for `from..=to`.
+    pub fn stats(&self, from: NaiveDate, to: NaiveDate) -> Vec<DailyFocusStats> {
+        let mut days: BTreeMap<NaiveDate, DailyFocusStats> = BTreeMap::new();
+        let mut day = from;
+        while day <= to {
+            days.insert(day, DailyFocusStats { date: day.format("%Y-%m-%d").to_string(), ..Default::default() });
+            day += Duration::days(1);
+        }
+
+        let zone = tz::zone();
+        for entry in &self.lock().log {
+            let (Some(start), Some(end)) = (parse_time(&entry.started_at), parse_time(&entry.ended_at)) else {
+                continue;
+            };
+            let Some(stats) = days.get_mut(&start.with_timezone(&zone).date_naive()) else {
+                continue;
+            };
+            let minutes = (end - start).num_minutes().max(0);
+            match (entry.phase, entry.completed) {
+                (Phase::Work, true) => {
+                    stats.focus_minutes += minutes;
+                    stats.completed_pomodoros += 1;
+                }
+                (Phase::Work, false) => {
+                    stats.focus_minutes += minutes;
+                    stats.interrupted_pomodoros += 1;
+                }
+                _ => stats.break_minutes += minutes,
+            }
+        }
+
+        days.into_values().collect()
+    }
+
+    /// Logs the phase that just ended and either starts the next phase or
+    /// leaves the session waiting for `advance`.
+    fn finish_phase(&self, timers: &TimerService, timer_id: &str, completed: bool, start_next: bool) -> Option<PhaseChange> {
+        let now = Utc::now();
+        let change = self.update(|store| {
+            let mut session = store.session.take()?;
+            push_log(store, &session, now, completed);
+            if session.phase == Phase::Work {
+                session.completed_work += 1;
+            }
+
+            let Some(next) = next_phase(&session) else {
+                return Some(PhaseChange::Finished { session });
+            };
+            session.phase = next;
+            session.timer_id = None;
+            session.phase_started_at = format_time(now);
+            store.session = Some(session.clone());
+            Some(PhaseChange::Waiting { session, next })
+        });
+
+        // The finished phase's timer is done with either way.
+        let _ = timers.cancel(Some(timer_id));
+
+        match change? {
+            PhaseChange::Waiting { .. } if start_next => self.start_waiting_phase(timers),
+            change => Some(change),
+        }
+    }
+
+    fn start_waiting_phase(&self, timers: &TimerService) -> Option<PhaseChange> {
+        let now = Utc::now();
+        let started = self.update(|store| {
+            let session = store.session.as_mut()?;
+            let timer = phase_timer(session, now);
+            session.timer_id = Some(timer.id.clone());
+            session.phase_started_at = format_time(now);
+            Some((session.clone(), timer))
+        });
+
+        let (session, timer) = started?;
+        timers.add(timer);
+        Some(PhaseChange::Started { session })
+    }
+
+    fn is_current(&self, timer: &ActiveTimer) -> bool {
+        timer.timer_type == TIMER_TYPE
+            && self
+                .lock()
+                .session
+                .as_ref()
+                .is_some_and(|s| s.timer_id.as_deref() == Some(timer.id.as_str()))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, PomodoroStore> {
+        self.store.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn update<T>(&self, f: impl FnOnce(&mut PomodoroStore) -> T) -> T {
+        let mut store = self.lock();
+        let result = f(&mut store);
+        if let Err(e) = storage::write_json(&self.path, &*store) {
+            eprintln!("Failed to save pomodoro state: {}", e);
+        }
+        result
+    }
+}
+
+fn next_phase(session: &PomodoroSession) -> Option<Phase> {
+    match session.phase {
+        Phase::Work if session.completed_work >= session.settings.cycles => Some(Phase::LongBreak),
+        Phase::Work => Some(Phase::ShortBreak),
+        Phase::ShortBreak => Some(Phase::Work),
+        Phase::LongBreak => None,
+    }
+}
+
+fn phase_timer(session: &PomodoroSession, now: DateTime<Utc>) -> ActiveTimer {
+    let minutes = session.phase.minutes(&session.settings);
+    let label = match session.phase {
+        Phase::Work => session.task_name.clone().unwrap_or_else(|| "Pomodoro".to_string()),
+        Phase::ShortBreak => "Short break".to_string(),
+        Phase::LongBreak => "Long break".to_string(),
+    };
+    ActiveTimer {
+        id: Uuid::new_v4().to_string(),
+        timer_type: TIMER_TYPE.to_string(),
+        task_id: session.task_id.clone().filter(|_| session.phase == Phase::Work),
+        task_name: Some(label),
+        end_time: format_time(now + Duration::minutes(minutes as i64)),
+        duration_minutes: minutes as i32,
+        started_at: Some(format_time(now)),
+        session_id: Some(session.id.clone()),
+        pomodoro_phase: Some(session.phase.as_str().to_string()),
+        ..Default::default()
+    }
+}
+
+fn push_log(store: &mut PomodoroStore, session: &PomodoroSession, ended_at: DateTime<Utc>, completed: bool) {
+    store.log.push(PomodoroLogEntry {
+        id: Uuid::new_v4().to_string(),
+        session_id: session.id.clone(),
+        phase: session.phase,
+        task_id: session.task_id.clone(),
+        started_at: session.phase_started_at.clone(),
+        ended_at: format_time(ended_at),
+        planned_minutes: session.phase.minutes(&session.settings),
+        completed,
+    });
+    if store.log.len() > MAX_LOG_ENTRIES {
+        let excess = store.log.len() - MAX_LOG_ENTRIES;
+        store.log.drain(..excess);
+    }
+}
+
+fn validate(settings: &PomodoroSettings) -> Result<PomodoroSettings, String> {
+    for (name, minutes) in [
+        ("Work", settings.work_minutes),
+        ("Short break", settings.short_break_minutes),
+        ("Long break", settings.long_break_minutes),
+    ] {
+        if !(1..=240).contains(&minutes) {
+            return Err(format!("{} length must be between 1 and 240 minutes", name));
+        }
+    }
+    if !(1..=12).contains(&settings.cycles) {
+        return Err("Cycles must be between 1 and 12".to_string());
+    }
+    Ok(settings.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::SystemClock;
+    use std::sync::Arc;
+
+    fn engine() -> (PomodoroEngine, TimerService, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("growing-pomodoro-{}", Uuid::new_v4()));
+        let timers = TimerService::new(Arc::new(SystemClock), Vec::new(), |_| {});
+        (PomodoroEngine::load(dir.join(POMODORO_FILE)), timers, dir)
+    }
+
+    fn settings(cycles: u32, auto_advance: bool) -> PomodoroSettings {
+        PomodoroSettings { cycles, auto_advance, ..Default::default() }
+    }
+
+    /// Lets the current phase's timer run out.
+    fn expire(engine: &PomodoroEngine, timers: &TimerService) -> Option<PhaseChange> {
+        let timer_id = engine.status()?.timer_id?;
+        let timer = timers.list().into_iter().find(|t| t.id == timer_id)?;
+        engine.on_timer_event(timers, &TimerEvent::Expired { timer, late: false })
+    }
+
+    fn phases(engine: &PomodoroEngine) -> Vec<(Phase, bool)> {
+        engine.lock().log.iter().map(|entry| (entry.phase, entry.completed)).collect()
+    }
+
+    #[test]
+    fn a_set_ends_with_a_long_break() {
+        let (engine, timers, dir) = engine();
+        let session = engine.start(&timers, &settings(2, true), Some(("t".to_string(), "Write".to_string()))).unwrap();
+        assert_eq!(session.phase, Phase::Work);
+        assert_eq!(timers.list()[0].task_name.as_deref(), Some("Write"));
+        assert!(engine.start(&timers, &settings(2, true), None).is_err());
+
+        let mut seen = Vec::new();
+        while let Some(change) = expire(&engine, &timers) {
+            let session = change.session();
+            seen.push((session.phase, session.completed_work, matches!(change, PhaseChange::Finished { .. })));
+        }
+        assert_eq!(
+            seen,
+            [
+                (Phase::ShortBreak, 1, false),
+                (Phase::Work, 1, false),
+                (Phase::LongBreak, 2, false),
+                (Phase::LongBreak, 2, true),
+            ]
+        );
+        assert!(engine.status().is_none());
+        assert!(timers.list().is_empty());
+        let all_done = [(Phase::Work, true), (Phase::ShortBreak, true), (Phase::Work, true), (Phase::LongBreak, true)];
+        assert_eq!(phases(&engine), all_done);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn breaks_get_their_own_timer_and_work_keeps_the_task() {
+        let (engine, timers, dir) = engine();
+        engine.start(&timers, &settings(4, true), Some(("t".to_string(), "Write".to_string()))).unwrap();
+        expire(&engine, &timers);
+        let timer = &timers.list()[0];
+        assert_eq!(timer.pomodoro_phase.as_deref(), Some("shortBreak"));
+        assert_eq!((timer.task_id.as_deref(), timer.task_name.as_deref()), (None, Some("Short break")));
+        assert_eq!(timer.duration_minutes, 5);
+        expire(&engine, &timers);
+        assert_eq!(timers.list()[0].task_id.as_deref(), Some("t"));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn without_auto_advance_the_next_phase_waits() {
+        let (engine, timers, dir) = engine();
+        engine.start(&timers, &settings(1, false), None).unwrap();
+        match expire(&engine, &timers) {
+            Some(PhaseChange::Waiting { next: Phase::LongBreak, session }) => assert_eq!(session.timer_id, None),
+            other => panic!("unexpected change {:?}", other),
+        }
+        assert!(timers.list().is_empty());
+        assert_eq!(engine.skip(&timers).unwrap_err(), "The next phase hasn't started yet");
+
+        assert!(matches!(engine.advance(&timers), Ok(PhaseChange::Started { .. })));
+        assert_eq!(engine.advance(&timers).unwrap_err(), "The current phase is still running");
+        // The last phase ends the session rather than waiting.
+        assert!(matches!(expire(&engine, &timers), Some(PhaseChange::Finished { .. })));
+        assert_eq!(engine.advance(&timers).unwrap_err(), "No pomodoro session is running");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn skipping_and_stopping_log_the_phase_as_unfinished() {
+        let (engine, timers, dir) = engine();
+        engine.start(&timers, &settings(4, true), None).unwrap();
+        assert!(matches!(engine.skip(&timers), Ok(PhaseChange::Started { session }) if session.phase == Phase::ShortBreak));
+        engine.stop(&timers).unwrap();
+        assert_eq!(phases(&engine), [(Phase::Work, false), (Phase::ShortBreak, false)]);
+        assert!(timers.list().is_empty());
+        assert_eq!(engine.stop(&timers).unwrap_err(), "No pomodoro session is running");
+
+        // Cancelling the phase's timer elsewhere stops the session too.
+        engine.start(&timers, &settings(4, true), None).unwrap();
+        let timer = timers.list().remove(0);
+        assert!(matches!(engine.on_timer_event(&timers, &TimerEvent::Cancelled { timer }), Some(PhaseChange::Stopped { .. })));
+        assert!(engine.status().is_none());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn settings_are_validated() {
+        let (engine, timers, _dir) = engine();
+        let bad = [
+            PomodoroSettings { work_minutes: 0, ..Default::default() },
+            PomodoroSettings { long_break_minutes: 241, ..Default::default() },
+            settings(0, true),
+            settings(13, true),
+        ];
+        for settings in &bad {
+            assert!(engine.start(&timers, settings, None).is_err());
+        }
+        assert!(engine.status().is_none());
+    }
+
+    #[test]
+    fn stats_add_up_each_day_from_the_log() {
+        let (_, _, dir) = engine();
+        let entry = |phase, start: &str, end: &str, completed| PomodoroLogEntry {
+            id: Uuid::new_v4().to_string(),
+            session_id: "s".to_string(),
+            phase,
+            task_id: None,
+            started_at: format!("2026-03-{}Z", start),
+            ended_at: format!("2026-03-{}Z", end),
+            planned_minutes: 25,
+            completed,
+        };
+        let store = PomodoroStore {
+            session: None,
+            log: vec![
+                entry(Phase::Work, "02T12:00:00", "02T12:25:00", true),
+                entry(Phase::ShortBreak, "02T12:25:00", "02T12:30:00", true),
+                entry(Phase::Work, "02T12:30:00", "02T12:40:30", false),
+                entry(Phase::LongBreak, "02T13:00:00", "02T13:15:00", false),
+                entry(Phase::Work, "03T12:00:00", "03T12:25:00", true),
+                entry(Phase::Work, "06T12:00:00", "06T12:25:00", true),
+                entry(Phase::Work, "02Tlater", "02T13:00:00", true),
+            ],
+        };
+        storage::write_json(&dir.join(POMODORO_FILE), &store).unwrap();
+        let engine = PomodoroEngine::load(dir.join(POMODORO_FILE));
+
+        let day = |d| NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+        let stats: Vec<(String, i64, i64, u32, u32)> = engine
+            .stats(day(2), day(4))
+            .into_iter()
+            .map(|s| (s.date, s.focus_minutes, s.break_minutes, s.completed_pomodoros, s.interrupted_pomodoros))
+            .collect();
+        assert_eq!(
+            stats,
+            [
+                ("2026-03-02".to_string(), 35, 20, 1, 1),
+                ("2026-03-03".to_string(), 25, 0, 1, 0),
+                ("2026-03-04".to_string(), 0, 0, 0, 0),
+            ]
+        );
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,482 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+use uuid::Uuid;
+
+use crate::actions;
+use crate::models::{AppData, DailyTask, TaskTemplate};
+use crate::schedule::Schedule;
+use crate::storage::parse_time;
+use crate::tz;
+
+/// Occurrences are generated this many days ahead, so they show among the
+/// upcoming tasks.
+const HORIZON_DAYS: i64 = 7;
+
+/// Longest gap allowed between an occurrence being done and the next one.
+const MAX_DAYS_AFTER_COMPLETION: u32 = 365;
+
+enum Occurrence {
+    /// Not done yet; an after-completion series waits for it.
+    Open,
+    /// Done on this day.
+    Completed(NaiveDate),
+    /// Deleted, which skips it.
+    Gone,
+}
+
+pub fn validate(template: &TaskTemplate) -> Result<(), String> {
+    if template.text.trim().is_empty() {
+        return Err("Task text must not be empty".to_string());
+    }
+    if !matches!(template.category.as_str(), "work" | "personal") {
+        return Err(format!("Unknown category: {}", template.category));
+    }
+    parse_date(&template.start_date)
+        .ok_or_else(|| format!("Invalid start date (expected YYYY-MM-DD): {}", template.start_date))?;
+    if let Some(time) = template.remind_time.as_deref() {
+        NaiveTime::parse_from_str(time, "%H:%M")
+            .map_err(|_| format!("Invalid reminder time (expected HH:MM): {}", time))?;
+    }
+    match template.every_days_after_completion {
+        Some(days) if !(1..=MAX_DAYS_AFTER_COMPLETION).contains(&days) => Err(format!(
+            "Repeat between 1 and {} days after completion, got {}",
+            MAX_DAYS_AFTER_COMPLETION, days
+        )),
+        Some(_) => Ok(()),
+        None if template.rule.trim().is_empty() => {
+            Err("A recurring task needs a rule or a number of days after completion".to_string())
+        }
+        None => Schedule::parse(&template.rule).map(|_| ()),
+    }
+}
+
+/// Generates the occurrences of every recurring task due between `today`
+/// and the horizon that haven't been generated yet. Returns how many were
+/// added. Running it again adds nothing until the day changes or a series
+/// is edited.
+pub fn materialize(data: &mut AppData, today: NaiveDate) -> usize {
+    let mut added = 0;
+    for index in 0..data.task_templates.len() {
+        let template = &data.task_templates[index];
+        let (dates, through) = match due_dates(template, &data.daily_tasks, today) {
+            Ok(due) => due,
+            Err(e) => {
+                eprintln!("Skipping recurring task {}: {}", template.text, e);
+                continue;
+            }
+        };
+        // A day whose occurrence survived a change of schedule keeps it.
+        let tasks: Vec<DailyTask> = dates
+            .iter()
+            .map(|date| occurrence(template, *date))
+            .filter(|task| {
+                !data.daily_tasks.iter().any(|t| t.template_id == task.template_id && t.occurrence == task.occurrence)
+            })
+            .collect();
+        if let Some(through) = through {
+            data.task_templates[index].generated_through = Some(format_date(through));
+        }
+        for mut task in tasks {
+            task.order = Some(next_order(data, &task.date));
+            data.daily_tasks.push(task);
+            added += 1;
+        }
+    }
+    added
+}
+
+/// The days to generate occurrences of `template` for now, and the new
+/// `generatedThrough`, if it moves.
+fn due_dates(
+    template: &TaskTemplate,
+    tasks: &[DailyTask],
+    today: NaiveDate,
+) -> Result<(Vec<NaiveDate>, Option<NaiveDate>), String> {
+    let start = parse_date(&template.start_date).ok_or_else(|| format!("Invalid start date {}", template.start_date))?;
+    let generated = template.generated_through.as_deref().and_then(parse_date);
+    let horizon = today + Duration::days(HORIZON_DAYS);
+
+    if let Some(days) = template.every_days_after_completion {
+        let next = match generated {
+            None => start,
+            Some(last) => match last_occurrence(template, tasks, last) {
+                Occurrence::Open => return Ok((Vec::new(), None)),
+                Occurrence::Completed(done) => (done + Duration::days(days as i64)).max(last + Duration::days(1)),
+                Occurrence::Gone => last + Duration::days(days as i64),
+            },
+        };
+        let mut next = next.max(today);
+        if template.skip_weekends {
+            while is_weekend(next) {
+                next += Duration::days(1);
+            }
+        }
+        return Ok(if next <= horizon { (vec![next], Some(next)) } else { (Vec::new(), None) });
+    }
+
+    let schedule = Schedule::parse(&template.rule)?;
+    let schedule = schedule.anchored(Some(start));
+    let mut from = start.max(today);
+    if let Some(last) = generated {
+        from = from.max(last + Duration::days(1));
+    }
+    if from > horizon {
+        return Ok((Vec::new(), None));
+    }
+    let dates = from
+        .iter_days()
+        .take_while(|date| *date <= horizon)
+        .filter(|date| schedule.occurs_on(*date) && !(template.skip_weekends && is_weekend(*date)))
+        .collect();
+    Ok((dates, Some(horizon)))
+}
+
+/// What became of the occurrence scheduled for `day`, including any copies
+/// carried forward to a later day.
+fn last_occurrence(template: &TaskTemplate, tasks: &[DailyTask], day: NaiveDate) -> Occurrence {
+    let day = format_date(day);
+    let zone = tz::zone();
+    let copies: Vec<&DailyTask> = tasks
+        .iter()
+        .filter(|t| t.template_id.as_deref() == Some(template.id.as_str()) && t.occurrence.as_deref() == Some(day.as_str()))
+        .collect();
+    let completed = copies.iter().filter(|t| t.completed).filter_map(|t| {
+        let done = t.completed_at.as_deref().and_then(parse_time);
+        done.map(|at| at.with_timezone(&zone).date_naive()).or_else(|| parse_date(&t.date))
+    });
+    if let Some(done) = completed.max() {
+        return Occurrence::Completed(done);
+    }
+    if copies.iter().any(|t| t.moved_to_date.is_none()) {
+        return Occurrence::Open;
+    }
+    Occurrence::Gone
+}
+
+fn occurrence(template: &TaskTemplate, date: NaiveDate) -> DailyTask {
+    let date = format_date(date);
+    DailyTask {
+        id: Uuid::new_v4().to_string(),
+        text: template.text.clone(),
+        description: template.description.clone(),
+        goal_id: template.goal_id.clone(),
+        category: template.category.clone(),
+        timebox_minutes: template.timebox_minutes,
+        remind_at: remind_at(template, &date),
+        template_id: Some(template.id.clone()),
+        occurrence: Some(date.clone()),
+        date,
+        ..Default::default()
+    }
+}
+
+/// Generated occurrences go to the end of their day.
+fn next_order(data: &AppData, date: &str) -> i32 {
+    data.daily_tasks
+        .iter()
+        .filter(|t| t.date == date)
+        .filter_map(|t| t.order)
+        .max()
+        .map_or(0, |order| order + 1)
+}
+
+/// Wall-clock reminder on the occurrence's day, as the task editor writes it.
+fn remind_at(template: &TaskTemplate, date: &str) -> Option<String> {
+    template.remind_time.as_ref().map(|time| format!("{}T{}", date, time))
+}
+
+/// Saves `template` as a new series, or as the new version of an existing
+/// one, and generates what is due. A new series can start from an existing
+/// task `task_id`, which becomes its first occurrence.
+///
+/// Editing the series updates its open occurrences from today on, except for
+/// fields edited on the occurrence itself. If the days it repeats on change,
+/// the occurrences after today that weren't edited or moved are generated
+/// again.
+pub fn save_template(
+    data: &mut AppData,
+    mut template: TaskTemplate,
+    task_id: Option<&str>,
+    today: NaiveDate,
+) -> Result<TaskTemplate, String> {
+    template.text = template.text.trim().to_string();
+    template.description = template.description.trim().to_string();
+    validate(&template)?;
+
+    let existing = data.task_templates.iter().position(|t| !template.id.is_empty() && t.id == template.id);
+    match existing {
+        Some(index) => {
+            let old = data.task_templates[index].clone();
+            template.generated_through = old.generated_through.clone();
+            let today_date = format_date(today);
+            for task in data.daily_tasks.iter_mut().filter(|t| is_open_occurrence(t, &template.id)) {
+                if task.date >= today_date {
+                    apply_edit(task, &old, &template);
+                }
+            }
+
+            let rescheduled = old.rule != template.rule
+                || old.skip_weekends != template.skip_weekends
+                || old.every_days_after_completion != template.every_days_after_completion
+                || old.start_date != template.start_date;
+            if rescheduled {
+                data.daily_tasks.retain(|t| {
+                    !(is_open_occurrence(t, &template.id)
+                        && t.date > today_date
+                        && t.occurrence.as_deref() == Some(t.date.as_str())
+                        && !is_edited(t, &template))
+                });
+                if template.generated_through.as_deref().is_some_and(|through| through > today_date.as_str()) {
+                    template.generated_through = Some(today_date);
+                }
+            }
+            data.task_templates[index] = template.clone();
+        }
+        None => {
+            template.id = Uuid::new_v4().to_string();
+            template.generated_through = None;
+            if let Some(task_id) = task_id {
+                let index = actions::find_task(data, task_id)?;
+                let task = &mut data.daily_tasks[index];
+                task.template_id = Some(template.id.clone());
+                task.occurrence = Some(task.date.clone());
+                template.generated_through = Some(task.date.clone());
+            }
+            data.task_templates.push(template.clone());
+        }
+    }
+
+    materialize(data, today);
+    Ok(data.task_templates.iter().find(|t| t.id == template.id).cloned().unwrap_or(template))
+}
+
+/// Ends a series. Its open occurrences after today are deleted, apart from
+/// `keep_task_id`; the rest stay as ordinary tasks.
+pub fn delete_template(data: &mut AppData, id: &str, keep_task_id: Option<&str>, today: NaiveDate) -> Result<(), String> {
+    let index = data
+        .task_templates
+        .iter()
+        .position(|t| t.id == id)
+        .ok_or_else(|| format!("No recurring task with id {}", id))?;
+    data.task_templates.remove(index);
+
+    let today = format_date(today);
+    data.daily_tasks
+        .retain(|t| !(is_open_occurrence(t, id) && t.date > today && Some(t.id.as_str()) != keep_task_id));
+    for task in data.daily_tasks.iter_mut().filter(|t| t.template_id.as_deref() == Some(id)) {
+        task.template_id = None;
+        task.occurrence = None;
+    }
+    Ok(())
+}
+
+fn is_open_occurrence(task: &DailyTask, template_id: &str) -> bool {
+    task.template_id.as_deref() == Some(template_id) && !task.completed && task.moved_to_date.is_none()
+}
+
+/// Copies the fields that changed in the series onto an occurrence, unless
+/// the occurrence has its own value for them.
+fn apply_edit(task: &mut DailyTask, old: &TaskTemplate, new: &TaskTemplate) {
+    if task.text == old.text {
+        task.text = new.text.clone();
+    }
+    if task.description == old.description {
+        task.description = new.description.clone();
+    }
+    if task.goal_id == old.goal_id {
+        task.goal_id = new.goal_id.clone();
+    }
+    if task.category == old.category {
+        task.category = new.category.clone();
+    }
+    if task.timebox_minutes == old.timebox_minutes {
+        task.timebox_minutes = new.timebox_minutes;
+    }
+    if task.remind_at == remind_at(old, &task.date) {
+        task.remind_at = remind_at(new, &task.date);
+    }
+}
+
+/// Whether the occurrence was edited on its own and differs from `template`.
+fn is_edited(task: &DailyTask, template: &TaskTemplate) -> bool {
+    task.text != template.text
+        || task.description != template.description
+        || task.goal_id != template.goal_id
+        || task.category != template.category
+        || task.timebox_minutes != template.timebox_minutes
+        || task.remind_at != remind_at(template, &task.date)
+}
+
+fn is_weekend(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, d).unwrap()
+    }
+
+    fn template(rule: &str) -> TaskTemplate {
+        TaskTemplate {
+            text: "Water the plants".to_string(),
+            category: "work".to_string(),
+            rule: rule.to_string(),
+            start_date: "2026-03-02".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Days of the series' tasks, in order.
+    fn days(data: &AppData, id: &str) -> Vec<u32> {
+        let mut days: Vec<u32> = data
+            .daily_tasks
+            .iter()
+            .filter(|t| t.template_id.as_deref() == Some(id))
+            .map(|t| parse_date(&t.date).unwrap().day())
+            .collect();
+        days.sort();
+        days
+    }
+
+    fn task_on<'a>(data: &'a mut AppData, id: &str, d: u32) -> &'a mut DailyTask {
+        let date = format_date(day(d));
+        data.daily_tasks.iter_mut().find(|t| t.template_id.as_deref() == Some(id) && t.date == date).unwrap()
+    }
+
+    #[test]
+    fn materialize_adds_each_occurrence_once() {
+        let mut data = AppData::default();
+        let series = save_template(&mut data, template("FREQ=DAILY"), None, day(2)).unwrap();
+        assert_eq!(days(&data, &series.id), [2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(series.generated_through.as_deref(), Some("2026-03-09"));
+        assert_eq!(materialize(&mut data, day(2)), 0);
+
+        // After a restart, from what was saved.
+        let saved = serde_json::to_string(&data).unwrap();
+        let mut data: AppData = serde_json::from_str(&saved).unwrap();
+        assert_eq!(materialize(&mut data, day(2)), 0);
+
+        // A deleted occurrence stays deleted; the new day adds one more.
+        data.daily_tasks.retain(|t| t.date != "2026-03-05");
+        assert_eq!(materialize(&mut data, day(3)), 1);
+        assert_eq!(materialize(&mut data, day(3)), 0);
+        assert_eq!(days(&data, &series.id), [2, 3, 4, 6, 7, 8, 9, 10]);
+    }
+
+    #[test]
+    fn after_completion_waits_for_the_open_occurrence() {
+        let mut data = AppData::default();
+        let mut every_two_days = template("");
+        every_two_days.every_days_after_completion = Some(2);
+        let series = save_template(&mut data, every_two_days, None, day(2)).unwrap();
+        assert_eq!(days(&data, &series.id), [2]);
+        assert_eq!(materialize(&mut data, day(4)), 0);
+
+        let task = task_on(&mut data, &series.id, 2);
+        task.completed = true;
+        task.completed_at = Some("2026-03-04T12:00:00Z".to_string());
+        assert_eq!(materialize(&mut data, day(4)), 1);
+        assert_eq!(materialize(&mut data, day(4)), 0);
+        assert_eq!(days(&data, &series.id), [2, 6]);
+    }
+
+    #[test]
+    fn a_task_can_start_a_series() {
+        let mut data = AppData::default();
+        data.daily_tasks.push(DailyTask {
+            id: "first".to_string(),
+            text: "Water the plants".to_string(),
+            date: "2026-03-02".to_string(),
+            category: "work".to_string(),
+            ..Default::default()
+        });
+        let series = save_template(&mut data, template("FREQ=DAILY;INTERVAL=2"), Some("first"), day(2)).unwrap();
+        assert_eq!(days(&data, &series.id), [2, 4, 6, 8]);
+        assert_eq!(data.daily_tasks[0].occurrence.as_deref(), Some("2026-03-02"));
+        assert!(save_template(&mut data, template("FREQ=DAILY"), Some("missing"), day(2)).is_err());
+    }
+
+    #[test]
+    fn editing_the_series_keeps_what_was_edited_on_an_occurrence() {
+        let mut data = AppData::default();
+        let series = save_template(&mut data, template("FREQ=DAILY"), None, day(2)).unwrap();
+        task_on(&mut data, &series.id, 3).completed = true;
+        task_on(&mut data, &series.id, 5).text = "Water the cactus".to_string();
+
+        let mut edited = series.clone();
+        edited.text = "Water the garden".to_string();
+        edited.category = "personal".to_string();
+        edited.remind_time = Some("08:00".to_string());
+        save_template(&mut data, edited, None, day(4)).unwrap();
+
+        let past = task_on(&mut data, &series.id, 3);
+        assert_eq!((past.text.as_str(), past.category.as_str()), ("Water the plants", "work"));
+        let today = task_on(&mut data, &series.id, 4);
+        assert_eq!((today.text.as_str(), today.category.as_str()), ("Water the garden", "personal"));
+        assert_eq!(today.remind_at.as_deref(), Some("2026-03-04T08:00"));
+        let own = task_on(&mut data, &series.id, 5);
+        assert_eq!((own.text.as_str(), own.category.as_str()), ("Water the cactus", "personal"));
+        // Same days, so nothing was generated again.
+        assert_eq!(days(&data, &series.id), [2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+    }
+
+    #[test]
+    fn rescheduling_replaces_the_unedited_future_occurrences() {
+        let mut data = AppData::default();
+        let series = save_template(&mut data, template("FREQ=DAILY"), None, day(2)).unwrap();
+        task_on(&mut data, &series.id, 6).text = "Water the cactus".to_string();
+        let moved = task_on(&mut data, &series.id, 7);
+        moved.date = "2026-03-12".to_string();
+
+        let mut weekly = series.clone();
+        weekly.rule = "FREQ=WEEKLY;BYDAY=MO,TH".to_string();
+        let saved = save_template(&mut data, weekly, None, day(3)).unwrap();
+        // Today's stays, as do the edited and moved ones; Monday and
+        // Thursday are generated again.
+        assert_eq!(days(&data, &series.id), [2, 3, 5, 6, 9, 12]);
+        assert_eq!(saved.generated_through.as_deref(), Some("2026-03-10"));
+        assert_eq!(materialize(&mut data, day(3)), 0);
+    }
+
+    #[test]
+    fn deleting_the_series_keeps_the_past_and_the_chosen_task() {
+        let mut data = AppData::default();
+        let series = save_template(&mut data, template("FREQ=DAILY"), None, day(2)).unwrap();
+        task_on(&mut data, &series.id, 2).completed = true;
+        let kept = task_on(&mut data, &series.id, 6).id.clone();
+        let done_early = task_on(&mut data, &series.id, 7);
+        done_early.completed = true;
+
+        assert!(delete_template(&mut data, "missing", None, day(3)).is_err());
+        delete_template(&mut data, &series.id, Some(&kept), day(3)).unwrap();
+        assert!(data.task_templates.is_empty());
+        let left: Vec<&str> = data.daily_tasks.iter().map(|t| t.date.as_str()).collect();
+        assert_eq!(left, ["2026-03-02", "2026-03-03", "2026-03-06", "2026-03-07"]);
+        assert!(data.daily_tasks.iter().all(|t| t.template_id.is_none() && t.occurrence.is_none()));
+        assert_eq!(materialize(&mut data, day(3)), 0);
+    }
+
+    #[test]
+    fn invalid_series_are_rejected() {
+        let mut data = AppData::default();
+        assert!(save_template(&mut data, template(""), None, day(2)).is_err());
+        assert!(save_template(&mut data, template("FREQ=HOURLY"), None, day(2)).is_err());
+        let mut blank = template("FREQ=DAILY");
+        blank.text = "  ".to_string();
+        assert!(save_template(&mut data, blank, None, day(2)).is_err());
+        let mut never = template("");
+        never.every_days_after_completion = Some(0);
+        assert!(save_template(&mut data, never, None, day(2)).is_err());
+        assert!(data.task_templates.is_empty() && data.daily_tasks.is_empty());
+    }
+}
@@ -0,0 +1,561 @@
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, NaiveDate};
+use chrono_tz::Tz;
+use serde::Serialize;
+
+use crate::clock::Clock;
+use crate::models::{NotificationSettings, ReminderAction, ReminderRule};
+use crate::schedule::{self, Schedule};
+
+pub const LEGACY_DAILY_ID: &str = "daily-check-in";
+pub const LEGACY_WEEKLY_ID: &str = "weekly-reflection";
+
+/// Views a reminder action may open, matching `NavView` in the frontend.
+pub const VIEWS: &[&str] = &["today", "tasks", "goals", "notes", "bragdoc", "curiosities", "reviews", "settings"];
+
+/// Longest the scheduler sleeps without looking at the clock, in case it
+/// jumped (sleep on platforms without wake events, manual changes).
+const MAX_WAIT: StdDuration = StdDuration::from_secs(10 * 60);
+
+/// How late an occurrence may be noticed and still count as on time. Anything
+/// later was missed, typically because the machine was asleep.
+const GRACE: Duration = Duration::minutes(2);
+
+/// Upper bound on missed occurrences counted per rule, e.g. an every-minute
+/// rule after a long sleep.
+const MAX_MISSED: usize = 1000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpcomingReminder {
+    #[serde(rename = "ruleId")]
+    pub rule_id: String,
+    pub title: String,
+    /// `None` if the schedule never fires again.
+    #[serde(rename = "nextFire")]
+    pub next_fire: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The rules the scheduler runs: the user's own list, or the legacy daily and
+/// weekly reminders translated into rules.
+pub fn effective_rules(settings: &NotificationSettings) -> Vec<ReminderRule> {
+    if let Some(rules) = &settings.reminders {
+        return rules.clone();
+    }
+
+    let mut rules = Vec::new();
+    if let Some((hour, minute)) = parse_time(&settings.daily_time) {
+        rules.push(ReminderRule {
+            id: LEGACY_DAILY_ID.to_string(),
+            title: "Daily Check-in".to_string(),
+            body: "Time to review your growth goals!".to_string(),
+            schedule: format!("{} {} * * *", minute, hour),
+            enabled: settings.daily_reminder,
+            start_date: None,
+            action: None,
+        });
+    }
+    if let (Some((hour, minute)), Some(day)) = (parse_time(&settings.weekly_time), cron_weekday(&settings.weekly_day)) {
+        rules.push(ReminderRule {
+            id: LEGACY_WEEKLY_ID.to_string(),
+            title: "Weekly Reflection".to_string(),
+            body: "Take a moment to reflect on your week's progress.".to_string(),
+            schedule: format!("{} {} * * {}", minute, hour, day),
+            enabled: settings.weekly_reminder,
+            start_date: None,
+            action: None,
+        });
+    }
+    rules
+}
+
+/// Checks a rule before it is saved or scheduled.
+pub fn validate(rule: &ReminderRule) -> Result<(), String> {
+    if rule.id.trim().is_empty() {
+        return Err("Reminder id must not be empty".to_string());
+    }
+    if rule.title.trim().is_empty() {
+        return Err("Reminder title must not be empty".to_string());
+    }
+    let schedule = Schedule::parse(&rule.schedule)?;
+    let start = start_date(rule)?;
+    if matches!(&schedule, Schedule::RRule(r) if r.count.is_some()) && start.is_none() {
+        return Err("A reminder with COUNT needs a start date to count from".to_string());
+    }
+    if let Some(ReminderAction::OpenView { view }) = &rule.action {
+        if !VIEWS.contains(&view.as_str()) {
+            return Err(format!("Unknown view: {}", view));
+        }
+    }
+    Ok(())
+}
+
+pub fn next_fire(rule: &ReminderRule, after: &DateTime<Tz>) -> Result<Option<DateTime<Tz>>, String> {
+    let schedule = Schedule::parse(&rule.schedule)?;
+    Ok(schedule.next_after(after, start_date(rule)?))
+}
+
+pub fn upcoming(settings: &NotificationSettings, now: &DateTime<Tz>) -> Vec<UpcomingReminder> {
+    effective_rules(settings)
+        .into_iter()
+        .filter(|rule| rule.enabled)
+        .map(|rule| {
+            let (next_fire, error) = match validate(&rule).and_then(|_| next_fire(&rule, now)) {
+                Ok(next) => (next.map(|t| t.to_rfc3339()), None),
+                Err(e) => (None, Some(e)),
+            };
+            UpcomingReminder { rule_id: rule.id, title: rule.title, next_fire, error }
+        })
+        .collect()
+}
+
+fn start_date(rule: &ReminderRule) -> Result<Option<NaiveDate>, String> {
+    rule.start_date
+        .as_deref()
+        .map(|date| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| format!("Invalid start date (expected YYYY-MM-DD): {}", date))
+        })
+        .transpose()
+}
+
+/// What to do with reminders that came due while the machine was asleep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissedPolicy {
+    /// Fire each missed rule once, marked as late.
+    #[default]
+    Late,
+    /// Fire a single notification listing everything that was missed.
+    Summary,
+    Skip,
+}
+
+impl MissedPolicy {
+    pub fn from_setting(value: &str) -> Self {
+        match value {
+            "summary" => MissedPolicy::Summary,
+            "skip" => MissedPolicy::Skip,
+            _ => MissedPolicy::Late,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Missed {
+    pub rule: ReminderRule,
+    /// Occurrences since the scheduler last ran.
+    pub count: usize,
+    pub last_due: DateTime<Tz>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Firing {
+    Due { rule: ReminderRule, due: DateTime<Tz> },
+    Late(Missed),
+    Summary(Vec<Missed>),
+}
+
+enum Update {
+    Settings(Box<NotificationSettings>),
+    /// The system woke up; look at the clock again.
+    Wake,
+}
+
+/// What the scheduler knows: the settings it was last given and how far it
+/// has looked. Kept apart from the worker thread so it can be stepped with
+/// any clock.
+pub struct ReminderState {
+    settings: Option<NotificationSettings>,
+    cursor: DateTime<Tz>,
+}
+
+impl ReminderState {
+    /// Looks back a little so a reminder due just before launch still fires;
+    /// the notification log stops it repeating across restarts.
+    pub fn new(settings: Option<NotificationSettings>, now: DateTime<Tz>) -> Self {
+        Self { settings, cursor: now - GRACE }
+    }
+
+    pub fn set_settings(&mut self, settings: NotificationSettings) {
+        self.settings = Some(settings);
+    }
+
+    /// Everything that came due since the last poll, and when the next
+    /// occurrence is. Schedules are evaluated in `now`'s zone.
+    pub fn poll(&mut self, now: DateTime<Tz>) -> (Vec<Firing>, Option<DateTime<Tz>>) {
+        if self.cursor.timezone() != now.timezone() {
+            self.change_zone(now.timezone());
+        }
+        let (rules, policy) = match &self.settings {
+            Some(settings) if settings.enabled => (
+                effective_rules(settings).into_iter().filter(|r| r.enabled).collect(),
+                MissedPolicy::from_setting(&settings.missed_reminders),
+            ),
+            _ => (Vec::new(), MissedPolicy::default()),
+        };
+
+        let mut firings = Vec::new();
+        let mut missed = Vec::new();
+        let mut next_wake: Option<DateTime<Tz>> = None;
+        // After moving to a zone where it is earlier, the cursor is ahead of
+        // now until the clock catches up.
+        let from = self.cursor.max(now);
+        for rule in &rules {
+            let due = match occurrences(rule, &self.cursor, &now) {
+                Ok(due) => due,
+                Err(e) => {
+                    eprintln!("Skipping reminder {}: {}", rule.id, e);
+                    continue;
+                }
+            };
+            if let Some(last_due) = due.last().cloned() {
+                if now - last_due <= GRACE {
+                    firings.push(Firing::Due { rule: rule.clone(), due: last_due });
+                } else {
+                    missed.push(Missed { rule: rule.clone(), count: due.len(), last_due });
+                }
+            }
+            if let Ok(Some(next)) = next_fire(rule, &from) {
+                next_wake = Some(next_wake.map_or(next, |w| w.min(next)));
+            }
+        }
+        self.cursor = from;
+
+        if !missed.is_empty() {
+            eprintln!("{} reminder(s) missed while away, policy {:?}", missed.len(), policy);
+            match policy {
+                MissedPolicy::Late => firings.extend(missed.into_iter().map(Firing::Late)),
+                MissedPolicy::Summary => firings.push(Firing::Summary(missed)),
+                MissedPolicy::Skip => {}
+            }
+        }
+        (firings, next_wake)
+    }
+
+    /// Carries the cursor's wall-clock time over to `zone`. Moving to a zone
+    /// where it is later catches up on the local times skipped over, like
+    /// after a sleep; moving to one where it is earlier waits until the clock
+    /// passes the old local time again, so nothing fires twice.
+    fn change_zone(&mut self, zone: Tz) {
+        eprintln!("Time zone changed from {} to {}", self.cursor.timezone(), zone);
+        let wall = self.cursor.naive_local();
+        self.cursor = schedule::resolve(&zone, wall).unwrap_or_else(|| self.cursor.with_timezone(&zone));
+    }
+}
+
+/// Fires reminder rules at their computed next occurrence.
+///
+/// The worker keeps the settings in memory, updated through `update`, and
+/// sleeps until the earliest next occurrence or until the settings change.
+/// Each pass looks at every occurrence since the previous one; occurrences
+/// noticed more than a couple of minutes late were missed while the machine
+/// slept and are handled according to the `missed_reminders` setting. Call
+/// `wake` when the system wakes so that happens promptly.
+#[derive(Clone)]
+pub struct ReminderScheduler {
+    updates: Sender<Update>,
+}
+
+impl ReminderScheduler {
+    pub fn start<F>(clock: Arc<dyn Clock>, settings: Option<NotificationSettings>, fire: F) -> Self
+    where
+        F: Fn(Firing) + Send + 'static,
+    {
+        let (updates, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let mut state = ReminderState::new(settings, clock.now());
+            loop {
+                let (firings, next) = state.poll(clock.now());
+                firings.into_iter().for_each(&fire);
+
+                let wait = next.map_or(MAX_WAIT, |next| (next - clock.now()).to_std().unwrap_or_default().min(MAX_WAIT));
+                match receiver.recv_timeout(wait) {
+                    Ok(Update::Settings(settings)) => state.set_settings(*settings),
+                    Ok(Update::Wake) | Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+                // Only the latest settings matter.
+                for update in receiver.try_iter() {
+                    if let Update::Settings(settings) = update {
+                        state.set_settings(*settings);
+                    }
+                }
+            }
+        });
+        Self { updates }
+    }
+
+    pub fn update(&self, settings: NotificationSettings) {
+        let _ = self.updates.send(Update::Settings(Box::new(settings)));
+    }
+
+    pub fn wake(&self) {
+        let _ = self.updates.send(Update::Wake);
+    }
+}
+
+/// Occurrences of `rule` in `(after, until]`, oldest first.
+fn occurrences(rule: &ReminderRule, after: &DateTime<Tz>, until: &DateTime<Tz>) -> Result<Vec<DateTime<Tz>>, String> {
+    let mut found = Vec::new();
+    let mut cursor = *after;
+    while found.len() < MAX_MISSED {
+        match next_fire(rule, &cursor)? {
+            Some(next) if next <= *until => {
+                cursor = next;
+                found.push(next);
+            }
+            _ => break,
+        }
+    }
+    Ok(found)
+}
+
+/// Parses `HH:MM` as saved by the settings screen.
+fn parse_time(time: &str) -> Option<(u32, u32)> {
+    let (hour, minute) = time.trim().split_once(':')?;
+    let (hour, minute) = (hour.parse().ok()?, minute.parse().ok()?);
+    (hour < 24 && minute < 60).then_some((hour, minute))
+}
+
+fn cron_weekday(day: &str) -> Option<&'static str> {
+    Some(match day.to_lowercase().as_str() {
+        "monday" => "MON",
+        "tuesday" => "TUE",
+        "wednesday" => "WED",
+        "thursday" => "THU",
+        "friday" => "FRI",
+        "saturday" => "SAT",
+        "sunday" => "SUN",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    use crate::clock::ManualClock;
+
+    fn rule(schedule: &str, start_date: Option<&str>) -> ReminderRule {
+        ReminderRule {
+            id: "rule".to_string(),
+            title: "Stretch".to_string(),
+            schedule: schedule.to_string(),
+            enabled: true,
+            start_date: start_date.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn named(id: &str, schedule: &str) -> ReminderRule {
+        ReminderRule { id: id.to_string(), ..rule(schedule, None) }
+    }
+
+    fn settings(rules: Vec<ReminderRule>, missed: &str) -> NotificationSettings {
+        NotificationSettings {
+            enabled: true,
+            reminders: Some(rules),
+            missed_reminders: missed.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// 2026-03-02 (a Monday) at `h:m` UTC.
+    fn at(h: u32, m: u32) -> DateTime<Tz> {
+        chrono_tz::UTC.with_ymd_and_hms(2026, 3, 2, h, m, 0).unwrap()
+    }
+
+    /// What each firing is, for comparing.
+    fn describe(firings: &[Firing]) -> Vec<String> {
+        let missed = |m: &Missed| format!("{} x{} {}", m.rule.id, m.count, m.last_due.format("%d %H:%M"));
+        firings
+            .iter()
+            .map(|firing| match firing {
+                Firing::Due { rule, due } => format!("due {} {}", rule.id, due.format("%d %H:%M")),
+                Firing::Late(m) => format!("late {}", missed(m)),
+                Firing::Summary(all) => format!("summary {}", all.iter().map(missed).collect::<Vec<_>>().join(", ")),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fires_once_at_the_scheduled_time() {
+        let clock = ManualClock::new(at(8, 0));
+        let mut state = ReminderState::new(Some(settings(vec![named("stretch", "0 9 * * *")], "late")), clock.now());
+
+        let (firings, next) = state.poll(clock.now());
+        assert!(firings.is_empty());
+        assert_eq!(next, Some(at(9, 0)));
+
+        clock.set(at(9, 0) + Duration::seconds(1));
+        let (firings, next) = state.poll(clock.now());
+        assert_eq!(describe(&firings), ["due stretch 02 09:00"]);
+        assert_eq!(next, Some(at(9, 0) + Duration::days(1)));
+
+        clock.advance(Duration::seconds(30));
+        assert!(state.poll(clock.now()).0.is_empty());
+    }
+
+    #[test]
+    fn a_reminder_due_within_the_grace_period_is_on_time() {
+        let clock = ManualClock::new(at(8, 0));
+        let mut state = ReminderState::new(Some(settings(vec![named("stretch", "0 9 * * *")], "late")), clock.now());
+        state.poll(clock.now());
+
+        clock.set(at(9, 2));
+        assert_eq!(describe(&state.poll(clock.now()).0), ["due stretch 02 09:00"]);
+    }
+
+    #[test]
+    fn launching_just_after_a_reminder_still_sends_it() {
+        let rules = vec![named("stretch", "0 9 * * *")];
+        let clock = ManualClock::new(at(9, 1));
+        let mut state = ReminderState::new(Some(settings(rules.clone(), "late")), clock.now());
+        assert_eq!(describe(&state.poll(clock.now()).0), ["due stretch 02 09:00"]);
+
+        clock.set(at(9, 5));
+        let mut state = ReminderState::new(Some(settings(rules, "late")), clock.now());
+        assert!(state.poll(clock.now()).0.is_empty());
+    }
+
+    #[test]
+    fn reminders_missed_during_sleep_fire_late_once_each() {
+        let clock = ManualClock::new(at(8, 0));
+        let rules = vec![named("stretch", "0 9 * * *"), named("water", "0 * * * *")];
+        let mut state = ReminderState::new(Some(settings(rules, "late")), clock.now());
+        state.poll(clock.now());
+
+        // Asleep from 08:00 until 11:30 the next day.
+        clock.set(at(11, 30) + Duration::days(1));
+        assert_eq!(
+            describe(&state.poll(clock.now()).0),
+            ["late stretch x2 03 09:00", "late water x27 03 11:00"]
+        );
+        assert!(state.poll(clock.now()).0.is_empty());
+    }
+
+    #[test]
+    fn missed_policy_summary_and_skip() {
+        let rules = vec![named("stretch", "0 9 * * *"), named("water", "30 9 * * *"), named("later", "0 12 * * *")];
+        for (policy, expected) in [
+            ("summary", vec!["summary stretch x1 02 09:00, water x1 02 09:30"]),
+            ("skip", vec![]),
+        ] {
+            let clock = ManualClock::new(at(8, 0));
+            let mut state = ReminderState::new(Some(settings(rules.clone(), policy)), clock.now());
+            state.poll(clock.now());
+
+            clock.set(at(10, 0));
+            let (firings, next) = state.poll(clock.now());
+            assert_eq!(describe(&firings), expected, "{}", policy);
+            assert_eq!(next, Some(at(12, 0)));
+        }
+    }
+
+    #[test]
+    fn on_time_and_missed_reminders_in_the_same_poll() {
+        let clock = ManualClock::new(at(8, 0));
+        let rules = vec![named("stretch", "0 9 * * *"), named("standup", "59 9 * * *")];
+        let mut state = ReminderState::new(Some(settings(rules, "summary")), clock.now());
+        state.poll(clock.now());
+
+        clock.set(at(10, 0));
+        assert_eq!(
+            describe(&state.poll(clock.now()).0),
+            ["due standup 02 09:59", "summary stretch x1 02 09:00"]
+        );
+    }
+
+    #[test]
+    fn disabled_rules_and_notifications_do_not_fire() {
+        let clock = ManualClock::new(at(8, 0));
+        let mut off = named("off", "0 9 * * *");
+        off.enabled = false;
+        let mut state = ReminderState::new(Some(settings(vec![off, named("on", "0 9 * * *")], "late")), clock.now());
+        state.poll(clock.now());
+        clock.set(at(9, 0));
+        assert_eq!(describe(&state.poll(clock.now()).0), ["due on 02 09:00"]);
+
+        let mut muted = settings(vec![named("on", "0 10 * * *")], "late");
+        muted.enabled = false;
+        state.set_settings(muted);
+        clock.set(at(10, 0));
+        let (firings, next) = state.poll(clock.now());
+        assert!(firings.is_empty());
+        assert_eq!(next, None);
+    }
+
+    #[test]
+    fn count_needs_a_start_date() {
+        assert!(validate(&rule("FREQ=DAILY;COUNT=5", None)).is_err());
+        assert!(validate(&rule("FREQ=DAILY;COUNT=5", Some("2026-03-02"))).is_ok());
+        assert!(validate(&rule("FREQ=DAILY;UNTIL=20260310", None)).is_ok());
+    }
+
+    #[test]
+    fn daylight_saving_changes_neither_skip_nor_repeat_reminders() {
+        let berlin = chrono_tz::Europe::Berlin;
+        let rules = vec![named("early", "30 2 * * *")];
+
+        // Spring forward: 02:30 doesn't exist and fires at 03:00.
+        let clock = ManualClock::new(berlin.with_ymd_and_hms(2026, 3, 29, 1, 0, 0).unwrap());
+        let mut state = ReminderState::new(Some(settings(rules.clone(), "late")), clock.now());
+        let (_, next) = state.poll(clock.now());
+        let gap_end = berlin.with_ymd_and_hms(2026, 3, 29, 3, 0, 0).unwrap();
+        assert_eq!(next, Some(gap_end));
+        clock.set(gap_end);
+        assert_eq!(describe(&state.poll(clock.now()).0), ["due early 29 03:00"]);
+
+        // Fall back: 02:30 comes round twice and fires the first time only.
+        let clock = ManualClock::new(berlin.with_ymd_and_hms(2026, 10, 25, 1, 0, 0).unwrap());
+        let mut state = ReminderState::new(Some(settings(rules, "late")), clock.now());
+        state.poll(clock.now());
+        clock.advance(Duration::minutes(90));
+        assert_eq!(clock.now().format("%H:%M %Z").to_string(), "02:30 CEST");
+        assert_eq!(describe(&state.poll(clock.now()).0), ["due early 25 02:30"]);
+        clock.advance(Duration::hours(1));
+        assert_eq!(clock.now().format("%H:%M %Z").to_string(), "02:30 CET");
+        let (firings, next) = state.poll(clock.now());
+        assert!(firings.is_empty());
+        assert_eq!(next, Some(berlin.with_ymd_and_hms(2026, 10, 26, 2, 30, 0).unwrap()));
+    }
+
+    #[test]
+    fn travelling_west_does_not_fire_a_reminder_twice() {
+        let clock = ManualClock::new(chrono_tz::Europe::Berlin.with_ymd_and_hms(2026, 3, 2, 9, 0, 30).unwrap());
+        let mut state = ReminderState::new(Some(settings(vec![named("stretch", "0 9 * * *")], "late")), clock.now());
+        assert_eq!(describe(&state.poll(clock.now()).0), ["due stretch 02 09:00"]);
+
+        // 03:00 in New York; 09:00 there has already been handled in Berlin.
+        clock.set_zone(chrono_tz::America::New_York);
+        let new_york = |d, h, m| chrono_tz::America::New_York.with_ymd_and_hms(2026, 3, d, h, m, 0).unwrap();
+        let (firings, next) = state.poll(clock.now());
+        assert!(firings.is_empty());
+        assert_eq!(next, Some(new_york(3, 9, 0)));
+        clock.set(new_york(2, 9, 1));
+        assert!(state.poll(clock.now()).0.is_empty());
+
+        clock.set(new_york(3, 9, 0));
+        assert_eq!(describe(&state.poll(clock.now()).0), ["due stretch 03 09:00"]);
+    }
+
+    #[test]
+    fn travelling_east_catches_up_on_skipped_reminders() {
+        let clock = ManualClock::new(chrono_tz::Europe::Berlin.with_ymd_and_hms(2026, 3, 2, 8, 0, 0).unwrap());
+        let rules = vec![named("stretch", "0 9 * * *"), named("lunch", "0 12 * * *"), named("evening", "0 18 * * *")];
+        let mut state = ReminderState::new(Some(settings(rules, "late")), clock.now());
+        state.poll(clock.now());
+
+        // 16:00 in Tokyo: 09:00 and 12:00 there were skipped over.
+        clock.set_zone(chrono_tz::Asia::Tokyo);
+        let (firings, next) = state.poll(clock.now());
+        assert_eq!(describe(&firings), ["late stretch x1 02 09:00", "late lunch x1 02 12:00"]);
+        assert_eq!(next, Some(chrono_tz::Asia::Tokyo.with_ymd_and_hms(2026, 3, 2, 18, 0, 0).unwrap()));
+    }
+}
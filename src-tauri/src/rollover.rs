@@ -0,0 +1,333 @@
+use std::collections::HashSet;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration as StdDuration;
+
+use chrono::{Duration, NaiveDate, NaiveTime};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::checklist;
+use crate::clock::Clock;
+use crate::models::{AppData, CarryForwardPolicy, CarryForwardSettings, DailyTask};
+use crate::schedule;
+
+/// Longest the worker sleeps without looking at the clock, so a change of
+/// zone moves the day boundary soon enough.
+const MAX_WAIT: StdDuration = StdDuration::from_secs(10 * 60);
+
+/// Unfinished tasks older than this are left where they are: after a long
+/// break they are more likely stale than still wanted.
+const LOOKBACK_DAYS: i64 = 7;
+
+/// A task moved to the new day.
+#[derive(Debug, Serialize, Clone)]
+pub struct CarriedTask {
+    /// The new task.
+    pub id: String,
+    /// The task left behind, now marked as moved.
+    #[serde(rename = "fromId")]
+    pub from_id: String,
+    #[serde(rename = "fromDate")]
+    pub from_date: String,
+    pub text: String,
+}
+
+/// What a rollover did, sent to the webview with `tasks-carried-forward`.
+#[derive(Debug, Serialize, Clone)]
+pub struct RolloverSummary {
+    pub date: String,
+    pub policy: CarryForwardPolicy,
+    pub moved: Vec<CarriedTask>,
+    /// Unfinished tasks the policy left on their day.
+    #[serde(rename = "leftBehind")]
+    pub left_behind: usize,
+}
+
+/// Whether `settings` move `task` to the new day.
+fn carries(settings: &CarryForwardSettings, task: &DailyTask) -> bool {
+    match settings.policy {
+        CarryForwardPolicy::None => false,
+        CarryForwardPolicy::All => true,
+        CarryForwardPolicy::FrogOnly => task.is_frog.unwrap_or(false),
+        CarryForwardPolicy::Categories => settings.categories.contains(&task.category),
+    }
+}
+
+/// Moves the unfinished tasks of the past week that the `carryForward`
+/// policy picks onto `today`, the same way carrying a task forward in the
+/// Today view does: a copy with the unfinished checklist items is added to
+/// today and the original is marked as moved. Carried tasks go to the top
+/// of today in the order they had, oldest day first. Tasks already moved
+/// are skipped, so running it twice moves nothing more.
+pub fn carry_forward(data: &mut AppData, today: NaiveDate) -> RolloverSummary {
+    let settings = data.carry_forward.clone();
+    let date = today.format("%Y-%m-%d").to_string();
+    let earliest = today - Duration::days(LOOKBACK_DAYS);
+
+    // A recurring task that has another occurrence today stays behind.
+    let recurring_today: HashSet<&str> = data
+        .daily_tasks
+        .iter()
+        .filter(|task| task.date == date)
+        .filter_map(|task| task.template_id.as_deref())
+        .collect();
+
+    let mut unfinished: Vec<(NaiveDate, usize)> = data
+        .daily_tasks
+        .iter()
+        .enumerate()
+        .filter(|(_, task)| !task.completed && task.moved_to_date.is_none())
+        .filter(|(_, task)| !task.template_id.as_deref().is_some_and(|id| recurring_today.contains(id)))
+        .filter_map(|(index, task)| {
+            let day = NaiveDate::parse_from_str(&task.date, "%Y-%m-%d").ok()?;
+            (day >= earliest && day < today).then_some((day, index))
+        })
+        .collect();
+    let left_behind = unfinished.len();
+    unfinished.retain(|&(_, index)| carries(&settings, &data.daily_tasks[index]));
+    unfinished.sort_by_key(|&(day, index)| {
+        let task = &data.daily_tasks[index];
+        (day, !task.is_frog.unwrap_or(false), task.order.unwrap_or(i32::MAX))
+    });
+
+    let shift = unfinished.len() as i32;
+    let mut has_frog = false;
+    for task in data.daily_tasks.iter_mut().filter(|t| t.date == date) {
+        if let Some(order) = task.order.as_mut() {
+            *order += shift;
+        }
+        has_frog |= task.is_frog.unwrap_or(false);
+    }
+
+    let mut carried = Vec::with_capacity(unfinished.len());
+    let mut moved = Vec::with_capacity(unfinished.len());
+    for (order, &(_, index)) in unfinished.iter().enumerate() {
+        let original = &mut data.daily_tasks[index];
+        original.moved_to_date = Some(date.clone());
+        // Today keeps a single frog.
+        let is_frog = original.is_frog.unwrap_or(false) && !has_frog;
+        has_frog |= is_frog;
+
+        let task = DailyTask {
+            id: Uuid::new_v4().to_string(),
+            text: original.text.clone(),
+            description: original.description.clone(),
+            goal_id: original.goal_id.clone(),
+            date: date.clone(),
+            order: Some(order as i32),
+            is_frog: is_frog.then_some(true),
+            category: original.category.clone(),
+            timebox_minutes: original.timebox_minutes,
+            remind_at: original.remind_at.clone(),
+            template_id: original.template_id.clone(),
+            occurrence: original.occurrence.clone(),
+            checklist: checklist::unfinished(&original.checklist),
+            ..Default::default()
+        };
+        moved.push(CarriedTask {
+            id: task.id.clone(),
+            from_id: original.id.clone(),
+            from_date: original.date.clone(),
+            text: task.text.clone(),
+        });
+        carried.push(task);
+    }
+    data.daily_tasks.splice(0..0, carried);
+
+    RolloverSummary { date, policy: settings.policy, left_behind: left_behind - moved.len(), moved }
+}
+
+/// Calls `roll` with the new local date whenever the day changes, once on
+/// start for a day begun while the app was closed, and again after waking,
+/// as the thread doesn't run while the machine sleeps through midnight.
+#[derive(Clone)]
+pub struct DayRollover {
+    wakes: Sender<()>,
+}
+
+impl DayRollover {
+    pub fn start<F>(clock: Arc<dyn Clock>, roll: F) -> Self
+    where
+        F: Fn(NaiveDate) + Send + 'static,
+    {
+        let (wakes, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let mut last = None;
+            loop {
+                let now = clock.now();
+                let today = now.date_naive();
+                if last != Some(today) {
+                    roll(today);
+                    last = Some(today);
+                }
+
+                let midnight = (today + Duration::days(1)).and_time(NaiveTime::MIN);
+                let wait = schedule::resolve(&now.timezone(), midnight).map_or(MAX_WAIT, |next| {
+                    (next - clock.now()).to_std().unwrap_or_default().min(MAX_WAIT)
+                });
+                match receiver.recv_timeout(wait) {
+                    Ok(()) | Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+        });
+        Self { wakes }
+    }
+
+    /// Looks at the date again, after the machine slept.
+    pub fn wake(&self) {
+        let _ = self.wakes.send(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ChecklistItem;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, d).unwrap()
+    }
+
+    fn task(id: &str, d: u32, order: i32) -> DailyTask {
+        DailyTask {
+            id: id.to_string(),
+            text: format!("Task {}", id),
+            date: day(d).format("%Y-%m-%d").to_string(),
+            order: Some(order),
+            category: "work".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn frog(mut task: DailyTask) -> DailyTask {
+        task.is_frog = Some(true);
+        task
+    }
+
+    fn app_data(policy: CarryForwardPolicy, tasks: Vec<DailyTask>) -> AppData {
+        AppData {
+            daily_tasks: tasks,
+            carry_forward: CarryForwardSettings { policy, categories: vec!["personal".to_string()] },
+            ..Default::default()
+        }
+    }
+
+    /// Texts of the tasks on `d`, in order.
+    fn on(data: &AppData, d: u32) -> Vec<String> {
+        let date = day(d).format("%Y-%m-%d").to_string();
+        let mut tasks: Vec<&DailyTask> = data.daily_tasks.iter().filter(|t| t.date == date).collect();
+        tasks.sort_by_key(|t| t.order);
+        tasks.iter().map(|t| t.text.clone()).collect()
+    }
+
+    #[test]
+    fn the_policy_picks_what_moves() {
+        let tasks = || {
+            let mut personal = task("p", 9, 1);
+            personal.category = "personal".to_string();
+            vec![frog(task("f", 9, 0)), personal, task("w", 9, 2)]
+        };
+        let moved = |policy| {
+            let mut data = app_data(policy, tasks());
+            let summary = carry_forward(&mut data, day(10));
+            (on(&data, 10), summary.left_behind)
+        };
+        assert_eq!(moved(CarryForwardPolicy::None), (vec![], 3));
+        assert_eq!(moved(CarryForwardPolicy::All), (vec!["Task f".into(), "Task p".into(), "Task w".into()], 0));
+        assert_eq!(moved(CarryForwardPolicy::FrogOnly), (vec!["Task f".into()], 2));
+        assert_eq!(moved(CarryForwardPolicy::Categories), (vec!["Task p".into()], 2));
+    }
+
+    #[test]
+    fn carried_tasks_go_on_top_oldest_day_and_frog_first() {
+        let mut done = task("done", 9, 0);
+        done.completed = true;
+        let mut data = app_data(
+            CarryForwardPolicy::All,
+            vec![task("b", 9, 2), frog(task("c", 9, 5)), task("a", 8, 3), done, task("today", 10, 0), task("later", 10, 1)],
+        );
+        let summary = carry_forward(&mut data, day(10));
+        assert_eq!(on(&data, 10), ["Task a", "Task c", "Task b", "Task today", "Task later"]);
+        // Orders stay distinct: today's tasks shifted down by the carried ones.
+        let orders: Vec<Option<i32>> = data.daily_tasks.iter().filter(|t| t.date == "2026-03-10").map(|t| t.order).collect();
+        assert_eq!(orders, [Some(0), Some(1), Some(2), Some(3), Some(4)]);
+        assert_eq!(summary.moved.iter().map(|m| m.from_date.as_str()).collect::<Vec<_>>(), ["2026-03-08", "2026-03-09", "2026-03-09"]);
+        assert_eq!(summary.date, "2026-03-10");
+    }
+
+    #[test]
+    fn the_original_is_marked_and_keeps_its_done_steps() {
+        let mut original = task("a", 9, 0);
+        let step = |id: &str, completed| ChecklistItem { id: id.to_string(), text: id.to_string(), completed, completed_at: None };
+        original.checklist = vec![step("one", true), step("two", false)];
+        let mut data = app_data(CarryForwardPolicy::All, vec![original]);
+        let summary = carry_forward(&mut data, day(10));
+
+        let (carried, left) = (&data.daily_tasks[0], &data.daily_tasks[1]);
+        assert_eq!(summary.moved[0].id, carried.id);
+        assert_eq!(summary.moved[0].from_id, "a");
+        assert_eq!(left.moved_to_date.as_deref(), Some("2026-03-10"));
+        assert_eq!(left.checklist.len(), 2);
+        assert_eq!(carried.checklist.iter().map(|i| i.id.as_str()).collect::<Vec<_>>(), ["two"]);
+        assert!(!carried.completed && carried.moved_to_date.is_none());
+    }
+
+    #[test]
+    fn today_keeps_a_single_frog() {
+        let mut data = app_data(CarryForwardPolicy::All, vec![frog(task("first", 8, 0)), frog(task("second", 9, 0))]);
+        carry_forward(&mut data, day(10));
+        let frogs: Vec<&str> = data.daily_tasks.iter().filter(|t| t.date == "2026-03-10" && t.is_frog == Some(true)).map(|t| t.text.as_str()).collect();
+        assert_eq!(frogs, ["Task first"]);
+
+        let mut data = app_data(CarryForwardPolicy::All, vec![frog(task("old", 9, 0)), frog(task("today", 10, 0))]);
+        carry_forward(&mut data, day(10));
+        let frogs = data.daily_tasks.iter().filter(|t| t.date == "2026-03-10" && t.is_frog == Some(true)).count();
+        assert_eq!(frogs, 1);
+        assert_eq!(on(&data, 10), ["Task old", "Task today"]);
+    }
+
+    #[test]
+    fn recurring_tasks_with_an_occurrence_today_stay_behind() {
+        let mut old = task("old", 9, 0);
+        old.template_id = Some("daily".to_string());
+        let mut new = task("new", 10, 0);
+        new.template_id = Some("daily".to_string());
+        let mut other = task("other", 9, 1);
+        other.template_id = Some("weekly".to_string());
+        let mut data = app_data(CarryForwardPolicy::All, vec![old, new, other]);
+        let summary = carry_forward(&mut data, day(10));
+        assert_eq!(on(&data, 10), ["Task other", "Task new"]);
+        assert_eq!(summary.moved.len(), 1);
+        assert_eq!(summary.left_behind, 0);
+        assert_eq!(data.daily_tasks.iter().find(|t| t.id == "old").unwrap().moved_to_date, None);
+    }
+
+    #[test]
+    fn only_the_past_week_is_looked_at() {
+        let mut data = app_data(CarryForwardPolicy::All, vec![task("stale", 2, 0), task("week", 3, 0), task("future", 11, 0)]);
+        let summary = carry_forward(&mut data, day(10));
+        assert_eq!(on(&data, 10), ["Task week"]);
+        assert_eq!(summary.left_behind, 0);
+        assert_eq!(on(&data, 2), ["Task stale"]);
+        assert_eq!(on(&data, 11), ["Task future"]);
+    }
+
+    #[test]
+    fn running_again_moves_nothing_more() {
+        let mut data = app_data(CarryForwardPolicy::All, vec![task("a", 8, 0), task("b", 9, 0)]);
+        assert_eq!(carry_forward(&mut data, day(10)).moved.len(), 2);
+        assert!(carry_forward(&mut data, day(10)).moved.is_empty());
+        assert_eq!(data.daily_tasks.len(), 4);
+
+        // The next day, the carried copies move on and nothing else does.
+        let summary = carry_forward(&mut data, day(11));
+        assert_eq!(summary.moved.iter().map(|m| m.from_date.as_str()).collect::<Vec<_>>(), ["2026-03-10", "2026-03-10"]);
+        assert_eq!(on(&data, 11), ["Task a", "Task b"]);
+        assert!(carry_forward(&mut data, day(11)).moved.is_empty());
+        assert_eq!(data.daily_tasks.len(), 6);
+        assert_eq!(data.daily_tasks.iter().filter(|t| t.moved_to_date.is_none()).count(), 2);
+    }
+}
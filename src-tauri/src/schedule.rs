@@ -0,0 +1,699 @@
+use chrono::{DateTime, Datelike, Duration, LocalResult, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Weekday};
+
+/// How far ahead `next_after` looks before giving up. Covers the rarest cron
+/// dates (29 February) with room to spare.
+const SEARCH_DAYS: i64 = 366 * 8 + 2;
+
+/// Default anchor for RRULE intervals when a rule has no start date.
+const DEFAULT_ANCHOR: (i32, u32, u32) = (1970, 1, 5);
+
+/// Most occurrences a COUNT can ask for.
+const MAX_COUNT: u32 = 1000;
+
+/// How far past its start a rule with COUNT is followed to find its last
+/// occurrence; one that runs longer than this is treated as never ending.
+const COUNT_SEARCH_DAYS: usize = 366 * 100;
+
+/// A recurring schedule, written either as a five-field cron expression
+/// (`30 9 * * MON-FRI`) or as an RRULE subset
+/// (`FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;BYHOUR=9;BYMINUTE=30`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schedule {
+    Cron(Cron),
+    RRule(RRule),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cron {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RRule {
+    pub freq: Frequency,
+    pub interval: u32,
+    pub by_day: Vec<Weekday>,
+    /// 1..=31, or negative to count from the end of the month (-1 = last day).
+    pub by_month_day: Vec<i32>,
+    pub by_month: Vec<u32>,
+    pub by_hour: Vec<u32>,
+    pub by_minute: Vec<u32>,
+    pub until: Option<NaiveDate>,
+    /// Number of occurrences from the start date, counting each time of day.
+    pub count: Option<u32>,
+}
+
+impl Schedule {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        let rule = spec.strip_prefix("RRULE:").unwrap_or(spec);
+        if rule.to_uppercase().contains("FREQ=") {
+            RRule::parse(rule).map(Schedule::RRule)
+        } else {
+            Cron::parse(spec).map(Schedule::Cron)
+        }
+    }
+
+    /// The first occurrence strictly after `after`, in `after`'s time zone.
+    ///
+    /// Times that fall into a DST gap fire when the gap ends; times repeated
+    /// when clocks go back fire once, on their first instance.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>, start: Option<NaiveDate>) -> Option<DateTime<Tz>> {
+        self.anchored(start).next_after(after)
+    }
+
+    /// The schedule counted from `start`, which anchors RRULE intervals and
+    /// COUNT and is the first date that can match. Finding where a COUNT rule ends
+    /// walks every day up to its last occurrence, so callers checking many
+    /// days should anchor the schedule once and ask it instead.
+    pub fn anchored(&self, start: Option<NaiveDate>) -> Anchored<'_> {
+        let end = match self {
+            Schedule::Cron(_) => None,
+            Schedule::RRule(rule) => rule.end(start),
+        };
+        Anchored { schedule: self, start, end }
+    }
+
+    fn times(&self) -> Vec<NaiveTime> {
+        let (hours, minutes): (Vec<u32>, Vec<u32>) = match self {
+            Schedule::Cron(cron) => (bits(cron.hours), bits(cron.minutes)),
+            Schedule::RRule(rule) => (
+                if rule.by_hour.is_empty() { vec![0] } else { rule.by_hour.clone() },
+                if rule.by_minute.is_empty() { vec![0] } else { rule.by_minute.clone() },
+            ),
+        };
+        let mut times: Vec<NaiveTime> = hours
+            .iter()
+            .flat_map(|h| minutes.iter().filter_map(move |m| NaiveTime::from_hms_opt(*h, *m, 0)))
+            .collect();
+        times.sort();
+        times.dedup();
+        times
+    }
+}
+
+/// A schedule with its start date and, for a rule that ends, its last day and
+/// how many of that day's times are still occurrences.
+pub struct Anchored<'a> {
+    schedule: &'a Schedule,
+    start: Option<NaiveDate>,
+    end: Option<(NaiveDate, usize)>,
+}
+
+impl Anchored<'_> {
+    /// Whether the schedule has an occurrence on `date`.
+    pub fn occurs_on(&self, date: NaiveDate) -> bool {
+        match self.schedule {
+            Schedule::Cron(cron) => cron.matches_date(date),
+            Schedule::RRule(rule) => {
+                rule.matches_date(date, self.start) && self.end.is_none_or(|(last, _)| date <= last)
+            }
+        }
+    }
+
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = after.timezone();
+        let times = self.schedule.times();
+        let first_day = after.naive_local().date();
+
+        for offset in 0..SEARCH_DAYS {
+            let date = first_day + Duration::days(offset);
+            if self.end.is_some_and(|(last, _)| date > last) {
+                return None;
+            }
+            if !self.occurs_on(date) {
+                continue;
+            }
+            let limit = match self.end {
+                Some((last, left)) if last == date => left,
+                _ => times.len(),
+            };
+            for time in times.iter().take(limit) {
+                if let Some(at) = resolve(&tz, date.and_time(*time)) {
+                    if at > *after {
+                        return Some(at);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Maps a wall-clock time to an instant, moving times inside a DST gap to the
+/// end of the gap.
+pub fn resolve<Tz: TimeZone>(tz: &Tz, local: NaiveDateTime) -> Option<DateTime<Tz>> {
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(at) => Some(at),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest),
+        LocalResult::None => (1..=4)
+            .map(|quarter| local + Duration::minutes(15 * quarter))
+            .find_map(|shifted| tz.from_local_datetime(&shifted).earliest()),
+    }
+}
+
+impl Cron {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = spec.split_whitespace().collect();
+        let [minute, hour, dom, month, dow] = fields.as_slice() else {
+            return Err(format!(
+                "Expected 5 cron fields (minute hour day-of-month month day-of-week), got {}",
+                fields.len()
+            ));
+        };
+
+        let mut days_of_week = cron_field(dow, 0, 7, "day of week", WEEKDAY_NAMES)?;
+        // Both 0 and 7 mean Sunday.
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Cron {
+            minutes: cron_field(minute, 0, 59, "minute", &[])?,
+            hours: cron_field(hour, 0, 23, "hour", &[])?,
+            days_of_month: cron_field(dom, 1, 31, "day of month", &[])?,
+            months: cron_field(month, 1, 12, "month", MONTH_NAMES)?,
+            days_of_week,
+            any_day_of_month: dom.starts_with('*'),
+            any_day_of_week: dow.starts_with('*'),
+        })
+    }
+
+    /// Standard cron day matching: when both day fields are restricted, a
+    /// date matches if either does.
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+        let dom = self.days_of_month & (1 << date.day()) != 0;
+        let dow = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (true, true) => true,
+            (true, false) => dow,
+            (false, true) => dom,
+            (false, false) => dom || dow,
+        }
+    }
+}
+
+const WEEKDAY_NAMES: &[&str] = &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+const MONTH_NAMES: &[&str] = &["", "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+
+fn cron_field(field: &str, min: u32, max: u32, what: &str, names: &[&str]) -> Result<u64, String> {
+    let value = |s: &str| -> Result<u32, String> {
+        let upper = s.to_uppercase();
+        let n = match names.iter().position(|name| !name.is_empty() && *name == upper) {
+            Some(i) => i as u32,
+            None => s.parse().map_err(|_| format!("Invalid {} in cron expression: {}", what, s))?,
+        };
+        if n < min || n > max {
+            return Err(format!("{} {} is out of range {}-{}", what, n, min, max));
+        }
+        Ok(n)
+    };
+
+    let mut set = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| format!("Invalid step in cron {}: {}", what, part))?;
+                if step == 0 {
+                    return Err(format!("Cron step must be positive: {}", part));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (from, to) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((from, to)) => (value(from)?, value(to)?),
+                None if step > 1 => (value(range)?, max),
+                None => {
+                    let n = value(range)?;
+                    (n, n)
+                }
+            },
+        };
+        if from > to {
+            return Err(format!("Invalid cron range {}", range));
+        }
+        for n in (from..=to).step_by(step as usize) {
+            set |= 1 << n;
+        }
+    }
+    Ok(set)
+}
+
+fn bits(set: u64) -> Vec<u32> {
+    (0..64).filter(|n| set & (1 << n) != 0).collect()
+}
+
+impl RRule {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut freq = None;
+        let mut rule = RRule {
+            freq: Frequency::Daily,
+            interval: 1,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_month: Vec::new(),
+            by_hour: Vec::new(),
+            by_minute: Vec::new(),
+            until: None,
+            count: None,
+        };
+
+        for part in spec.trim().split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid RRULE part: {}", part))?;
+            match key.trim().to_uppercase().as_str() {
+                "FREQ" => {
+                    freq = Some(match value.to_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        other => return Err(format!("Unsupported FREQ: {}", other)),
+                    })
+                }
+                "INTERVAL" => {
+                    rule.interval = value
+                        .parse()
+                        .ok()
+                        .filter(|n| (1..=366).contains(n))
+                        .ok_or_else(|| format!("Invalid INTERVAL: {}", value))?
+                }
+                "BYDAY" => rule.by_day = list(value, |day| weekday(day).ok_or_else(|| format!("Invalid BYDAY: {}", day)))?,
+                "BYMONTHDAY" => {
+                    rule.by_month_day = list(value, |day| {
+                        day.parse()
+                            .ok()
+                            .filter(|d: &i32| *d != 0 && (-31..=31).contains(d))
+                            .ok_or_else(|| format!("Invalid BYMONTHDAY: {}", day))
+                    })?
+                }
+                "BYMONTH" => rule.by_month = list(value, |m| number(m, 1, 12, "BYMONTH"))?,
+                "BYHOUR" => rule.by_hour = list(value, |h| number(h, 0, 23, "BYHOUR"))?,
+                "BYMINUTE" => rule.by_minute = list(value, |m| number(m, 0, 59, "BYMINUTE"))?,
+                "UNTIL" => {
+                    let date = value.get(..8).unwrap_or(value);
+                    rule.until = Some(
+                        NaiveDate::parse_from_str(date, "%Y%m%d")
+                            .map_err(|_| format!("Invalid UNTIL (expected YYYYMMDD): {}", value))?,
+                    )
+                }
+                "COUNT" => rule.count = Some(number(value, 1, MAX_COUNT, "COUNT")?),
+                "WKST" => {}
+                other => return Err(format!("Unsupported RRULE part: {}", other)),
+            }
+        }
+
+        rule.freq = freq.ok_or("RRULE is missing FREQ")?;
+        if rule.count.is_some() && rule.until.is_some() {
+            return Err("RRULE can't have both COUNT and UNTIL".to_string());
+        }
+        Ok(rule)
+    }
+
+    /// For a rule that ends, its last day and how many of that day's times
+    /// are still occurrences.
+    fn end(&self, start: Option<NaiveDate>) -> Option<(NaiveDate, usize)> {
+        let per_day = self.by_hour.len().max(1) * self.by_minute.len().max(1);
+        let Some(count) = self.count else {
+            return self.until.map(|until| (until, per_day));
+        };
+        let mut left = count as usize;
+        for date in start.unwrap_or_else(default_anchor).iter_days().take(COUNT_SEARCH_DAYS) {
+            if !self.matches_date(date, start) {
+                continue;
+            }
+            if left <= per_day {
+                return Some((date, left));
+            }
+            left -= per_day;
+        }
+        None
+    }
+
+    fn matches_date(&self, date: NaiveDate, start: Option<NaiveDate>) -> bool {
+        let anchor = start.unwrap_or_else(default_anchor);
+        if date < anchor || self.until.is_some_and(|until| date > until) {
+            return false;
+        }
+        if !self.by_month.is_empty() && !self.by_month.contains(&date.month()) {
+            return false;
+        }
+        if !self.by_day.is_empty() && !self.by_day.contains(&date.weekday()) {
+            return false;
+        }
+        if !self.by_month_day.is_empty() && !self.by_month_day.iter().any(|d| month_day_matches(*d, date)) {
+            return false;
+        }
+
+        let interval = self.interval as i64;
+        match self.freq {
+            Frequency::Daily => (date - anchor).num_days() % interval == 0,
+            Frequency::Weekly => {
+                let week = |d: NaiveDate| d - Duration::days(d.weekday().num_days_from_monday() as i64);
+                let in_week = (week(date) - week(anchor)).num_weeks() % interval == 0;
+                in_week && (!self.by_day.is_empty() || date.weekday() == anchor.weekday())
+            }
+            Frequency::Monthly => {
+                let months = (date.year() - anchor.year()) as i64 * 12 + date.month() as i64 - anchor.month() as i64;
+                let default_day = self.by_day.is_empty() && self.by_month_day.is_empty();
+                months % interval == 0 && (!default_day || same_day_of_month(anchor, date))
+            }
+            Frequency::Yearly => {
+                let years = (date.year() - anchor.year()) as i64;
+                let default_day = self.by_day.is_empty() && self.by_month_day.is_empty() && self.by_month.is_empty();
+                years % interval == 0 && (!default_day || (date.month() == anchor.month() && date.day() == anchor.day()))
+            }
+        }
+    }
+}
+
+fn default_anchor() -> NaiveDate {
+    let (y, m, d) = DEFAULT_ANCHOR;
+    NaiveDate::from_ymd_opt(y, m, d).unwrap_or_default()
+}
+
+fn month_day_matches(day: i32, date: NaiveDate) -> bool {
+    if day > 0 {
+        return date.day() == day as u32;
+    }
+    let last = last_day_of_month(date);
+    last as i32 + day + 1 == date.day() as i32
+}
+
+/// Monthly repeats of the 29th-31st fall on the last day of shorter months.
+fn same_day_of_month(anchor: NaiveDate, date: NaiveDate) -> bool {
+    date.day() == anchor.day() || (anchor.day() > date.day() && date.day() == last_day_of_month(date))
+}
+
+fn last_day_of_month(date: NaiveDate) -> u32 {
+    date.with_day(1)
+        .and_then(|first| first.checked_add_months(Months::new(1)))
+        .map(|next| (next - Duration::days(1)).day())
+        .unwrap_or(28)
+}
+
+fn weekday(day: &str) -> Option<Weekday> {
+    Some(match day.trim().to_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+fn number(value: &str, min: u32, max: u32, what: &str) -> Result<u32, String> {
+    value
+        .trim()
+        .parse()
+        .ok()
+        .filter(|n| (min..=max).contains(n))
+        .ok_or_else(|| format!("Invalid {}: {}", what, value))
+}
+
+fn list<T: PartialEq>(value: &str, parse: impl Fn(&str) -> Result<T, String>) -> Result<Vec<T>, String> {
+    let mut items = Vec::new();
+    for item in value.split(',') {
+        let item = parse(item.trim())?;
+        if !items.contains(&item) {
+            items.push(item);
+        }
+    }
+    Ok(items)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    fn parse(spec: &str) -> Schedule {
+        Schedule::parse(spec).unwrap_or_else(|e| panic!("{}: {}", spec, e))
+    }
+
+    /// Up to `n` occurrences after `after`.
+    fn next(spec: &str, after: DateTime<Utc>, start: Option<NaiveDate>, n: usize) -> Vec<DateTime<Utc>> {
+        let schedule = parse(spec);
+        let schedule = schedule.anchored(start);
+        let mut times = Vec::new();
+        let mut at = after;
+        while times.len() < n {
+            let Some(next) = schedule.next_after(&at) else { break };
+            times.push(next);
+            at = next;
+        }
+        times
+    }
+
+    fn days(spec: &str, from: NaiveDate, to: NaiveDate, start: Option<NaiveDate>) -> Vec<NaiveDate> {
+        let schedule = parse(spec);
+        let schedule = schedule.anchored(start);
+        from.iter_days().take_while(|d| *d <= to).filter(|d| schedule.occurs_on(*d)).collect()
+    }
+
+    #[test]
+    fn cron_ranges_lists_and_steps() {
+        assert_eq!(
+            next("*/20 9-17/4 * * *", utc(2026, 3, 2, 9, 50), None, 4),
+            [utc(2026, 3, 2, 13, 0), utc(2026, 3, 2, 13, 20), utc(2026, 3, 2, 13, 40), utc(2026, 3, 2, 17, 0)]
+        );
+        // A step from a single value runs to the end of the field.
+        assert_eq!(
+            next("10/25 8,12 * * *", utc(2026, 3, 2, 12, 35), None, 3),
+            [utc(2026, 3, 3, 8, 10), utc(2026, 3, 3, 8, 35), utc(2026, 3, 3, 12, 10)]
+        );
+        assert_eq!(
+            days("0 9 1-3,15 * *", date(2026, 3, 1), date(2026, 3, 31), None),
+            [date(2026, 3, 1), date(2026, 3, 2), date(2026, 3, 3), date(2026, 3, 15)]
+        );
+    }
+
+    #[test]
+    fn cron_sunday_is_0_and_7() {
+        assert_eq!(parse("0 9 * * 0"), parse("0 9 * * 7"));
+        assert_eq!(parse("0 9 * * 5-7"), parse("0 9 * * 0,5,6"));
+        // 2026-03-01 is a Sunday.
+        assert_eq!(next("0 9 * * 7", utc(2026, 2, 26, 0, 0), None, 1), [utc(2026, 3, 1, 9, 0)]);
+        assert_eq!(
+            days("0 9 * * 5-7", date(2026, 3, 2), date(2026, 3, 8), None),
+            [date(2026, 3, 6), date(2026, 3, 7), date(2026, 3, 8)]
+        );
+    }
+
+    #[test]
+    fn cron_names() {
+        assert_eq!(parse("30 9 * * MON-FRI"), parse("30 9 * * 1-5"));
+        assert_eq!(parse("30 9 * * mon,Wed"), parse("30 9 * * 1,3"));
+        assert_eq!(parse("0 0 1 JAN,jul *"), parse("0 0 1 1,7 *"));
+        assert_eq!(
+            next("30 9 * * MON-FRI", utc(2026, 3, 6, 10, 0), None, 2),
+            [utc(2026, 3, 9, 9, 30), utc(2026, 3, 10, 9, 30)]
+        );
+    }
+
+    #[test]
+    fn cron_day_of_month_or_day_of_week() {
+        // Fridays and the 13th, as in standard cron.
+        assert_eq!(
+            days("0 9 13 * FRI", date(2026, 3, 1), date(2026, 3, 20), None),
+            [date(2026, 3, 6), date(2026, 3, 13), date(2026, 3, 20)]
+        );
+        assert_eq!(days("0 9 14 * FRI", date(2026, 3, 12), date(2026, 3, 14), None), [date(2026, 3, 13), date(2026, 3, 14)]);
+    }
+
+    #[test]
+    fn rrule_last_day_of_month() {
+        assert_eq!(
+            next("FREQ=MONTHLY;BYMONTHDAY=-1;BYHOUR=18", utc(2026, 2, 10, 0, 0), None, 3),
+            [utc(2026, 2, 28, 18, 0), utc(2026, 3, 31, 18, 0), utc(2026, 4, 30, 18, 0)]
+        );
+        assert_eq!(
+            next("RRULE:FREQ=MONTHLY;BYMONTHDAY=-1", utc(2028, 2, 1, 0, 0), None, 1),
+            [utc(2028, 2, 29, 0, 0)]
+        );
+        assert_eq!(days("FREQ=MONTHLY;BYMONTHDAY=-2", date(2026, 4, 1), date(2026, 4, 30), None), [date(2026, 4, 29)]);
+    }
+
+    #[test]
+    fn rrule_interval_counts_from_the_start_date() {
+        // 2026-03-02 is a Monday.
+        let start = Some(date(2026, 3, 2));
+        assert_eq!(
+            days("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH", date(2026, 2, 23), date(2026, 3, 19), start),
+            [date(2026, 3, 2), date(2026, 3, 5), date(2026, 3, 16), date(2026, 3, 19)]
+        );
+        assert_eq!(
+            days("FREQ=DAILY;INTERVAL=3", date(2026, 3, 1), date(2026, 3, 10), start),
+            [date(2026, 3, 2), date(2026, 3, 5), date(2026, 3, 8)]
+        );
+        // Without BYDAY a weekly rule repeats on the start's weekday.
+        assert_eq!(
+            days("FREQ=WEEKLY", date(2026, 3, 1), date(2026, 3, 14), Some(date(2026, 3, 4))),
+            [date(2026, 3, 4), date(2026, 3, 11)]
+        );
+        // Monthly on the 31st falls back to the last day of shorter months.
+        assert_eq!(
+            next("FREQ=MONTHLY;BYHOUR=8", utc(2026, 1, 1, 0, 0), Some(date(2026, 1, 31)), 3),
+            [utc(2026, 1, 31, 8, 0), utc(2026, 2, 28, 8, 0), utc(2026, 3, 31, 8, 0)]
+        );
+    }
+
+    #[test]
+    fn rrule_until() {
+        assert_eq!(
+            next("FREQ=DAILY;UNTIL=20260304;BYHOUR=9", utc(2026, 3, 2, 12, 0), None, 5),
+            [utc(2026, 3, 3, 9, 0), utc(2026, 3, 4, 9, 0)]
+        );
+        assert_eq!(parse("FREQ=DAILY;UNTIL=20260304T235959Z"), parse("FREQ=DAILY;UNTIL=20260304"));
+        assert!(!parse("FREQ=DAILY;UNTIL=20260304").anchored(None).occurs_on(date(2026, 3, 5)));
+    }
+
+    #[test]
+    fn rrule_count() {
+        let start = Some(date(2026, 3, 2));
+        assert_eq!(
+            next("FREQ=DAILY;COUNT=3;BYHOUR=9", utc(2026, 3, 1, 0, 0), start, 5),
+            [utc(2026, 3, 2, 9, 0), utc(2026, 3, 3, 9, 0), utc(2026, 3, 4, 9, 0)]
+        );
+        // Each time of day counts, so the last day can be cut short.
+        assert_eq!(
+            next("FREQ=DAILY;COUNT=3;BYHOUR=9,17", utc(2026, 3, 1, 0, 0), start, 5),
+            [utc(2026, 3, 2, 9, 0), utc(2026, 3, 2, 17, 0), utc(2026, 3, 3, 9, 0)]
+        );
+        assert_eq!(
+            days("FREQ=WEEKLY;BYDAY=MO,FR;COUNT=3", date(2026, 3, 1), date(2026, 3, 31), start),
+            [date(2026, 3, 2), date(2026, 3, 6), date(2026, 3, 9)]
+        );
+        // Counting starts at the start date, not at the first lookup.
+        assert_eq!(next("FREQ=DAILY;COUNT=3", utc(2026, 3, 3, 12, 0), start, 5), [utc(2026, 3, 4, 0, 0)]);
+        assert!(next("FREQ=DAILY;COUNT=3", utc(2026, 3, 4, 12, 0), start, 5).is_empty());
+    }
+
+    #[test]
+    fn anchored_schedules_agree_with_one_off_lookups() {
+        let start = Some(date(2026, 3, 2));
+        let schedule = parse("FREQ=WEEKLY;BYDAY=MO,WE;BYHOUR=9,18;COUNT=7");
+        let anchored = schedule.anchored(start);
+        for day in date(2026, 3, 1).iter_days().take(30) {
+            let at = Utc.from_utc_datetime(&day.and_hms_opt(12, 0, 0).unwrap());
+            assert_eq!(anchored.next_after(&at), schedule.next_after(&at, start), "{}", day);
+        }
+        assert_eq!(days("FREQ=WEEKLY;BYDAY=MO,WE;BYHOUR=9,18;COUNT=7", date(2026, 3, 1), date(2026, 3, 31), start).len(), 4);
+    }
+
+    #[test]
+    fn a_count_that_is_never_reached_does_not_end_the_rule() {
+        // 30 February never comes, so the rule neither matches nor ends.
+        let never = "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30;COUNT=2";
+        assert!(days(never, date(2026, 1, 1), date(2030, 12, 31), Some(date(2026, 1, 1))).is_empty());
+        assert!(next(never, utc(2026, 1, 1, 0, 0), Some(date(2026, 1, 1)), 1).is_empty());
+        // Occurrences before the search limit still count toward the end.
+        assert_eq!(
+            days("FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=29;COUNT=2", date(2026, 1, 1), date(2036, 12, 31), Some(date(2026, 1, 1))),
+            [date(2028, 2, 29), date(2032, 2, 29)]
+        );
+    }
+
+    fn berlin(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<chrono_tz::Tz> {
+        chrono_tz::Europe::Berlin.with_ymd_and_hms(y, m, d, h, min, 0).earliest().unwrap()
+    }
+
+    #[test]
+    fn a_time_in_the_spring_forward_gap_fires_when_the_gap_ends() {
+        // Clocks in Berlin go from 02:00 to 03:00 on 2026-03-29.
+        let schedule = parse("30 2 * * *");
+        let first = schedule.next_after(&berlin(2026, 3, 29, 0, 0), None).unwrap();
+        assert_eq!(first.naive_utc(), utc(2026, 3, 29, 1, 0).naive_utc());
+        assert_eq!(first.format("%H:%M %Z").to_string(), "03:00 CEST");
+        assert_eq!(schedule.next_after(&first, None).unwrap(), berlin(2026, 3, 30, 2, 30));
+
+        let gap = chrono::NaiveDate::from_ymd_opt(2026, 3, 29).unwrap().and_hms_opt(2, 30, 0).unwrap();
+        assert_eq!(resolve(&chrono_tz::Europe::Berlin, gap), Some(first));
+    }
+
+    #[test]
+    fn a_time_in_the_repeated_hour_fires_once() {
+        // Clocks in Berlin go from 03:00 back to 02:00 on 2026-10-25, so
+        // 02:30 happens at 00:30 and again at 01:30 UTC.
+        let schedule = parse("FREQ=DAILY;BYHOUR=2;BYMINUTE=30");
+        let first = schedule.next_after(&berlin(2026, 10, 25, 0, 0), None).unwrap();
+        assert_eq!(first.naive_utc(), utc(2026, 10, 25, 0, 30).naive_utc());
+        let second = schedule.next_after(&first, None).unwrap();
+        assert_eq!(second, berlin(2026, 10, 26, 2, 30));
+        // Asking from inside the repeated hour doesn't find it again either.
+        let repeated = first + Duration::hours(1);
+        assert_eq!(schedule.next_after(&repeated, None), Some(second));
+    }
+
+    #[test]
+    fn malformed_input_is_rejected() {
+        for spec in [
+            "",
+            "* * * *",
+            "* * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * 32 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+            "* * * * FOO",
+            "* * * * MON-",
+            "INTERVAL=2",
+            "FREQ=",
+            "FREQ=HOURLY",
+            "FREQ=DAILY;INTERVAL=0",
+            "FREQ=DAILY;INTERVAL=x",
+            "FREQ=DAILY;BYDAY=XX",
+            "FREQ=DAILY;BYMONTHDAY=0",
+            "FREQ=DAILY;BYMONTHDAY=32",
+            "FREQ=DAILY;BYMONTH=13",
+            "FREQ=DAILY;BYHOUR=24",
+            "FREQ=DAILY;BYMINUTE=60",
+            "FREQ=DAILY;UNTIL=2026",
+            "FREQ=DAILY;COUNT=0",
+            "FREQ=DAILY;COUNT=1001",
+            "FREQ=DAILY;COUNT=2;UNTIL=20260304",
+            "FREQ=DAILY;BYHOUR",
+            "FREQ=DAILY;BYSETPOS=1",
+        ] {
+            assert!(Schedule::parse(spec).is_err(), "accepted {:?}", spec);
+        }
+    }
+}
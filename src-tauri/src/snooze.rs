@@ -0,0 +1,294 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration as StdDuration;
+
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::storage::{self, format_time, parse_time};
+
+pub const SNOOZE_FILE: &str = "snoozes.json";
+
+/// Longest snooze accepted, so a typo doesn't silence an alert for days.
+pub const MAX_SNOOZE_MINUTES: u32 = 24 * 60;
+
+const MAX_WAIT: StdDuration = StdDuration::from_secs(30);
+
+/// An alert the user can snooze or acknowledge.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AlertRef {
+    /// A prompt to switch to `mode` ("sitting" or "standing").
+    Stand { mode: String },
+    Reminder {
+        #[serde(rename = "ruleId")]
+        rule_id: String,
+        title: String,
+        #[serde(default)]
+        body: String,
+    },
+}
+
+impl AlertRef {
+    /// Whether both refer to the same alert; a reminder is identified by its
+    /// rule and there is only ever one stand prompt.
+    pub fn same_as(&self, other: &AlertRef) -> bool {
+        match (self, other) {
+            (AlertRef::Stand { .. }, AlertRef::Stand { .. }) => true,
+            (AlertRef::Reminder { rule_id: a, .. }, AlertRef::Reminder { rule_id: b, .. }) => a == b,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Snooze {
+    pub id: String,
+    pub alert: AlertRef,
+    /// When the alert comes back.
+    pub until: String,
+    #[serde(rename = "snoozedAt")]
+    pub snoozed_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct SnoozeStore {
+    #[serde(default)]
+    snoozes: Vec<Snooze>,
+}
+
+struct Inner {
+    path: PathBuf,
+    store: Mutex<SnoozeStore>,
+    changed: Condvar,
+}
+
+/// Snoozed stand and reminder alerts, persisted in `snoozes.json` so they
+/// come back after a restart. A worker thread re-fires each one when its time
+/// is up; snoozes that ran out while the app was closed fire on startup.
+#[derive(Clone)]
+pub struct Snoozes {
+    inner: Arc<Inner>,
+}
+
+impl Snoozes {
+    pub fn load(path: PathBuf) -> Self {
+        let store = storage::read_json(&path);
+        Self {
+            inner: Arc::new(Inner { path, store: Mutex::new(store), changed: Condvar::new() }),
+        }
+    }
+
+    /// Snoozes `alert` for `minutes`, replacing any earlier snooze of it.
+    pub fn snooze(&self, alert: AlertRef, minutes: u32) -> Result<Snooze, String> {
+        if minutes == 0 || minutes > MAX_SNOOZE_MINUTES {
+            return Err(format!("Snooze must be between 1 and {} minutes", MAX_SNOOZE_MINUTES));
+        }
+        let now = Utc::now();
+        let snooze = Snooze {
+            id: Uuid::new_v4().to_string(),
+            alert,
+            until: format_time(now + Duration::minutes(minutes as i64)),
+            snoozed_at: format_time(now),
+        };
+        self.update(|store| {
+            store.snoozes.retain(|s| !s.alert.same_as(&snooze.alert));
+            store.snoozes.push(snooze.clone());
+        });
+        Ok(snooze)
+    }
+
+    /// Drops the snooze of `alert`, if any. Returns whether there was one.
+    pub fn cancel(&self, alert: &AlertRef) -> bool {
+        self.update(|store| {
+            let before = store.snoozes.len();
+            store.snoozes.retain(|s| !s.alert.same_as(alert));
+            store.snoozes.len() != before
+        })
+    }
+
+    pub fn cancel_id(&self, id: &str) -> bool {
+        self.update(|store| {
+            let before = store.snoozes.len();
+            store.snoozes.retain(|s| s.id != id);
+            store.snoozes.len() != before
+        })
+    }
+
+    pub fn stand_snoozed(&self) -> bool {
+        self.lock().snoozes.iter().any(|s| matches!(s.alert, AlertRef::Stand { .. }))
+    }
+
+    /// Soonest first.
+    pub fn list(&self) -> Vec<Snooze> {
+        let mut snoozes = self.lock().snoozes.clone();
+        snoozes.sort_by(|a, b| a.until.cmp(&b.until));
+        snoozes
+    }
+
+    /// Calls `fire` with each snoozed alert once its time is up. The snooze
+    /// is removed before `fire` runs, which may snooze the alert again.
+    pub fn run<F>(&self, fire: F)
+    where
+        F: Fn(AlertRef) + Send + 'static,
+    {
+        let snoozes = self.clone();
+        thread::spawn(move || loop {
+            let now = Utc::now();
+            let due: Vec<Snooze> = {
+                let mut store = snoozes.lock();
+                let (due, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut store.snoozes)
+                    .into_iter()
+                    .partition(|s| parse_time(&s.until).is_none_or(|until| until <= now));
+                store.snoozes = waiting;
+                if !due.is_empty() {
+                    snoozes.save(&store);
+                }
+                due
+            };
+            for snooze in due {
+                fire(snooze.alert);
+            }
+
+            let store = snoozes.lock();
+            let wait = store
+                .snoozes
+                .iter()
+                .filter_map(|s| parse_time(&s.until))
+                .min()
+                .and_then(|next| (next - Utc::now()).to_std().ok())
+                .map_or(MAX_WAIT, |d| d.min(MAX_WAIT));
+            let _ = snoozes.inner.changed.wait_timeout(store, wait);
+        });
+    }
+
+    /// Applies `f` to the store, saves it and wakes the worker.
+    fn update<T>(&self, f: impl FnOnce(&mut SnoozeStore) -> T) -> T {
+        let mut store = self.lock();
+        let result = f(&mut store);
+        self.save(&store);
+        self.inner.changed.notify_all();
+        result
+    }
+
+    fn save(&self, store: &SnoozeStore) {
+        if let Err(e) = storage::write_json(&self.inner.path, store) {
+            eprintln!("Failed to save snoozes: {}", e);
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SnoozeStore> {
+        self.inner.store.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+    use std::sync::mpsc;
+
+    fn snoozes() -> (Snoozes, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("growing-snooze-{}", Uuid::new_v4()));
+        (Snoozes::load(dir.join(SNOOZE_FILE)), dir)
+    }
+
+    fn reminder(rule_id: &str, title: &str) -> AlertRef {
+        AlertRef::Reminder { rule_id: rule_id.to_string(), title: title.to_string(), body: String::new() }
+    }
+
+    fn stand(mode: &str) -> AlertRef {
+        AlertRef::Stand { mode: mode.to_string() }
+    }
+
+    #[test]
+    fn snoozes_survive_a_restart() {
+        let (snoozes, dir) = snoozes();
+        let snooze = snoozes.snooze(reminder("r", "Stretch"), 10).unwrap();
+        let until = parse_time(&snooze.until).unwrap() - parse_time(&snooze.snoozed_at).unwrap();
+        assert_eq!(until, Duration::minutes(10));
+
+        let reloaded = Snoozes::load(dir.join(SNOOZE_FILE));
+        let listed = reloaded.list();
+        assert_eq!((listed.len(), &listed[0].id, &listed[0].alert), (1, &snooze.id, &snooze.alert));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn snoozing_again_replaces_the_earlier_snooze() {
+        let (snoozes, dir) = snoozes();
+        snoozes.snooze(reminder("r", "Stretch"), 30).unwrap();
+        snoozes.snooze(stand("standing"), 5).unwrap();
+        snoozes.snooze(reminder("other", "Water"), 20).unwrap();
+        // The same rule with a new body, and the stand prompt now asking to sit.
+        let again = snoozes.snooze(reminder("r", "Stretch again"), 10).unwrap();
+        snoozes.snooze(stand("sitting"), 15).unwrap();
+
+        let alerts: Vec<AlertRef> = snoozes.list().into_iter().map(|s| s.alert).collect();
+        assert_eq!(alerts, [reminder("r", "Stretch again"), stand("sitting"), reminder("other", "Water")]);
+        assert_eq!(snoozes.list()[0].id, again.id);
+        assert!(snoozes.stand_snoozed());
+
+        for minutes in [0, MAX_SNOOZE_MINUTES + 1] {
+            assert_eq!(snoozes.snooze(stand("sitting"), minutes).unwrap_err(), "Snooze must be between 1 and 1440 minutes");
+        }
+        assert_eq!(snoozes.list().len(), 3);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn acknowledging_an_alert_clears_its_snooze() {
+        let (snoozes, dir) = snoozes();
+        snoozes.snooze(reminder("r", "Stretch"), 10).unwrap();
+        let stand_snooze = snoozes.snooze(stand("standing"), 10).unwrap();
+
+        // Acknowledging cancels by alert, whatever the reminder's text now is.
+        assert!(snoozes.cancel(&reminder("r", "Renamed")));
+        assert!(!snoozes.cancel(&reminder("r", "Stretch")));
+        assert!(!snoozes.cancel_id("missing"));
+        assert!(snoozes.cancel_id(&stand_snooze.id));
+        assert!(!snoozes.stand_snoozed());
+        assert!(Snoozes::load(dir.join(SNOOZE_FILE)).list().is_empty());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn snoozes_that_ran_out_fire_once() {
+        let (_, dir) = snoozes();
+        let snooze = |alert, until: DateTime<Utc>| Snooze {
+            id: Uuid::new_v4().to_string(),
+            alert,
+            until: format_time(until),
+            snoozed_at: format_time(until - Duration::minutes(10)),
+        };
+        let mut unreadable = snooze(reminder("broken", "Broken"), Utc::now());
+        unreadable.until = "soon".to_string();
+        let store = SnoozeStore {
+            snoozes: vec![
+                snooze(reminder("r", "Stretch"), Utc::now() - Duration::hours(2)),
+                snooze(stand("standing"), Utc::now() + Duration::hours(1)),
+                unreadable,
+            ],
+        };
+        storage::write_json(&dir.join(SNOOZE_FILE), &store).unwrap();
+
+        let snoozes = Snoozes::load(dir.join(SNOOZE_FILE));
+        let (tx, rx) = mpsc::channel();
+        snoozes.run(move |alert| tx.send(alert).unwrap());
+        let mut fired = vec![
+            rx.recv_timeout(StdDuration::from_secs(5)).unwrap(),
+            rx.recv_timeout(StdDuration::from_secs(5)).unwrap(),
+        ];
+        fired.sort_by_key(|alert| format!("{:?}", alert));
+        assert_eq!(fired, [reminder("broken", "Broken"), reminder("r", "Stretch")]);
+        assert!(rx.recv_timeout(StdDuration::from_millis(200)).is_err());
+
+        // Only the snooze still waiting is left, also on disk.
+        let waiting: Vec<AlertRef> = Snoozes::load(dir.join(SNOOZE_FILE)).list().into_iter().map(|s| s.alert).collect();
+        assert_eq!(waiting, [stand("standing")]);
+        assert!(snoozes.stand_snoozed());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
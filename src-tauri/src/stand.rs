@@ -0,0 +1,302 @@
+use std::sync::{Mutex, MutexGuard};
+
+use chrono::{DateTime, Duration};
+use chrono_tz::Tz;
+
+use crate::idle::IdleDetector;
+use crate::models::NotificationSettings;
+use crate::storage::parse_time;
+
+/// Gaps between ticks longer than this mean the machine was asleep.
+const SLEEP_GAP: Duration = Duration::minutes(2);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StandAction {
+    None,
+    /// Switch to `mode` ("sitting" or "standing") and alert the user.
+    Switch { mode: &'static str },
+    /// The user came back after being away since `away_since`; restart or
+    /// shift the current period without alerting.
+    Resume { mode: String, changed_at: DateTime<Tz>, away_since: DateTime<Tz> },
+}
+
+#[derive(Default)]
+struct TickState {
+    last_tick: Option<DateTime<Tz>>,
+    away_since: Option<DateTime<Tz>>,
+    woke: bool,
+    locked_since: Option<DateTime<Tz>>,
+}
+
+/// The sit/stand cycle. Time spent idle or asleep doesn't count towards the
+/// current period, so coming back from lunch doesn't immediately ask the user
+/// to stand.
+pub struct StandCycle {
+    idle: Box<dyn IdleDetector>,
+    state: Mutex<TickState>,
+}
+
+impl StandCycle {
+    pub fn new(idle: Box<dyn IdleDetector>) -> Self {
+        Self { idle, state: Mutex::new(TickState::default()) }
+    }
+
+    /// Called when the system wakes; the time since the last tick counts as
+    /// away.
+    pub fn wake(&self) {
+        self.lock().woke = true;
+    }
+
+    /// Called when the screen is locked. A lock lasting the idle threshold
+    /// counts as away, even where idle time can't be read behind the lock
+    /// screen.
+    pub fn screen_locked(&self, at: DateTime<Tz>) {
+        self.lock().locked_since = Some(at);
+    }
+
+    pub fn screen_unlocked(&self) {
+        self.lock().locked_since = None;
+    }
+
+    /// Advances the cycle. `allowed` is false while alerts are held back
+    /// (quiet hours, timeboxes), in which case a due switch waits.
+    pub fn tick(&self, settings: &NotificationSettings, now: DateTime<Tz>, allowed: bool) -> StandAction {
+        let mut state = self.lock();
+        if !settings.enabled || !settings.stand_reminder_enabled {
+            *state = TickState { locked_since: state.locked_since, ..TickState::default() };
+            return StandAction::None;
+        }
+
+        let previous = state.last_tick.replace(now);
+        let threshold = Duration::minutes(settings.stand_idle_minutes as i64);
+        let idle_enabled = settings.stand_idle_minutes > 0;
+
+        if idle_enabled {
+            let slept = std::mem::take(&mut state.woke) || previous.is_some_and(|t| now - t > SLEEP_GAP.max(threshold));
+            if slept && state.away_since.is_none() {
+                state.away_since = Some(previous.unwrap_or(now));
+            }
+
+            if let Some(since) = state.locked_since.filter(|since| now - *since >= threshold) {
+                state.away_since.get_or_insert(since);
+                return StandAction::None;
+            }
+
+            if let Some(idle) = self.idle.idle_seconds().map(|s| Duration::seconds(s as i64)) {
+                if idle >= threshold {
+                    state.away_since.get_or_insert(now - idle);
+                    return StandAction::None;
+                }
+            }
+
+            if let Some(since) = state.away_since.take() {
+                return resume(settings, since, now);
+            }
+        }
+
+        let mode = current_mode(settings);
+        let duration = if mode == "sitting" {
+            settings.sit_duration_minutes
+        } else {
+            settings.stand_duration_minutes
+        };
+        let should_switch = match settings.stand_mode_changed_at.as_deref().and_then(parse_time) {
+            Some(changed) => now.signed_duration_since(changed).num_minutes() >= duration as i64,
+            // Invalid or missing timestamp: start the cycle.
+            _ => true,
+        };
+
+        if should_switch && allowed {
+            StandAction::Switch { mode: if mode == "sitting" { "standing" } else { "sitting" } }
+        } else {
+            StandAction::None
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, TickState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn resume(settings: &NotificationSettings, away_since: DateTime<Tz>, now: DateTime<Tz>) -> StandAction {
+    let changed = settings
+        .stand_mode_changed_at
+        .as_deref()
+        .and_then(parse_time)
+        .map(|t| t.with_timezone(&now.timezone()));
+
+    match (settings.stand_idle_action.as_str(), changed) {
+        ("pause", Some(changed)) => StandAction::Resume {
+            mode: current_mode(settings).to_string(),
+            changed_at: changed + (now - away_since).max(Duration::zero()),
+            away_since,
+        },
+        _ => StandAction::Resume { mode: "sitting".to_string(), changed_at: now, away_since },
+    }
+}
+
+pub fn current_mode(settings: &NotificationSettings) -> &str {
+    if settings.stand_mode.is_empty() {
+        "sitting"
+    } else {
+        &settings.stand_mode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    use crate::idle::MockIdle;
+
+    /// A sitting period started at minute 0, with ticks a minute apart as the
+    /// app's worker makes them.
+    struct Cycle {
+        idle: MockIdle,
+        cycle: StandCycle,
+        settings: NotificationSettings,
+    }
+
+    fn at(minute: i64) -> DateTime<Tz> {
+        chrono_tz::UTC.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap() + Duration::minutes(minute)
+    }
+
+    impl Cycle {
+        fn new(idle_action: &str) -> Self {
+            let idle = MockIdle::new(0);
+            let settings = NotificationSettings {
+                enabled: true,
+                stand_reminder_enabled: true,
+                sit_duration_minutes: 45,
+                stand_duration_minutes: 10,
+                stand_mode: "sitting".to_string(),
+                stand_mode_changed_at: Some(at(0).to_rfc3339()),
+                stand_idle_minutes: 5,
+                stand_idle_action: idle_action.to_string(),
+                ..Default::default()
+            };
+            Self { cycle: StandCycle::new(Box::new(idle.clone())), idle, settings }
+        }
+
+        fn tick(&self, minute: i64) -> StandAction {
+            self.cycle.tick(&self.settings, at(minute), true)
+        }
+
+        /// Ticks through `minutes` with the idle time `idle(minute)` seconds,
+        /// expecting nothing to happen.
+        fn quiet(&self, minutes: std::ops::RangeInclusive<i64>, idle: impl Fn(i64) -> u64) {
+            for minute in minutes {
+                self.idle.set(idle(minute));
+                assert_eq!(self.tick(minute), StandAction::None, "minute {}", minute);
+            }
+        }
+
+        /// Applies a resume the way the app does.
+        fn apply(&mut self, action: &StandAction) {
+            let StandAction::Resume { mode, changed_at, .. } = action else {
+                panic!("expected a resume, got {:?}", action);
+            };
+            self.settings.stand_mode = mode.clone();
+            self.settings.stand_mode_changed_at = Some(changed_at.to_rfc3339());
+        }
+    }
+
+    #[test]
+    fn switches_when_the_period_is_over() {
+        let cycle = Cycle::new("reset");
+        cycle.quiet(0..=44, |_| 30);
+        assert_eq!(cycle.tick(45), StandAction::Switch { mode: "standing" });
+        // Held back while alerts aren't allowed.
+        assert_eq!(cycle.cycle.tick(&cycle.settings, at(46), false), StandAction::None);
+    }
+
+    #[test]
+    fn idle_pause_shifts_the_period_by_the_time_away() {
+        let mut cycle = Cycle::new("pause");
+        cycle.quiet(0..=13, |_| 0);
+        // Away from minute 14; idle reaches the 5 minute threshold at 19.
+        cycle.quiet(14..=40, |minute| (minute - 14) as u64 * 60);
+
+        cycle.idle.set(0);
+        let action = cycle.tick(41);
+        assert_eq!(
+            action,
+            StandAction::Resume { mode: "sitting".to_string(), changed_at: at(27), away_since: at(14) }
+        );
+        cycle.apply(&action);
+
+        // 14 minutes sat before leaving, 31 more after coming back.
+        cycle.quiet(42..=71, |_| 0);
+        assert_eq!(cycle.tick(72), StandAction::Switch { mode: "standing" });
+    }
+
+    #[test]
+    fn idle_reset_starts_a_fresh_sitting_period() {
+        let mut cycle = Cycle::new("reset");
+        cycle.settings.stand_mode = "standing".to_string();
+        cycle.quiet(0..=4, |_| 0);
+        cycle.quiet(5..=20, |minute| (minute - 5) as u64 * 60);
+
+        cycle.idle.set(0);
+        let action = cycle.tick(21);
+        assert_eq!(
+            action,
+            StandAction::Resume { mode: "sitting".to_string(), changed_at: at(21), away_since: at(5) }
+        );
+        cycle.apply(&action);
+        cycle.quiet(22..=65, |_| 0);
+        assert_eq!(cycle.tick(66), StandAction::Switch { mode: "standing" });
+    }
+
+    #[test]
+    fn short_idle_does_not_pause() {
+        let cycle = Cycle::new("pause");
+        cycle.quiet(0..=44, |minute| if minute % 10 == 0 { 4 * 60 } else { 0 });
+        assert_eq!(cycle.tick(45), StandAction::Switch { mode: "standing" });
+    }
+
+    #[test]
+    fn wake_from_sleep_counts_as_away() {
+        let cycle = Cycle::new("pause");
+        cycle.quiet(0..=10, |_| 0);
+        cycle.cycle.wake();
+        // Idle time reads low after wake, but the sleep still counts.
+        assert_eq!(
+            cycle.tick(11),
+            StandAction::Resume { mode: "sitting".to_string(), changed_at: at(1), away_since: at(10) }
+        );
+    }
+
+    #[test]
+    fn a_gap_between_ticks_counts_as_sleep_without_a_wake_event() {
+        let cycle = Cycle::new("reset");
+        cycle.quiet(0..=10, |_| 0);
+        assert_eq!(
+            cycle.tick(70),
+            StandAction::Resume { mode: "sitting".to_string(), changed_at: at(70), away_since: at(10) }
+        );
+    }
+
+    #[test]
+    fn screen_lock_counts_as_away_once_past_the_threshold() {
+        let cycle = Cycle::new("reset");
+        cycle.quiet(0..=10, |_| 0);
+        cycle.cycle.screen_locked(at(10));
+        // Idle time can't be read behind the lock screen.
+        cycle.quiet(11..=30, |_| 0);
+        cycle.cycle.screen_unlocked();
+        assert_eq!(
+            cycle.tick(31),
+            StandAction::Resume { mode: "sitting".to_string(), changed_at: at(31), away_since: at(10) }
+        );
+    }
+
+    #[test]
+    fn idle_detection_can_be_turned_off() {
+        let mut cycle = Cycle::new("reset");
+        cycle.settings.stand_idle_minutes = 0;
+        cycle.quiet(0..=44, |_| 3600);
+        assert_eq!(cycle.tick(45), StandAction::Switch { mode: "standing" });
+    }
+}
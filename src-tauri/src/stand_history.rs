@@ -0,0 +1,523 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::NotificationSettings;
+use crate::schedule;
+use crate::storage::{self, format_time, parse_time};
+use crate::tz;
+
+pub const STAND_HISTORY_FILE: &str = "stand_history.json";
+
+const RETENTION: Duration = Duration::days(400);
+
+/// A period is credited at most this many times its configured length. Longer
+/// ones almost always mean the app was closed or alerts were held back, not
+/// that the user stood (or sat) the whole time.
+const MAX_PERIOD_FACTOR: i32 = 2;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum StandEventKind {
+    /// The cycle switched mode and alerted the user.
+    Prompt,
+    /// The user switched mode by hand, or turned stand reminders on.
+    Manual,
+    /// The user came back after being away and the cycle restarted or
+    /// shifted the current period.
+    Resume,
+    /// The user dismissed a stand alert.
+    Acknowledged,
+    /// The user snoozed a stand alert and stays in the mode they were in.
+    Snoozed,
+    /// Stand reminders were turned off.
+    Stopped,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StandEvent {
+    pub id: String,
+    pub at: String,
+    pub kind: StandEventKind,
+    /// The mode in effect from `at` on. Not set for acknowledgements and
+    /// stops.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    /// When the user left, for resumes.
+    #[serde(default, rename = "awaySince", skip_serializing_if = "Option::is_none")]
+    pub away_since: Option<String>,
+}
+
+impl StandEvent {
+    pub fn new(kind: StandEventKind, mode: Option<&str>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            at: format_time(Utc::now()),
+            kind,
+            mode: mode.map(str::to_string),
+            away_since: None,
+        }
+    }
+
+    pub fn resumed(mode: &str, away_since: DateTime<Tz>) -> Self {
+        Self {
+            away_since: Some(format_time(away_since.with_timezone(&Utc))),
+            ..Self::new(StandEventKind::Resume, Some(mode))
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct HistoryStore {
+    #[serde(default)]
+    events: Vec<StandEvent>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct DailyStandStats {
+    pub date: String,
+    #[serde(rename = "standingMinutes")]
+    pub standing_minutes: i64,
+    #[serde(rename = "sittingMinutes")]
+    pub sitting_minutes: i64,
+    /// Alerts asking the user to stand.
+    pub prompts: u32,
+    pub acknowledged: u32,
+    /// Stand prompts that were acknowledged and not cut short.
+    pub complied: u32,
+    #[serde(rename = "complianceRate", skip_serializing_if = "Option::is_none")]
+    pub compliance_rate: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct WeeklyStandStats {
+    /// The Monday the week starts on.
+    #[serde(rename = "weekStart")]
+    pub week_start: String,
+    #[serde(rename = "standingMinutes")]
+    pub standing_minutes: i64,
+    #[serde(rename = "sittingMinutes")]
+    pub sitting_minutes: i64,
+    pub prompts: u32,
+    pub complied: u32,
+    #[serde(rename = "complianceRate", skip_serializing_if = "Option::is_none")]
+    pub compliance_rate: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct StandStats {
+    pub days: Vec<DailyStandStats>,
+    pub weeks: Vec<WeeklyStandStats>,
+    #[serde(rename = "standingMinutes")]
+    pub standing_minutes: i64,
+    #[serde(rename = "sittingMinutes")]
+    pub sitting_minutes: i64,
+    pub prompts: u32,
+    pub complied: u32,
+    #[serde(rename = "complianceRate", skip_serializing_if = "Option::is_none")]
+    pub compliance_rate: Option<f64>,
+    /// Consecutive days, up to today, on which the user stood for at least one
+    /// full standing period. Days without any stand activity (weekends, days
+    /// off) neither extend nor break a streak.
+    #[serde(rename = "currentStreak")]
+    pub current_streak: u32,
+    #[serde(rename = "longestStreak")]
+    pub longest_streak: u32,
+}
+
+/// Every sit/stand transition and alert acknowledgement, persisted in
+/// `stand_history.json`, so standing time and how well the user keeps to the
+/// configured cycle can be reported.
+pub struct StandHistory {
+    path: PathBuf,
+    store: Mutex<HistoryStore>,
+}
+
+impl StandHistory {
+    pub fn load(path: PathBuf) -> Self {
+        let store = storage::read_json(&path);
+        Self { path, store: Mutex::new(store) }
+    }
+
+    pub fn record(&self, event: StandEvent) {
+        let mut store = self.lock();
+        store.events.push(event);
+
+        let cutoff = format_time(Utc::now() - RETENTION);
+        store.events.retain(|e| e.at >= cutoff);
+
+        if let Err(e) = storage::write_json(&self.path, &*store) {
+            eprintln!("Failed to save stand history: {}", e);
+        }
+    }
+
+    /// Events between the two local dates (inclusive), oldest first.
+    pub fn events(&self, from: NaiveDate, to: NaiveDate) -> Vec<StandEvent> {
+        let zone = tz::zone();
+        self.lock()
+            .events
+            .iter()
+            .filter(|e| parse_time(&e.at).is_some_and(|t| (from..=to).contains(&local_date(t, &zone))))
+            .cloned()
+            .collect()
+    }
+
+    /// Standing time, compliance and streaks, judged against the sit and
+    /// stand durations in `settings`.
+    pub fn stats(&self, settings: &NotificationSettings, from: NaiveDate, to: NaiveDate, now: DateTime<Utc>) -> StandStats {
+        let zone = tz::zone();
+        let store = self.lock();
+        // Streaks look at the whole history, not just the requested range.
+        let all = daily_stats(&store.events, settings, now, &zone);
+        drop(store);
+
+        let stand_goal = (settings.stand_duration_minutes as i64).max(1);
+        let active_days: Vec<(NaiveDate, bool)> = all
+            .iter()
+            .filter(|(_, d)| d.standing_minutes + d.sitting_minutes > 0 || d.prompts > 0)
+            .map(|(date, d)| (*date, d.standing_minutes >= stand_goal))
+            .collect();
+        let mut longest_streak = 0;
+        let mut run = 0;
+        for (_, met) in &active_days {
+            run = if *met { run + 1 } else { 0 };
+            longest_streak = longest_streak.max(run);
+        }
+        let today = local_date(now, &zone);
+        // Today doesn't break the streak until it's over.
+        let current_streak = active_days
+            .iter()
+            .rev()
+            .skip_while(|(date, met)| *date == today && !met)
+            .take_while(|(_, met)| *met)
+            .count() as u32;
+
+        let mut days = Vec::new();
+        let mut weeks: BTreeMap<NaiveDate, WeeklyStandStats> = BTreeMap::new();
+        let mut day = from;
+        while day <= to {
+            let stats = all.get(&day).cloned().unwrap_or_else(|| DailyStandStats {
+                date: day.format("%Y-%m-%d").to_string(),
+                ..Default::default()
+            });
+            let monday = day - Duration::days(day.weekday().num_days_from_monday() as i64);
+            let week = weeks.entry(monday).or_insert_with(|| WeeklyStandStats {
+                week_start: monday.format("%Y-%m-%d").to_string(),
+                ..Default::default()
+            });
+            week.standing_minutes += stats.standing_minutes;
+            week.sitting_minutes += stats.sitting_minutes;
+            week.prompts += stats.prompts;
+            week.complied += stats.complied;
+            days.push(stats);
+            day += Duration::days(1);
+        }
+        let weeks: Vec<_> = weeks
+            .into_values()
+            .map(|w| WeeklyStandStats { compliance_rate: rate(w.complied, w.prompts), ..w })
+            .collect();
+
+        let prompts = days.iter().map(|d| d.prompts).sum();
+        let complied = days.iter().map(|d| d.complied).sum();
+        StandStats {
+            standing_minutes: days.iter().map(|d| d.standing_minutes).sum(),
+            sitting_minutes: days.iter().map(|d| d.sitting_minutes).sum(),
+            prompts,
+            complied,
+            compliance_rate: rate(complied, prompts),
+            current_streak,
+            longest_streak,
+            days,
+            weeks,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HistoryStore> {
+        self.store.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A stretch of time spent in one mode.
+struct Period<'a> {
+    mode: &'a str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    /// The event that ended it, `None` if it is still going.
+    ended_by: Option<&'a StandEvent>,
+    acknowledged: bool,
+    prompted: bool,
+}
+
+fn daily_stats(events: &[StandEvent], settings: &NotificationSettings, now: DateTime<Utc>, zone: &Tz) -> BTreeMap<NaiveDate, DailyStandStats> {
+    let mut days: BTreeMap<NaiveDate, DailyStandStats> = BTreeMap::new();
+    let sit = Duration::minutes(settings.sit_duration_minutes as i64);
+    let stand = Duration::minutes(settings.stand_duration_minutes as i64);
+
+    for period in periods(events, now) {
+        let limit = if period.mode == "standing" { stand } else { sit };
+        let end = period.end.min(period.start + limit * MAX_PERIOD_FACTOR);
+        add_minutes(&mut days, period.mode, period.start, end, zone);
+
+        if period.mode != "standing" || !period.prompted {
+            continue;
+        }
+        let stats = day_entry(&mut days, local_date(period.start, zone));
+        stats.prompts += 1;
+        if period.acknowledged {
+            stats.acknowledged += 1;
+            // Switching back to sitting by hand before the time is up cuts
+            // the period short; walking away or the cycle moving on doesn't.
+            let cut_short = period
+                .ended_by
+                .is_some_and(|e| e.kind == StandEventKind::Manual && period.end - period.start < stand);
+            if !cut_short {
+                stats.complied += 1;
+            }
+        }
+    }
+
+    for stats in days.values_mut() {
+        stats.compliance_rate = rate(stats.complied, stats.prompts);
+    }
+    days
+}
+
+fn periods(events: &[StandEvent], now: DateTime<Utc>) -> Vec<Period<'_>> {
+    let mut timed: Vec<(DateTime<Utc>, &StandEvent)> =
+        events.iter().filter_map(|e| parse_time(&e.at).map(|t| (t, e))).collect();
+    timed.sort_by_key(|(t, _)| *t);
+
+    let mut periods: Vec<Period> = Vec::new();
+    let mut current: Option<Period> = None;
+    for (at, event) in timed {
+        if event.kind == StandEventKind::Acknowledged {
+            if let Some(period) = current.as_mut() {
+                period.acknowledged = true;
+            }
+            continue;
+        }
+        if let Some(mut period) = current.take() {
+            // Time away doesn't count towards the period the user left.
+            let away = event.away_since.as_deref().and_then(parse_time);
+            period.end = away.unwrap_or(at).clamp(period.start, at);
+            period.ended_by = Some(event);
+            periods.push(period);
+        }
+        current = event.mode.as_deref().filter(|_| event.kind != StandEventKind::Stopped).map(|mode| Period {
+            mode,
+            start: at,
+            end: at,
+            ended_by: None,
+            acknowledged: false,
+            prompted: event.kind == StandEventKind::Prompt,
+        });
+    }
+    if let Some(mut period) = current {
+        period.end = now.max(period.start);
+        periods.push(period);
+    }
+    periods
+}
+
+/// Adds the span to the days it covers, split at midnight in `zone`.
+fn add_minutes(days: &mut BTreeMap<NaiveDate, DailyStandStats>, mode: &str, start: DateTime<Utc>, end: DateTime<Utc>, zone: &Tz) {
+    let mut cursor = start;
+    while cursor < end {
+        let date = local_date(cursor, zone);
+        let midnight = date
+            .succ_opt()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .and_then(|t| schedule::resolve(zone, t))
+            .map(|t| t.with_timezone(&Utc))
+            .filter(|t| *t > cursor)
+            .unwrap_or(end);
+        let slice_end = midnight.min(end);
+        let minutes = (slice_end - cursor).num_seconds() / 60;
+        let stats = day_entry(days, date);
+        if mode == "standing" {
+            stats.standing_minutes += minutes;
+        } else {
+            stats.sitting_minutes += minutes;
+        }
+        cursor = slice_end;
+    }
+}
+
+fn day_entry(days: &mut BTreeMap<NaiveDate, DailyStandStats>, date: NaiveDate) -> &mut DailyStandStats {
+    days.entry(date).or_insert_with(|| DailyStandStats {
+        date: date.format("%Y-%m-%d").to_string(),
+        ..Default::default()
+    })
+}
+
+fn rate(complied: u32, prompts: u32) -> Option<f64> {
+    (prompts > 0).then(|| complied as f64 / prompts as f64)
+}
+
+fn local_date(time: DateTime<Utc>, zone: &Tz) -> NaiveDate {
+    time.with_timezone(zone).date_naive()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// 2026-03-02 is a Monday.
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, hour, minute, 0).unwrap()
+    }
+
+    fn event(kind: StandEventKind, mode: Option<&str>, time: DateTime<Utc>) -> StandEvent {
+        StandEvent { at: format_time(time), ..StandEvent::new(kind, mode) }
+    }
+
+    fn prompt(mode: &str, time: DateTime<Utc>) -> StandEvent {
+        event(StandEventKind::Prompt, Some(mode), time)
+    }
+
+    fn settings() -> NotificationSettings {
+        NotificationSettings { sit_duration_minutes: 45, stand_duration_minutes: 15, ..Default::default() }
+    }
+
+    fn day(days: &BTreeMap<NaiveDate, DailyStandStats>, d: u32) -> (i64, i64, u32, u32, u32) {
+        let stats = days.get(&NaiveDate::from_ymd_opt(2026, 3, d).unwrap()).cloned().unwrap_or_default();
+        (stats.standing_minutes, stats.sitting_minutes, stats.prompts, stats.acknowledged, stats.complied)
+    }
+
+    #[test]
+    fn events_split_into_periods() {
+        let mut away = event(StandEventKind::Resume, Some("sitting"), at(2, 10, 50));
+        away.away_since = Some(format_time(at(2, 10, 20)));
+        let mut broken = prompt("standing", at(2, 9, 0));
+        broken.at = "yesterday".to_string();
+        let events = vec![
+            prompt("standing", at(2, 9, 45)),
+            event(StandEventKind::Manual, Some("sitting"), at(2, 9, 0)),
+            event(StandEventKind::Acknowledged, None, at(2, 9, 46)),
+            prompt("sitting", at(2, 10, 0)),
+            away,
+            broken,
+            event(StandEventKind::Stopped, None, at(2, 11, 30)),
+            event(StandEventKind::Manual, Some("standing"), at(2, 12, 0)),
+        ];
+
+        let periods: Vec<_> = periods(&events, at(2, 12, 10))
+            .iter()
+            .map(|p| (p.mode, p.start, p.end, p.ended_by.map(|e| e.kind), p.acknowledged, p.prompted))
+            .collect();
+        assert_eq!(
+            periods,
+            [
+                ("sitting", at(2, 9, 0), at(2, 9, 45), Some(StandEventKind::Prompt), false, false),
+                ("standing", at(2, 9, 45), at(2, 10, 0), Some(StandEventKind::Prompt), true, true),
+                // Time away is left out of the period the user walked away from.
+                ("sitting", at(2, 10, 0), at(2, 10, 20), Some(StandEventKind::Resume), false, true),
+                ("sitting", at(2, 10, 50), at(2, 11, 30), Some(StandEventKind::Stopped), false, false),
+                // The last period runs until now.
+                ("standing", at(2, 12, 0), at(2, 12, 10), None, false, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn long_periods_are_capped() {
+        let events = vec![
+            event(StandEventKind::Manual, Some("sitting"), at(2, 6, 0)),
+            prompt("standing", at(2, 9, 0)),
+            event(StandEventKind::Stopped, None, at(2, 12, 0)),
+        ];
+        // Two sit durations (45) and two stand durations (15) at most.
+        let days = daily_stats(&events, &settings(), at(2, 18, 0), &chrono_tz::UTC);
+        assert_eq!(day(&days, 2), (30, 90, 1, 0, 0));
+    }
+
+    #[test]
+    fn periods_over_midnight_are_split_in_the_local_zone() {
+        let events = vec![prompt("standing", at(2, 22, 50)), prompt("sitting", at(2, 23, 10))];
+        let days = daily_stats(&events, &settings(), at(3, 0, 0), &chrono_tz::UTC);
+        assert_eq!((day(&days, 2).0, day(&days, 3).0), (20, 0));
+        // 22:50 UTC is 23:50 in Berlin, so there the period crosses midnight.
+        let days = daily_stats(&events, &settings(), at(3, 0, 0), &chrono_tz::Europe::Berlin);
+        assert_eq!((day(&days, 2).0, day(&days, 3).0), (10, 10));
+        // The prompt is counted on the day the period starts.
+        assert_eq!((day(&days, 2).2, day(&days, 3).2), (1, 0));
+    }
+
+    #[test]
+    fn compliance_is_judged_against_the_stand_duration() {
+        let ack = |time| event(StandEventKind::Acknowledged, None, time);
+        let manual_sit = |time| event(StandEventKind::Manual, Some("sitting"), time);
+        let events = vec![
+            // Acknowledged and kept up until the cycle moved on.
+            prompt("standing", at(2, 9, 0)),
+            ack(at(2, 9, 1)),
+            prompt("sitting", at(2, 9, 15)),
+            // Acknowledged, then cut short by sitting down by hand.
+            prompt("standing", at(2, 10, 0)),
+            ack(at(2, 10, 1)),
+            manual_sit(at(2, 10, 5)),
+            // Sitting down by hand once the time was up is fine.
+            prompt("standing", at(2, 11, 0)),
+            ack(at(2, 11, 1)),
+            manual_sit(at(2, 11, 16)),
+            // Never acknowledged.
+            prompt("standing", at(2, 12, 0)),
+            prompt("sitting", at(2, 12, 15)),
+            // Standing up by hand isn't a prompt.
+            event(StandEventKind::Manual, Some("standing"), at(2, 13, 0)),
+            manual_sit(at(2, 13, 20)),
+        ];
+        let days = daily_stats(&events, &settings(), at(2, 13, 30), &chrono_tz::UTC);
+        assert_eq!(day(&days, 2), (15 + 5 + 16 + 15 + 20, 45 + 55 + 44 + 45 + 10, 4, 3, 2));
+        assert_eq!(days.values().next().unwrap().compliance_rate, Some(0.5));
+
+        // A longer stand duration turns the first stand into a short one too.
+        let strict = NotificationSettings { stand_duration_minutes: 20, ..settings() };
+        let days = daily_stats(&events, &strict, at(2, 13, 30), &chrono_tz::UTC);
+        assert_eq!(day(&days, 2).4, 1);
+    }
+
+    #[test]
+    fn streaks_count_days_that_met_the_stand_goal() {
+        let dir = std::env::temp_dir().join(format!("growing-stand-history-{}", Uuid::new_v4()));
+        let history = StandHistory::load(dir.join(STAND_HISTORY_FILE));
+        let stood = |d: u32, minutes: i64| {
+            history.record(prompt("standing", at(d, 12, 0)));
+            history.record(prompt("sitting", at(d, 12, 0) + Duration::minutes(minutes)));
+            history.record(event(StandEventKind::Stopped, None, at(d, 13, 0)));
+        };
+        stood(2, 15);
+        stood(3, 20);
+        // Nothing on the 4th: a day off doesn't break the streak.
+        stood(5, 15);
+        // The 6th was missed: the user only stood for 10 minutes.
+        stood(6, 10);
+        stood(7, 15);
+        stood(8, 30);
+        history.record(event(StandEventKind::Manual, Some("sitting"), at(9, 9, 0)));
+
+        let stats = |now| history.stats(&settings(), at(2, 0, 0).date_naive(), at(9, 0, 0).date_naive(), now);
+        // Today hasn't met the goal yet, but isn't over either.
+        let today = stats(at(9, 9, 30));
+        assert_eq!((today.current_streak, today.longest_streak), (2, 3));
+        assert_eq!((today.days.len(), today.weeks.len()), (8, 2));
+        assert_eq!(today.weeks[0].standing_minutes, 15 + 20 + 15 + 10 + 15 + 30);
+        assert_eq!((today.prompts, today.complied), (6, 0));
+
+        // Once the day is over without standing, the streak is broken.
+        history.record(event(StandEventKind::Stopped, None, at(9, 10, 0)));
+        let tomorrow = stats(at(10, 9, 0));
+        assert_eq!((tomorrow.current_streak, tomorrow.longest_streak), (0, 3));
+
+        // The history is saved as it is recorded.
+        let reloaded = StandHistory::load(dir.join(STAND_HISTORY_FILE));
+        assert_eq!(reloaded.events(at(2, 0, 0).date_naive(), at(9, 0, 0).date_naive()).len(), 20);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
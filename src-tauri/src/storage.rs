@@ -0,0 +1,190 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::models::AppData;
+
+pub const APP_IDENTIFIER: &str = "com.christoflurp.growing";
+pub const DATA_FILE: &str = "data.json";
+
+const LOCK_FILE: &str = "data.json.lock";
+const STALE_LOCK_AGE: Duration = Duration::from_secs(60);
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Debug builds keep their data in `dev-data` under the working directory so
+/// development never touches the real data.
+pub fn dev_data_dir() -> PathBuf {
+    std::env::current_dir().unwrap_or_default().join("dev-data")
+}
+
+/// Data directory the app uses: `dev-data` for debug builds, otherwise
+/// resolved the same way Tauri resolves `app_local_data_dir`, so tools running
+/// outside the app find the same files.
+pub fn default_data_dir() -> Option<PathBuf> {
+    if cfg!(debug_assertions) {
+        return Some(dev_data_dir());
+    }
+    dirs::data_local_dir().map(|dir| dir.join(APP_IDENTIFIER))
+}
+
+pub fn read_app_data(path: &Path) -> Result<AppData, String> {
+    if !path.exists() {
+        return Ok(AppData::default());
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read data: {}", e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse data: {}", e))
+}
+
+/// Writes through a temporary file and renames it over the target so a crash
+/// mid-write never leaves a truncated `data.json` behind.
+pub fn write_app_data(path: &Path, data: &AppData) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(data)
+        .map_err(|e| format!("Failed to serialize data: {}", e))?;
+    write_atomic(path, content.as_bytes())
+}
+
+/// Reads a backend-owned JSON store (timers, logs, caches), falling back to an
+/// empty store if the file is missing or unreadable.
+pub fn read_json<T: DeserializeOwned + Default>(path: &Path) -> T {
+    let Ok(content) = fs::read_to_string(path) else {
+        return T::default();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        eprintln!("Failed to parse {}: {}. Starting empty.", path.display(), e);
+        T::default()
+    })
+}
+
+/// Timestamps in backend stores are RFC 3339 in UTC with milliseconds, the
+/// same shape the frontend writes (`Date.toISOString()`).
+pub fn format_time(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Reads a timestamp in any offset, as written by `format_time` or by hand.
+pub fn parse_time(time: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(time).ok().map(|t| t.with_timezone(&Utc))
+}
+
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+    write_atomic(path, content.as_bytes())
+}
+
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        if !dir.exists() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create data dir: {}", e))?;
+        }
+    }
+
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+    fs::write(&tmp_path, bytes)
+        .map_err(|e| format!("Failed to write data: {}", e))?;
+    fs::rename(&tmp_path, path)
+        .map_err(|e| format!("Failed to write data: {}", e))
+}
+
+/// Advisory lock held while writing `data.json`, by the app and by tools
+/// editing it while the app isn't running. The lock file is removed when the
+/// guard is dropped; locks older than a minute are treated as left over from
+/// a crashed process.
+pub struct DataFileLock {
+    path: PathBuf,
+}
+
+impl DataFileLock {
+    pub fn acquire(data_dir: &Path) -> Result<Self, String> {
+        fs::create_dir_all(data_dir)
+            .map_err(|e| format!("Failed to create data dir: {}", e))?;
+        let path = data_dir.join(LOCK_FILE);
+
+        for _ in 0..2 {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    let _ = writeln!(file, "{}", std::process::id());
+                    return Ok(Self { path });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    if !is_stale(&path) {
+                        return Err(format!(
+                            "Data file is locked by another process ({})",
+                            path.display()
+                        ));
+                    }
+                    let _ = fs::remove_file(&path);
+                }
+                Err(e) => return Err(format!("Failed to lock data file: {}", e)),
+            }
+        }
+
+        Err(format!("Failed to lock data file ({})", path.display()))
+    }
+
+    /// Like `acquire`, but waits up to `timeout` for another holder to let go.
+    pub fn acquire_within(data_dir: &Path, timeout: Duration) -> Result<Self, String> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            match Self::acquire(data_dir) {
+                Err(_) if Instant::now() < deadline => thread::sleep(LOCK_RETRY_INTERVAL),
+                result => return result,
+            }
+        }
+    }
+}
+
+impl Drop for DataFileLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn is_stale(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .map(|age| age > STALE_LOCK_AGE)
+        .unwrap_or(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn debug_builds_use_the_app_dev_data_dir() {
+        assert_eq!(default_data_dir(), Some(dev_data_dir()));
+        assert!(dev_data_dir().ends_with("dev-data"));
+    }
+
+    #[test]
+    fn lock_is_exclusive_until_dropped() {
+        let dir = std::env::temp_dir().join(format!("growing-lock-{}", uuid::Uuid::new_v4()));
+        let lock = DataFileLock::acquire(&dir).unwrap();
+        assert!(DataFileLock::acquire(&dir).is_err());
+        assert!(DataFileLock::acquire_within(&dir, Duration::from_millis(120)).is_err());
+
+        let waiter = {
+            let dir = dir.clone();
+            thread::spawn(move || DataFileLock::acquire_within(&dir, Duration::from_secs(5)).map(|_| ()))
+        };
+        thread::sleep(Duration::from_millis(100));
+        drop(lock);
+        assert!(waiter.join().unwrap().is_ok());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
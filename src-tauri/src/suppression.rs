@@ -0,0 +1,239 @@
+use std::sync::Mutex;
+
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Weekday};
+use chrono_tz::Tz;
+
+use crate::models::{ActiveTimer, AppData, QuietHours, ReminderRule};
+
+/// Alerts that quiet hours and timeboxes can hold back. Timer expiries are
+/// never suppressed.
+#[derive(Debug, Clone, Copy)]
+pub enum Alert<'a> {
+    Reminder { rule_id: &'a str },
+    Stand,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Allow,
+    /// Drop the alert; the reason is recorded in the notification log.
+    Suppress(&'static str),
+    /// Hold the alert until the running timebox ends.
+    Defer,
+}
+
+/// Decides whether an alert may go out now, given quiet hours, running task
+/// timeboxes and the on-call (ATC) profile for today. Quiet hours and "today"
+/// are read in `now`'s zone.
+pub fn decide(data: &AppData, timers: &[ActiveTimer], alert: Alert, now: DateTime<Tz>) -> Decision {
+    let settings = &data.notifications;
+    let today = now.format("%Y-%m-%d").to_string();
+    let profile = settings
+        .atc_profile
+        .as_ref()
+        .filter(|_| data.atc_days.contains(&today));
+
+    match (alert, profile) {
+        (Alert::Stand, Some(profile)) if !profile.stand_reminders => return Decision::Suppress("atc profile"),
+        (Alert::Reminder { rule_id }, Some(profile)) if profile.muted_reminders.iter().any(|id| id == rule_id) => {
+            return Decision::Suppress("atc profile")
+        }
+        _ => {}
+    }
+
+    let quiet_hours = profile.map_or(&settings.quiet_hours, |p| &p.quiet_hours);
+    if in_quiet_hours(quiet_hours, now) {
+        return Decision::Suppress("quiet hours");
+    }
+
+    if settings.suppress_during_timebox && timebox_running(timers) {
+        return if settings.defer_during_timebox {
+            Decision::Defer
+        } else {
+            Decision::Suppress("timebox")
+        };
+    }
+
+    Decision::Allow
+}
+
+/// A task timebox that is counting down (not paused or already over).
+pub fn timebox_running(timers: &[ActiveTimer]) -> bool {
+    timers
+        .iter()
+        .any(|t| t.timer_type == "task" && t.paused_at.is_none() && t.expired_at.is_none())
+}
+
+/// Windows ending before they start run past midnight and belong to the day
+/// they start on, so `22:00-07:00` on Friday covers early Saturday morning.
+pub fn in_quiet_hours(windows: &[QuietHours], now: DateTime<Tz>) -> bool {
+    let time = now.time();
+    let today = now.weekday();
+    let yesterday = (now - Duration::days(1)).weekday();
+
+    windows.iter().any(|window| {
+        let (Some(start), Some(end)) = (parse_time(&window.start), parse_time(&window.end)) else {
+            return false;
+        };
+        if start <= end {
+            applies_on(window, today) && time >= start && time < end
+        } else {
+            (applies_on(window, today) && time >= start) || (applies_on(window, yesterday) && time < end)
+        }
+    })
+}
+
+/// Windows without days apply every day.
+fn applies_on(window: &QuietHours, weekday: Weekday) -> bool {
+    window.days.is_empty()
+        || window
+            .days
+            .iter()
+            .any(|day| day.parse::<Weekday>().is_ok_and(|d| d == weekday))
+}
+
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time.trim(), "%H:%M").ok()
+}
+
+#[derive(Debug, Clone)]
+pub struct DeferredReminder {
+    pub rule: ReminderRule,
+    pub due: DateTime<Tz>,
+    pub body: String,
+}
+
+/// Reminders held back by a running timebox, delivered when it ends. Kept in
+/// memory only: anything still waiting when the app quits is dropped.
+#[derive(Default)]
+pub struct DeferredReminders {
+    queue: Mutex<Vec<DeferredReminder>>,
+}
+
+impl DeferredReminders {
+    pub fn push(&self, reminder: DeferredReminder) {
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        // A rule that fires repeatedly during one timebox is delivered once.
+        queue.retain(|r| r.rule.id != reminder.rule.id);
+        queue.push(reminder);
+    }
+
+    pub fn take(&self) -> Vec<DeferredReminder> {
+        std::mem::take(&mut *self.queue.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{NotificationSettings, ReminderProfile};
+    use chrono::TimeZone;
+
+    /// 2026-03-02 is a Monday.
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Tz> {
+        chrono_tz::Europe::Berlin.with_ymd_and_hms(2026, 3, day, hour, minute, 0).unwrap()
+    }
+
+    fn window(days: &[&str], start: &str, end: &str) -> QuietHours {
+        QuietHours { days: days.iter().map(|d| d.to_string()).collect(), start: start.to_string(), end: end.to_string() }
+    }
+
+    fn timebox(paused: bool) -> ActiveTimer {
+        ActiveTimer {
+            id: "t".to_string(),
+            timer_type: "task".to_string(),
+            paused_at: paused.then(|| "2026-03-02T09:00:00Z".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn windows_within_a_day() {
+        let weekdays = [window(&["Mon", "Tue", "Wed", "Thu", "Fri"], "12:00", "13:00")];
+        assert!(in_quiet_hours(&weekdays, at(2, 12, 0)));
+        assert!(in_quiet_hours(&weekdays, at(6, 12, 59)));
+        // The end is exclusive, and weekends aren't covered.
+        assert!(!in_quiet_hours(&weekdays, at(2, 13, 0)));
+        assert!(!in_quiet_hours(&weekdays, at(7, 12, 30)));
+        assert!(in_quiet_hours(&[window(&[], "12:00", "13:00")], at(7, 12, 30)));
+        // Unparseable windows are ignored.
+        assert!(!in_quiet_hours(&[window(&[], "noon", "13:00")], at(2, 12, 30)));
+    }
+
+    #[test]
+    fn windows_past_midnight_belong_to_the_day_they_start() {
+        let friday_night = [window(&["Friday"], "22:00", "07:00")];
+        assert!(!in_quiet_hours(&friday_night, at(6, 21, 59)));
+        assert!(in_quiet_hours(&friday_night, at(6, 23, 30)));
+        // Early Saturday is still Friday's window...
+        assert!(in_quiet_hours(&friday_night, at(7, 6, 59)));
+        assert!(!in_quiet_hours(&friday_night, at(7, 7, 0)));
+        // ...while Saturday night and early Friday morning aren't covered.
+        assert!(!in_quiet_hours(&friday_night, at(7, 23, 0)));
+        assert!(!in_quiet_hours(&friday_night, at(6, 3, 0)));
+
+        let every_night = [window(&[], "22:00", "07:00")];
+        assert!(in_quiet_hours(&every_night, at(2, 0, 30)));
+        assert!(!in_quiet_hours(&every_night, at(2, 12, 0)));
+    }
+
+    #[test]
+    fn timeboxes_suppress_or_defer_reminders() {
+        let mut data = AppData::default();
+        data.notifications.suppress_during_timebox = true;
+        let reminder = Alert::Reminder { rule_id: "r" };
+        assert_eq!(decide(&data, &[], reminder, at(2, 10, 0)), Decision::Allow);
+        assert_eq!(decide(&data, &[timebox(false)], reminder, at(2, 10, 0)), Decision::Suppress("timebox"));
+        assert_eq!(decide(&data, &[timebox(true)], reminder, at(2, 10, 0)), Decision::Allow);
+        let expired = ActiveTimer { expired_at: Some("2026-03-02T09:00:00Z".to_string()), ..timebox(false) };
+        assert_eq!(decide(&data, &[expired], reminder, at(2, 10, 0)), Decision::Allow);
+        let focus = ActiveTimer { timer_type: "focus".to_string(), ..timebox(false) };
+        assert_eq!(decide(&data, &[focus], reminder, at(2, 10, 0)), Decision::Allow);
+
+        data.notifications.defer_during_timebox = true;
+        assert_eq!(decide(&data, &[timebox(false)], reminder, at(2, 10, 0)), Decision::Defer);
+        // Quiet hours win over deferring.
+        data.notifications.quiet_hours = vec![window(&[], "09:00", "11:00")];
+        assert_eq!(decide(&data, &[timebox(false)], reminder, at(2, 10, 0)), Decision::Suppress("quiet hours"));
+
+        data.notifications.suppress_during_timebox = false;
+        assert_eq!(decide(&data, &[timebox(false)], Alert::Stand, at(2, 12, 0)), Decision::Allow);
+    }
+
+    #[test]
+    fn atc_days_switch_to_the_atc_profile() {
+        let data = AppData {
+            atc_days: vec!["2026-03-03".to_string()],
+            notifications: NotificationSettings {
+                quiet_hours: vec![window(&[], "12:00", "13:00")],
+                atc_profile: Some(ReminderProfile {
+                    quiet_hours: vec![window(&[], "02:00", "04:00")],
+                    stand_reminders: false,
+                    muted_reminders: vec!["standup".to_string()],
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let standup = Alert::Reminder { rule_id: "standup" };
+        let other = Alert::Reminder { rule_id: "other" };
+
+        // An ordinary day uses the normal quiet hours.
+        assert_eq!(decide(&data, &[], other, at(2, 12, 30)), Decision::Suppress("quiet hours"));
+        assert_eq!(decide(&data, &[], other, at(2, 3, 0)), Decision::Allow);
+        assert_eq!(decide(&data, &[], standup, at(2, 10, 0)), Decision::Allow);
+        assert_eq!(decide(&data, &[], Alert::Stand, at(2, 10, 0)), Decision::Allow);
+
+        // On the ATC day the profile's quiet hours replace them.
+        assert_eq!(decide(&data, &[], other, at(3, 12, 30)), Decision::Allow);
+        assert_eq!(decide(&data, &[], other, at(3, 3, 0)), Decision::Suppress("quiet hours"));
+        assert_eq!(decide(&data, &[], standup, at(3, 10, 0)), Decision::Suppress("atc profile"));
+        assert_eq!(decide(&data, &[], Alert::Stand, at(3, 10, 0)), Decision::Suppress("atc profile"));
+
+        // "Today" is the date in the zone of `now`: 23:30 UTC on the 2nd is
+        // already the 3rd in Berlin.
+        let late = chrono_tz::UTC.with_ymd_and_hms(2026, 3, 2, 23, 30, 0).unwrap();
+        assert_eq!(decide(&data, &[], standup, late), Decision::Allow);
+        assert_eq!(decide(&data, &[], standup, late.with_timezone(&chrono_tz::Europe::Berlin)), Decision::Suppress("atc profile"));
+    }
+}
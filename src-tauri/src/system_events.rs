@@ -0,0 +1,379 @@
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SystemEvent {
+    /// The machine is about to sleep. Not every source can tell beforehand.
+    Sleep,
+    Wake,
+    Lock,
+    Unlock,
+}
+
+pub type Sink = Arc<dyn Fn(SystemEvent) + Send + Sync>;
+
+/// Tells the app when the machine sleeps and wakes and when the screen is
+/// locked and unlocked.
+pub trait SystemEventSource: Send {
+    /// Identifies the source in logs.
+    fn name(&self) -> &'static str;
+    /// Starts delivering events to `sink`, from whatever thread the platform
+    /// reports them on.
+    fn start(self: Box<Self>, sink: Sink);
+}
+
+/// Picks the source for this platform: workspace notifications on macOS,
+/// logind on Linux, and elsewhere a watch on the wall clock, which only
+/// notices waking up. `GROWING_SYSTEM_EVENTS=clock` forces the clock watch.
+pub fn source() -> Box<dyn SystemEventSource> {
+    match std::env::var("GROWING_SYSTEM_EVENTS").as_deref() {
+        Ok("clock") => return Box::new(ClockJumps),
+        Ok(other) => eprintln!("System event source {} is not available here, using the default", other),
+        Err(_) => {}
+    }
+    platform_source()
+}
+
+#[cfg(target_os = "macos")]
+fn platform_source() -> Box<dyn SystemEventSource> {
+    Box::new(Workspace)
+}
+
+#[cfg(target_os = "linux")]
+fn platform_source() -> Box<dyn SystemEventSource> {
+    Box::new(Logind)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn platform_source() -> Box<dyn SystemEventSource> {
+    Box::new(ClockJumps)
+}
+
+/// How often the clock watch looks at the wall clock.
+const CLOCK_CHECK: Duration = Duration::from_secs(5);
+
+/// A check arriving this much later than expected means the machine slept.
+const CLOCK_JUMP: Duration = Duration::from_secs(30);
+
+/// Reports a wake-up whenever the wall clock moves on much further than the
+/// watching thread slept: the thread doesn't run while the machine sleeps.
+/// Setting the clock forward looks the same, which does no harm, as waking up
+/// only makes the schedulers look at the time again.
+pub struct ClockJumps;
+
+impl SystemEventSource for ClockJumps {
+    fn name(&self) -> &'static str {
+        "clock"
+    }
+
+    fn start(self: Box<Self>, sink: Sink) {
+        thread::spawn(move || watch_clock(&sink));
+    }
+}
+
+fn watch_clock(sink: &Sink) {
+    let mut last = SystemTime::now();
+    loop {
+        thread::sleep(CLOCK_CHECK);
+        let now = SystemTime::now();
+        if now.duration_since(last).is_ok_and(|elapsed| elapsed > CLOCK_CHECK + CLOCK_JUMP) {
+            sink(SystemEvent::Wake);
+        }
+        last = now;
+    }
+}
+
+/// `NSWorkspace` sleep and wake notifications, and the screen lock
+/// notifications distributed by the login window.
+#[cfg(target_os = "macos")]
+struct Workspace;
+
+#[cfg(target_os = "macos")]
+impl SystemEventSource for Workspace {
+    fn name(&self) -> &'static str {
+        "workspace"
+    }
+
+    fn start(self: Box<Self>, sink: Sink) {
+        use objc::runtime::{Class, Object, Sel};
+        use objc::{msg_send, sel, sel_impl};
+        use std::sync::{Once, OnceLock};
+
+        type Id = *mut Object;
+
+        static REGISTER_OBSERVER_CLASS: Once = Once::new();
+        static SINK: OnceLock<Sink> = OnceLock::new();
+
+        let _ = SINK.set(sink);
+
+        fn deliver(event: SystemEvent) {
+            if let Some(sink) = SINK.get() {
+                sink(event);
+            }
+        }
+
+        extern "C" fn will_sleep(_this: &Object, _sel: Sel, _notification: Id) {
+            deliver(SystemEvent::Sleep);
+        }
+
+        extern "C" fn did_wake(_this: &Object, _sel: Sel, _notification: Id) {
+            deliver(SystemEvent::Wake);
+        }
+
+        extern "C" fn screen_locked(_this: &Object, _sel: Sel, _notification: Id) {
+            deliver(SystemEvent::Lock);
+        }
+
+        extern "C" fn screen_unlocked(_this: &Object, _sel: Sel, _notification: Id) {
+            deliver(SystemEvent::Unlock);
+        }
+
+        REGISTER_OBSERVER_CLASS.call_once(|| {
+            let superclass = Class::get("NSObject").unwrap();
+            let mut decl = objc::declare::ClassDecl::new("GrowingSystemEventObserver", superclass).unwrap();
+
+            unsafe {
+                decl.add_method(sel!(willSleep:), will_sleep as extern "C" fn(&Object, Sel, Id));
+                decl.add_method(sel!(didWake:), did_wake as extern "C" fn(&Object, Sel, Id));
+                decl.add_method(sel!(screenLocked:), screen_locked as extern "C" fn(&Object, Sel, Id));
+                decl.add_method(sel!(screenUnlocked:), screen_unlocked as extern "C" fn(&Object, Sel, Id));
+            }
+
+            decl.register();
+        });
+
+        unsafe {
+            let observer_class = Class::get("GrowingSystemEventObserver").unwrap();
+            // Intentionally leaked so it observes for the lifetime of the app.
+            let observer: Id = msg_send![observer_class, new];
+
+            let workspace_class = Class::get("NSWorkspace").unwrap();
+            let workspace: Id = msg_send![workspace_class, sharedWorkspace];
+            let workspace_center: Id = msg_send![workspace, notificationCenter];
+
+            let distributed_class = Class::get("NSDistributedNotificationCenter").unwrap();
+            let distributed_center: Id = msg_send![distributed_class, defaultCenter];
+
+            let nsstring_class = Class::get("NSString").unwrap();
+            let observe = |center: Id, selector: Sel, name: &[u8]| {
+                let name: Id = msg_send![nsstring_class, stringWithUTF8String: name.as_ptr()];
+                let _: () = msg_send![
+                    center,
+                    addObserver: observer
+                    selector: selector
+                    name: name
+                    object: std::ptr::null::<Object>()
+                ];
+            };
+
+            observe(workspace_center, sel!(willSleep:), b"NSWorkspaceWillSleepNotification\0");
+            observe(workspace_center, sel!(didWake:), b"NSWorkspaceDidWakeNotification\0");
+            observe(distributed_center, sel!(screenLocked:), b"com.apple.screenIsLocked\0");
+            observe(distributed_center, sel!(screenUnlocked:), b"com.apple.screenIsUnlocked\0");
+        }
+    }
+}
+
+/// logind's `PrepareForSleep` signal and the lock state of this session,
+/// followed with `gdbus monitor` on the system bus. Falls back to the clock
+/// watch if logind can't be reached.
+#[cfg(target_os = "linux")]
+struct Logind;
+
+#[cfg(target_os = "linux")]
+impl SystemEventSource for Logind {
+    fn name(&self) -> &'static str {
+        "logind"
+    }
+
+    fn start(self: Box<Self>, sink: Sink) {
+        thread::spawn(move || {
+            if let Err(e) = monitor_logind(&sink) {
+                eprintln!("{}, watching the clock for wake-ups instead", e);
+            }
+            watch_clock(&sink);
+        });
+    }
+}
+
+#[cfg(any(target_os = "linux", test))]
+const LOGIND: &str = "org.freedesktop.login1";
+
+#[cfg(target_os = "linux")]
+fn monitor_logind(sink: &Sink) -> Result<(), String> {
+    use std::io::BufReader;
+    use std::process::{Command, Stdio};
+
+    let session = logind_session();
+    let mut child = Command::new("gdbus")
+        .args(["monitor", "--system", "--dest", LOGIND])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Can't run gdbus to follow logind: {}", e))?;
+    let stdout = child.stdout.take().ok_or("gdbus has no output")?;
+    forward_logind_signals(BufReader::new(stdout), session.as_deref(), sink);
+    let _ = child.kill();
+    let _ = child.wait();
+    Err("Lost the connection to logind".to_string())
+}
+
+/// Passes the events in `gdbus monitor` output on to `sink` until the output
+/// ends. Desktops report a lock both ways, or repeat it; only changes of the
+/// lock state are passed on.
+#[cfg(any(target_os = "linux", test))]
+fn forward_logind_signals(output: impl std::io::BufRead, session: Option<&str>, sink: &Sink) {
+    let mut locked = false;
+    for line in output.lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        let Some(event) = parse_logind_signal(&line, session) else {
+            continue;
+        };
+        match event {
+            SystemEvent::Lock | SystemEvent::Unlock if locked == (event == SystemEvent::Lock) => continue,
+            SystemEvent::Lock | SystemEvent::Unlock => locked = event == SystemEvent::Lock,
+            SystemEvent::Sleep | SystemEvent::Wake => {}
+        }
+        sink(event);
+    }
+}
+
+/// Object path of the session the app runs in, so locking another user's
+/// session is ignored.
+#[cfg(target_os = "linux")]
+fn logind_session() -> Option<String> {
+    let output = std::process::Command::new("busctl")
+        .args(["--system", "--json=short", "call", LOGIND, "/org/freedesktop/login1"])
+        .args(["org.freedesktop.login1.Manager", "GetSession", "s", "auto"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    let reply: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    reply["data"][0].as_str().map(str::to_string)
+}
+
+/// Reads one line of `gdbus monitor` output, such as
+/// `/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (true,)`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_logind_signal(line: &str, session: Option<&str>) -> Option<SystemEvent> {
+    let (path, signal) = line.split_once(": ")?;
+    let (member, args) = signal.split_once(' ').unwrap_or((signal, ""));
+    if member == "org.freedesktop.login1.Manager.PrepareForSleep" {
+        return Some(if args.contains("true") { SystemEvent::Sleep } else { SystemEvent::Wake });
+    }
+
+    let ours = match session {
+        Some(session) => path == session,
+        None => path.starts_with("/org/freedesktop/login1/session/"),
+    };
+    if !ours {
+        return None;
+    }
+    match member {
+        "org.freedesktop.login1.Session.Lock" => Some(SystemEvent::Lock),
+        "org.freedesktop.login1.Session.Unlock" => Some(SystemEvent::Unlock),
+        "org.freedesktop.DBus.Properties.PropertiesChanged" if args.contains("'LockedHint': <true>") => {
+            Some(SystemEvent::Lock)
+        }
+        "org.freedesktop.DBus.Properties.PropertiesChanged" if args.contains("'LockedHint': <false>") => {
+            Some(SystemEvent::Unlock)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    const SESSION: &str = "/org/freedesktop/login1/session/_32";
+
+    #[test]
+    fn reads_logind_signals_from_gdbus_monitor() {
+        let cases = [
+            ("/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (true,)", Some(SystemEvent::Sleep)),
+            ("/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (false,)", Some(SystemEvent::Wake)),
+            ("/org/freedesktop/login1/session/_32: org.freedesktop.login1.Session.Lock ()", Some(SystemEvent::Lock)),
+            ("/org/freedesktop/login1/session/_32: org.freedesktop.login1.Session.Unlock ()", Some(SystemEvent::Unlock)),
+            // Another user's session.
+            ("/org/freedesktop/login1/session/c2: org.freedesktop.login1.Session.Lock ()", None),
+            (
+                "/org/freedesktop/login1/session/_32: org.freedesktop.DBus.Properties.PropertiesChanged \
+                 ('org.freedesktop.login1.Session', {'LockedHint': <true>}, @as [])",
+                Some(SystemEvent::Lock),
+            ),
+            (
+                "/org/freedesktop/login1/session/_32: org.freedesktop.DBus.Properties.PropertiesChanged \
+                 ('org.freedesktop.login1.Session', {'LockedHint': <false>}, @as [])",
+                Some(SystemEvent::Unlock),
+            ),
+            (
+                "/org/freedesktop/login1/session/_32: org.freedesktop.DBus.Properties.PropertiesChanged \
+                 ('org.freedesktop.login1.Session', {'IdleHint': <true>, 'IdleSinceHint': <uint64 1772445600000000>}, @as [])",
+                None,
+            ),
+            (
+                "/org/freedesktop/login1: org.freedesktop.login1.Manager.SessionNew ('33', objectpath '/org/freedesktop/login1/session/_33')",
+                None,
+            ),
+            ("Monitoring signals from all objects owned by org.freedesktop.login1", None),
+            ("The name org.freedesktop.login1 is owned by :1.5", None),
+            ("", None),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(parse_logind_signal(line, Some(SESSION)), expected, "{}", line);
+        }
+    }
+
+    #[test]
+    fn without_a_known_session_any_session_counts() {
+        let other = "/org/freedesktop/login1/session/c2: org.freedesktop.login1.Session.Lock ()";
+        assert_eq!(parse_logind_signal(other, None), Some(SystemEvent::Lock));
+        let manager = "/org/freedesktop/login1: org.freedesktop.login1.Session.Lock ()";
+        assert_eq!(parse_logind_signal(manager, None), None);
+    }
+
+    #[test]
+    fn repeated_lock_signals_are_passed_on_once() {
+        let lock = format!("{}: org.freedesktop.login1.Session.Lock ()", SESSION);
+        let unlock = format!("{}: org.freedesktop.login1.Session.Unlock ()", SESSION);
+        let hint = |locked: bool| {
+            format!(
+                "{}: org.freedesktop.DBus.Properties.PropertiesChanged ('org.freedesktop.login1.Session', {{'LockedHint': <{}>}}, @as [])",
+                SESSION, locked
+            )
+        };
+        let sleep = "/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (true,)".to_string();
+        let wake = "/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (false,)".to_string();
+        let output = [
+            unlock.clone(),
+            hint(false),
+            lock.clone(),
+            hint(true),
+            lock,
+            sleep.clone(),
+            sleep,
+            wake,
+            hint(false),
+            unlock,
+        ]
+        .join("\n");
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let seen = events.clone();
+        let sink: Sink = Arc::new(move |event| seen.lock().unwrap().push(event));
+        forward_logind_signals(output.as_bytes(), Some(SESSION), &sink);
+        // Sleep and wake aren't de-duplicated; logind sends each once.
+        assert_eq!(
+            *events.lock().unwrap(),
+            [SystemEvent::Lock, SystemEvent::Sleep, SystemEvent::Sleep, SystemEvent::Wake, SystemEvent::Unlock]
+        );
+    }
+}
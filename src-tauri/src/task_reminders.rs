@@ -0,0 +1,463 @@
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::actions;
+use crate::clock::Clock;
+use crate::models::{AppData, DailyTask};
+use crate::schedule;
+use crate::storage::{format_time, parse_time};
+
+/// Longest the worker sleeps without looking at the clock.
+const MAX_WAIT: StdDuration = StdDuration::from_secs(10 * 60);
+
+/// How often reminders held back by a timebox are offered again.
+const HOLD_RETRY: Duration = Duration::seconds(30);
+
+/// Reminders noticed later than this are marked late.
+const GRACE: Duration = Duration::minutes(2);
+
+/// On startup, reminders that came due this recently while the app was
+/// closed are still sent.
+const LOOKBACK: Duration = Duration::hours(12);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ItemKind {
+    Task,
+    Todo,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskReminder {
+    pub kind: ItemKind,
+    pub id: String,
+    pub text: String,
+    #[serde(serialize_with = "serialize_time")]
+    pub due: DateTime<Tz>,
+}
+
+fn serialize_time<S: serde::Serializer>(time: &DateTime<Tz>, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&time.to_rfc3339())
+}
+
+impl TaskReminder {
+    /// Identifies the reminder in the notification log.
+    pub fn log_id(&self) -> String {
+        log_id(self.kind, &self.id)
+    }
+}
+
+pub fn log_id(kind: ItemKind, id: &str) -> String {
+    match kind {
+        ItemKind::Task => format!("task:{}", id),
+        ItemKind::Todo => format!("todo:{}", id),
+    }
+}
+
+/// Accepts RFC 3339 as written by `Date.toISOString()`, a fixed instant, or
+/// a wall-clock `YYYY-MM-DDTHH:MM` as produced by a datetime input, which is
+/// read in `zone` and so stays at the same local time when the zone changes.
+pub fn parse_remind_at(value: &str, zone: &Tz) -> Option<DateTime<Tz>> {
+    let value = value.trim();
+    if let Some(time) = parse_time(value) {
+        return Some(time.with_timezone(zone));
+    }
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M")
+        .ok()
+        .and_then(|t| schedule::resolve(zone, t))
+}
+
+/// When an open task's reminder fires. A reminder dated before the task's
+/// day (the task was carried forward or rescheduled) moves to the task's day
+/// at the same time.
+pub fn task_due(task: &DailyTask, zone: &Tz) -> Option<DateTime<Tz>> {
+    if task.completed || task.moved_to_date.is_some() {
+        return None;
+    }
+    let remind_at = parse_remind_at(task.remind_at.as_deref()?, zone)?;
+    let Ok(date) = NaiveDate::parse_from_str(&task.date, "%Y-%m-%d") else {
+        return Some(remind_at);
+    };
+    if remind_at.date_naive() >= date {
+        return Some(remind_at);
+    }
+    schedule::resolve(zone, date.and_time(remind_at.time()))
+}
+
+/// Every pending task and todo reminder, soonest first.
+pub fn pending(data: &AppData, zone: &Tz) -> Vec<TaskReminder> {
+    let tasks = data.daily_tasks.iter().filter_map(|task| {
+        task_due(task, zone).map(|due| TaskReminder { kind: ItemKind::Task, id: task.id.clone(), text: task.text.clone(), due })
+    });
+    let todos = data.todos.iter().filter_map(|todo| {
+        let due = parse_remind_at(todo.remind_at.as_deref()?, zone)?;
+        Some(TaskReminder { kind: ItemKind::Todo, id: todo.id.clone(), text: todo.text.clone(), due })
+    });
+    let mut reminders: Vec<_> = tasks.chain(todos).collect();
+    reminders.sort_by_key(|r| r.due);
+    reminders
+}
+
+/// Marks a task done. A todo is done by moving it onto today's list as a
+/// completed task.
+pub fn complete(data: &mut AppData, kind: ItemKind, id: &str) -> Result<(), String> {
+    let now = actions::timestamp();
+    match kind {
+        ItemKind::Task => {
+            let index = actions::find_task(data, id)?;
+            let task = &mut data.daily_tasks[index];
+            if !task.completed {
+                task.completed = true;
+                task.completed_at = Some(now);
+            }
+        }
+        ItemKind::Todo => {
+            let index = find_todo(data, id)?;
+            let todo = data.todos.remove(index);
+            data.daily_tasks.insert(
+                0,
+                DailyTask {
+                    id: Uuid::new_v4().to_string(),
+                    text: todo.text,
+                    description: todo.description,
+                    goal_id: todo.goal_id,
+                    checklist: todo.checklist,
+                    completed: true,
+                    completed_at: Some(now),
+                    date: actions::today(data),
+                    category: "work".to_string(),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Pushes the reminder back by `minutes` from now. Returns the new time.
+pub fn postpone(data: &mut AppData, kind: ItemKind, id: &str, minutes: u32) -> Result<String, String> {
+    if minutes == 0 || minutes > 7 * 24 * 60 {
+        return Err("Postpone by between 1 minute and a week".to_string());
+    }
+    let remind_at = format_time(Utc::now() + Duration::minutes(minutes as i64));
+    match kind {
+        ItemKind::Task => {
+            let index = actions::find_task(data, id)?;
+            data.daily_tasks[index].remind_at = Some(remind_at.clone());
+        }
+        ItemKind::Todo => {
+            let index = find_todo(data, id)?;
+            data.todos[index].remind_at = Some(remind_at.clone());
+        }
+    }
+    Ok(remind_at)
+}
+
+fn find_todo(data: &AppData, id: &str) -> Result<usize, String> {
+    data.todos
+        .iter()
+        .position(|t| t.id == id.trim())
+        .ok_or_else(|| format!("No todo with id {}", id))
+}
+
+enum Update {
+    Data(Arc<AppData>),
+    Wake,
+}
+
+/// The pending reminders and how far the scheduler has looked, kept apart
+/// from the worker thread so it can be stepped with any clock.
+pub struct TaskReminderState {
+    data: Option<Arc<AppData>>,
+    reminders: Vec<TaskReminder>,
+    cursor: DateTime<Tz>,
+    /// Log ids of reminders held back last time, offered again.
+    held: Vec<String>,
+}
+
+impl TaskReminderState {
+    /// Reminders that came due recently, while the app was closed, are
+    /// still sent.
+    pub fn new(data: Option<&AppData>, now: DateTime<Tz>) -> Self {
+        let data = data.map(|data| Arc::new(data.clone()));
+        Self {
+            reminders: data.as_deref().map(|data| pending(data, &now.timezone())).unwrap_or_default(),
+            data,
+            cursor: now - LOOKBACK,
+            held: Vec::new(),
+        }
+    }
+
+    pub fn set_data(&mut self, data: Arc<AppData>) {
+        self.reminders = pending(&data, &self.cursor.timezone());
+        self.data = Some(data);
+    }
+
+    /// Re-reads wall-clock reminder times in `zone` and carries the cursor's
+    /// local time over, so a reminder already sent at 9:00 is not sent again
+    /// at 9:00 in a zone further west.
+    fn change_zone(&mut self, zone: Tz) {
+        let wall = self.cursor.naive_local();
+        self.cursor = schedule::resolve(&zone, wall).unwrap_or_else(|| self.cursor.with_timezone(&zone));
+        self.reminders = self.data.as_deref().map(|data| pending(data, &zone)).unwrap_or_default();
+    }
+
+    /// Offers everything that came due since the last poll to `fire`, which
+    /// returns `false` to hold a reminder until the next poll. Returns when
+    /// the next reminder is due.
+    pub fn poll<F>(&mut self, now: DateTime<Tz>, mut fire: F) -> Option<DateTime<Tz>>
+    where
+        F: FnMut(TaskReminder, bool) -> bool,
+    {
+        if self.cursor.timezone() != now.timezone() {
+            self.change_zone(now.timezone());
+        }
+        let mut next_wake = None;
+        let mut held = Vec::new();
+        for reminder in &self.reminders {
+            if reminder.due > now {
+                next_wake = Some(reminder.due);
+                break;
+            }
+            let id = reminder.log_id();
+            if reminder.due > self.cursor || self.held.contains(&id) {
+                let late = now - reminder.due > GRACE;
+                if !fire(reminder.clone(), late) {
+                    held.push(id);
+                }
+            }
+        }
+        self.held = held;
+        self.cursor = self.cursor.max(now);
+        // Check back on held reminders regularly.
+        if !self.held.is_empty() {
+            next_wake = Some(next_wake.map_or(now + HOLD_RETRY, |w: DateTime<Tz>| w.min(now + HOLD_RETRY)));
+        }
+        next_wake
+    }
+}
+
+/// Fires task and todo reminders at their `remindAt` time. Works like the
+/// reminder rule scheduler: the worker keeps the pending reminders in memory,
+/// updated through `update`, and sleeps until the next one is due. `fire` is
+/// told whether the reminder is late and returns `false` to have it offered
+/// again shortly, e.g. while a timebox holds it back.
+#[derive(Clone)]
+pub struct TaskReminderScheduler {
+    updates: Sender<Update>,
+}
+
+impl TaskReminderScheduler {
+    pub fn start<F>(clock: Arc<dyn Clock>, data: Option<&AppData>, fire: F) -> Self
+    where
+        F: Fn(TaskReminder, bool) -> bool + Send + 'static,
+    {
+        let (updates, receiver) = mpsc::channel();
+        let mut state = TaskReminderState::new(data, clock.now());
+        thread::spawn(move || loop {
+            let next = state.poll(clock.now(), &fire);
+
+            let wait = next.map_or(MAX_WAIT, |next| (next - clock.now()).to_std().unwrap_or_default().min(MAX_WAIT));
+            let first = match receiver.recv_timeout(wait) {
+                Ok(update) => Some(update),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => return,
+            };
+            for update in first.into_iter().chain(receiver.try_iter()) {
+                if let Update::Data(data) = update {
+                    state.set_data(data);
+                }
+            }
+        });
+        Self { updates }
+    }
+
+    pub fn update(&self, data: Arc<AppData>) {
+        let _ = self.updates.send(Update::Data(data));
+    }
+
+    pub fn wake(&self) {
+        let _ = self.updates.send(Update::Wake);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    use crate::clock::{Clock, ManualClock};
+    use crate::models::Todo;
+
+    /// 2026-03-02 at `h:m` UTC.
+    fn at(h: u32, m: u32) -> DateTime<Tz> {
+        chrono_tz::UTC.with_ymd_and_hms(2026, 3, 2, h, m, 0).unwrap()
+    }
+
+    fn data() -> AppData {
+        AppData {
+            daily_tasks: vec![DailyTask {
+                id: "task".to_string(),
+                text: "Send the report".to_string(),
+                date: "2026-03-02".to_string(),
+                category: "work".to_string(),
+                remind_at: Some("2026-03-02T09:00".to_string()),
+                ..Default::default()
+            }],
+            todos: vec![Todo {
+                id: "todo".to_string(),
+                text: "Book flights".to_string(),
+                remind_at: Some("2026-03-02T09:30:00.000Z".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    /// Polls, recording `(log id, late)` for each reminder offered and
+    /// holding the ones listed in `hold`.
+    fn poll(state: &mut TaskReminderState, now: DateTime<Tz>, hold: &[&str]) -> (Vec<(String, bool)>, Option<DateTime<Tz>>) {
+        let mut offered = Vec::new();
+        let next = state.poll(now, |reminder, late| {
+            let id = reminder.log_id();
+            let sent = !hold.contains(&id.as_str());
+            offered.push((id, late));
+            sent
+        });
+        (offered, next)
+    }
+
+    fn offered(ids: &[(&str, bool)]) -> Vec<(String, bool)> {
+        ids.iter().map(|(id, late)| (id.to_string(), *late)).collect()
+    }
+
+    #[test]
+    fn fires_each_reminder_once_when_due() {
+        let clock = ManualClock::new(at(8, 0));
+        let mut state = TaskReminderState::new(Some(&data()), clock.now());
+        assert_eq!(poll(&mut state, clock.now(), &[]), (Vec::new(), Some(at(9, 0))));
+
+        clock.set(at(9, 1));
+        assert_eq!(poll(&mut state, clock.now(), &[]), (offered(&[("task:task", false)]), Some(at(9, 30))));
+        clock.set(at(9, 30));
+        assert_eq!(poll(&mut state, clock.now(), &[]), (offered(&[("todo:todo", false)]), None));
+        clock.advance(Duration::minutes(1));
+        assert_eq!(poll(&mut state, clock.now(), &[]), (Vec::new(), None));
+    }
+
+    #[test]
+    fn reminders_noticed_after_the_grace_period_are_late() {
+        let clock = ManualClock::new(at(8, 0));
+        let mut state = TaskReminderState::new(Some(&data()), clock.now());
+        poll(&mut state, clock.now(), &[]);
+
+        clock.set(at(9, 31));
+        assert_eq!(
+            poll(&mut state, clock.now(), &[]).0,
+            offered(&[("task:task", true), ("todo:todo", false)])
+        );
+    }
+
+    #[test]
+    fn reminders_due_while_the_app_was_closed_are_sent_late() {
+        let clock = ManualClock::new(at(20, 0));
+        let mut state = TaskReminderState::new(Some(&data()), clock.now());
+        assert_eq!(
+            poll(&mut state, clock.now(), &[]).0,
+            offered(&[("task:task", true), ("todo:todo", true)])
+        );
+
+        // Past the lookback they are dropped.
+        clock.advance(LOOKBACK);
+        let mut state = TaskReminderState::new(Some(&data()), clock.now());
+        assert!(poll(&mut state, clock.now(), &[]).0.is_empty());
+    }
+
+    #[test]
+    fn held_reminders_are_retried_until_sent() {
+        let clock = ManualClock::new(at(8, 0));
+        let mut state = TaskReminderState::new(Some(&data()), clock.now());
+        poll(&mut state, clock.now(), &[]);
+
+        clock.set(at(9, 0));
+        let (sent, next) = poll(&mut state, clock.now(), &["task:task"]);
+        assert_eq!(sent, offered(&[("task:task", false)]));
+        assert_eq!(next, Some(at(9, 0) + HOLD_RETRY));
+
+        // Still held: offered again, now late, and the retry doesn't push
+        // back the next reminder.
+        clock.set(at(9, 29) + Duration::seconds(50));
+        let (sent, next) = poll(&mut state, clock.now(), &["task:task"]);
+        assert_eq!(sent, offered(&[("task:task", true)]));
+        assert_eq!(next, Some(at(9, 30)));
+
+        clock.set(at(9, 30));
+        let (sent, next) = poll(&mut state, clock.now(), &[]);
+        assert_eq!(sent, offered(&[("task:task", true), ("todo:todo", false)]));
+        assert_eq!(next, None);
+        clock.advance(HOLD_RETRY);
+        assert!(poll(&mut state, clock.now(), &[]).0.is_empty());
+    }
+
+    #[test]
+    fn done_tasks_and_removed_reminders_do_not_fire() {
+        let clock = ManualClock::new(at(8, 0));
+        let mut state = TaskReminderState::new(Some(&data()), clock.now());
+        poll(&mut state, clock.now(), &[]);
+
+        let mut changed = data();
+        changed.daily_tasks[0].completed = true;
+        changed.todos[0].remind_at = None;
+        state.set_data(Arc::new(changed));
+        clock.set(at(10, 0));
+        assert_eq!(poll(&mut state, clock.now(), &[]), (Vec::new(), None));
+    }
+
+    #[test]
+    fn a_task_carried_to_a_later_day_reminds_on_that_day() {
+        let mut data = data();
+        data.daily_tasks[0].date = "2026-03-03".to_string();
+        data.todos.clear();
+        let clock = ManualClock::new(at(8, 0));
+        let mut state = TaskReminderState::new(Some(&data), clock.now());
+        assert_eq!(poll(&mut state, clock.now(), &[]).1, Some(at(9, 0) + Duration::days(1)));
+    }
+
+    #[test]
+    fn travelling_west_does_not_repeat_a_task_reminder() {
+        let mut data = data();
+        data.todos.clear();
+        let clock = ManualClock::new(chrono_tz::Europe::Berlin.with_ymd_and_hms(2026, 3, 2, 9, 0, 30).unwrap());
+        let mut state = TaskReminderState::new(Some(&data), clock.now());
+        assert_eq!(poll(&mut state, clock.now(), &[]).0, offered(&[("task:task", false)]));
+
+        // The wall-clock reminder moves to 09:00 in New York, which counts
+        // as already sent.
+        let new_york = |m| chrono_tz::America::New_York.with_ymd_and_hms(2026, 3, 2, 9, m, 0).unwrap();
+        clock.set_zone(chrono_tz::America::New_York);
+        assert_eq!(poll(&mut state, clock.now(), &[]), (Vec::new(), Some(new_york(0))));
+        clock.set(new_york(1));
+        assert_eq!(poll(&mut state, clock.now(), &[]), (Vec::new(), None));
+    }
+
+    #[test]
+    fn travelling_east_sends_reminders_skipped_over() {
+        let clock = ManualClock::new(chrono_tz::Europe::Berlin.with_ymd_and_hms(2026, 3, 2, 8, 0, 0).unwrap());
+        let mut state = TaskReminderState::new(Some(&data()), clock.now());
+        assert_eq!(poll(&mut state, clock.now(), &[]).1.map(|t| t.naive_utc()), Some(at(8, 0).naive_utc()));
+
+        // 16:00 in Tokyo. The task's 09:00 is read in Tokyo and has passed;
+        // the todo is a fixed instant, 18:30 in Tokyo.
+        clock.set_zone(chrono_tz::Asia::Tokyo);
+        let (sent, next) = poll(&mut state, clock.now(), &[]);
+        assert_eq!(sent, offered(&[("task:task", true)]));
+        assert_eq!(next.map(|t| t.naive_utc()), Some(at(9, 30).naive_utc()));
+    }
+}
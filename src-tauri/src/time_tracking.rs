@@ -0,0 +1,655 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{ActiveTimer, AppData};
+use crate::timers::{self, TimerEvent};
+use crate::storage::{self, format_time, parse_time};
+use crate::{pomodoro, tz};
+
+pub const TIME_ENTRIES_FILE: &str = "time_entries.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum EntrySource {
+    /// A pomodoro work phase run against the task.
+    Timer,
+    /// The task's own timebox timer.
+    Timebox,
+    Manual,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TimeEntry {
+    pub id: String,
+    #[serde(rename = "taskId")]
+    pub task_id: String,
+    pub source: EntrySource,
+    pub start: String,
+    /// `None` while the timer that opened the entry is still running.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop: Option<String>,
+    #[serde(default, rename = "timerId", skip_serializing_if = "Option::is_none")]
+    pub timer_id: Option<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+    #[serde(default, rename = "editedAt", skip_serializing_if = "Option::is_none")]
+    pub edited_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct TimeStore {
+    #[serde(default)]
+    entries: Vec<TimeEntry>,
+}
+
+/// Fields of an entry to change; `None` leaves the field as it is.
+#[derive(Debug, Deserialize, Default)]
+pub struct EntryEdit {
+    #[serde(default, rename = "taskId")]
+    pub task_id: Option<String>,
+    #[serde(default)]
+    pub start: Option<String>,
+    #[serde(default)]
+    pub stop: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TaskTotal {
+    #[serde(rename = "taskId")]
+    pub task_id: String,
+    #[serde(rename = "actualMinutes")]
+    pub actual_minutes: i64,
+    #[serde(rename = "entryCount")]
+    pub entry_count: usize,
+    pub running: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ReportPeriod {
+    Day { date: String },
+    /// The Monday-to-Sunday week containing `date`.
+    Week { date: String },
+    Goal {
+        #[serde(rename = "goalId")]
+        goal_id: String,
+    },
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ReportRow {
+    #[serde(rename = "taskId")]
+    pub task_id: String,
+    pub text: String,
+    pub date: String,
+    #[serde(rename = "goalId", skip_serializing_if = "Option::is_none")]
+    pub goal_id: Option<String>,
+    pub completed: bool,
+    #[serde(rename = "estimateMinutes", skip_serializing_if = "Option::is_none")]
+    pub estimate_minutes: Option<i32>,
+    #[serde(rename = "actualMinutes")]
+    pub actual_minutes: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TimeReport {
+    pub rows: Vec<ReportRow>,
+    /// Sum of estimates, counting only tasks that have one.
+    #[serde(rename = "estimateMinutes")]
+    pub estimate_minutes: i64,
+    #[serde(rename = "actualMinutes")]
+    pub actual_minutes: i64,
+    /// Actual time spent on the tasks that have an estimate, for a fair
+    /// comparison against `estimate_minutes`.
+    #[serde(rename = "estimatedTasksActualMinutes")]
+    pub estimated_tasks_actual_minutes: i64,
+}
+
+/// Records how long was actually spent on each task. Entries are opened and
+/// closed from timer events (task timeboxes and pomodoro work phases) and can
+/// also be added or corrected by hand. Stored in `time_entries.json`.
+pub struct TimeTracker {
+    path: PathBuf,
+    store: Mutex<TimeStore>,
+}
+
+impl TimeTracker {
+    pub fn load(path: PathBuf) -> Self {
+        let store = storage::read_json(&path);
+        Self { path, store: Mutex::new(store) }
+    }
+
+    pub fn on_timer_event(&self, event: &TimerEvent) {
+        let timer = event.timer();
+        let Some(source) = tracked_source(timer) else {
+            return;
+        };
+        let now = Utc::now();
+
+        match event {
+            TimerEvent::Started { .. } => {
+                self.open(timer, source, timer.started_at.as_deref().and_then(parse_time).unwrap_or(now));
+            }
+            TimerEvent::Resumed { .. } => self.open(timer, source, now),
+            // Extending a timer that already ran out restarts it.
+            TimerEvent::Extended { .. } if timer.paused_at.is_none() && !self.is_open(&timer.id) => {
+                self.open(timer, source, now);
+            }
+            TimerEvent::Paused { .. } => {
+                self.close(&timer.id, timer.paused_at.as_deref().and_then(parse_time).unwrap_or(now));
+            }
+            // Late expiries (app closed, machine asleep) count up to the
+            // deadline, not to when the expiry was noticed.
+            TimerEvent::Expired { .. } => self.close(&timer.id, timers::end_time(timer).min(now)),
+            TimerEvent::Cancelled { .. } => self.close(&timer.id, now),
+            TimerEvent::Extended { .. } => {}
+        }
+    }
+
+    pub fn entries(&self, task_id: Option<&str>, from: Option<NaiveDate>, to: Option<NaiveDate>) -> Vec<TimeEntry> {
+        let zone = tz::zone();
+        self.lock()
+            .entries
+            .iter()
+            .filter(|e| task_id.is_none_or(|id| e.task_id == id))
+            .filter(|e| {
+                let Some(day) = parse_time(&e.start).map(|t| t.with_timezone(&zone).date_naive()) else {
+                    return false;
+                };
+                from.is_none_or(|from| day >= from) && to.is_none_or(|to| day <= to)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Actual time per task, including entries still running.
+    pub fn totals(&self, task_ids: Option<&[String]>) -> Vec<TaskTotal> {
+        let now = Utc::now();
+        let mut totals: HashMap<&str, TaskTotal> = HashMap::new();
+        let store = self.lock();
+        for entry in &store.entries {
+            if task_ids.is_some_and(|ids| !ids.contains(&entry.task_id)) {
+                continue;
+            }
+            let total = totals.entry(&entry.task_id).or_insert_with(|| TaskTotal {
+                task_id: entry.task_id.clone(),
+                actual_minutes: 0,
+                entry_count: 0,
+                running: false,
+            });
+            total.actual_minutes += entry_seconds(entry, now);
+            total.entry_count += 1;
+            total.running |= entry.stop.is_none();
+        }
+
+        let mut totals: Vec<TaskTotal> = totals
+            .into_values()
+            .map(|mut total| {
+                total.actual_minutes = to_minutes(total.actual_minutes);
+                total
+            })
+            .collect();
+        totals.sort_by(|a, b| a.task_id.cmp(&b.task_id));
+        totals
+    }
+
+    /// Estimated (`timeboxMinutes`) against actual time for the tasks in
+    /// `period`. Tasks moved to another day are left out of day and week
+    /// reports so they are counted where they ended up.
+    pub fn report(&self, data: &AppData, period: &ReportPeriod) -> Result<TimeReport, String> {
+        let range = match period {
+            ReportPeriod::Day { date } => {
+                let day = parse_date(date)?;
+                Some((day, day))
+            }
+            ReportPeriod::Week { date } => {
+                let day = parse_date(date)?;
+                let monday = day - Duration::days(day.weekday().num_days_from_monday() as i64);
+                Some((monday, monday + Duration::days(6)))
+            }
+            ReportPeriod::Goal { .. } => None,
+        };
+
+        let tasks: Vec<_> = data
+            .daily_tasks
+            .iter()
+            .filter(|task| match (period, range) {
+                (ReportPeriod::Goal { goal_id }, _) => task.goal_id.as_deref() == Some(goal_id.as_str()),
+                (_, Some((from, to))) => {
+                    task.moved_to_date.is_none()
+                        && NaiveDate::parse_from_str(&task.date, "%Y-%m-%d").is_ok_and(|d| d >= from && d <= to)
+                }
+                _ => false,
+            })
+            .collect();
+
+        let now = Utc::now();
+        let mut seconds: HashMap<&str, i64> = HashMap::new();
+        let store = self.lock();
+        for entry in &store.entries {
+            *seconds.entry(&entry.task_id).or_default() += entry_seconds(entry, now);
+        }
+
+        let rows: Vec<ReportRow> = tasks
+            .iter()
+            .map(|task| ReportRow {
+                task_id: task.id.clone(),
+                text: task.text.clone(),
+                date: task.date.clone(),
+                goal_id: task.goal_id.clone(),
+                completed: task.completed,
+                estimate_minutes: task.timebox_minutes,
+                actual_minutes: to_minutes(seconds.get(task.id.as_str()).copied().unwrap_or(0)),
+            })
+            .collect();
+
+        Ok(TimeReport {
+            estimate_minutes: rows.iter().filter_map(|r| r.estimate_minutes).map(i64::from).sum(),
+            actual_minutes: rows.iter().map(|r| r.actual_minutes).sum(),
+            estimated_tasks_actual_minutes: rows
+                .iter()
+                .filter(|r| r.estimate_minutes.is_some())
+                .map(|r| r.actual_minutes)
+                .sum(),
+            rows,
+        })
+    }
+
+    pub fn add_manual(&self, task_id: String, start: &str, stop: &str, note: &str) -> Result<TimeEntry, String> {
+        let (start, stop) = validate_span(start, Some(stop))?;
+        self.update(|store| {
+            check_overlap(&store.entries, &task_id, None, start, stop)?;
+            let entry = TimeEntry {
+                id: Uuid::new_v4().to_string(),
+                task_id,
+                source: EntrySource::Manual,
+                start: format_time(start),
+                stop: stop.map(format_time),
+                timer_id: None,
+                note: note.trim().to_string(),
+                edited_at: None,
+            };
+            store.entries.push(entry.clone());
+            Ok(entry)
+        })
+    }
+
+    /// Corrects an entry. The stop time of an entry whose timer is still
+    /// running can't be set by hand; stop the timer instead.
+    pub fn edit(&self, id: &str, edit: EntryEdit) -> Result<TimeEntry, String> {
+        self.update(|store| {
+            let index = store
+                .entries
+                .iter()
+                .position(|e| e.id == id)
+                .ok_or_else(|| format!("No time entry with id {}", id))?;
+            let entry = &store.entries[index];
+            if edit.stop.is_some() && entry.stop.is_none() {
+                return Err("This entry is still running".to_string());
+            }
+
+            let start = edit.start.as_deref().unwrap_or(&entry.start);
+            let stop = edit.stop.as_deref().or(entry.stop.as_deref());
+            let (start, stop) = validate_span(start, stop)?;
+            let task_id = edit.task_id.as_deref().unwrap_or(&entry.task_id);
+            check_overlap(&store.entries, task_id, Some(id), start, stop)?;
+
+            let entry = &mut store.entries[index];
+            entry.start = format_time(start);
+            entry.stop = stop.map(format_time);
+            if let Some(task_id) = edit.task_id {
+                entry.task_id = task_id;
+            }
+            if let Some(note) = edit.note {
+                entry.note = note.trim().to_string();
+            }
+            entry.edited_at = Some(format_time(Utc::now()));
+            Ok(entry.clone())
+        })
+    }
+
+    pub fn delete(&self, id: &str) -> Result<TimeEntry, String> {
+        self.update(|store| {
+            let index = store
+                .entries
+                .iter()
+                .position(|e| e.id == id)
+                .ok_or_else(|| format!("No time entry with id {}", id))?;
+            Ok(store.entries.remove(index))
+        })
+    }
+
+    fn open(&self, timer: &ActiveTimer, source: EntrySource, start: DateTime<Utc>) {
+        let Some(task_id) = timer.task_id.clone() else {
+            return;
+        };
+        self.update(|store| {
+            if store.entries.iter().any(|e| e.stop.is_none() && e.timer_id.as_deref() == Some(timer.id.as_str())) {
+                return;
+            }
+            store.entries.push(TimeEntry {
+                id: Uuid::new_v4().to_string(),
+                task_id,
+                source,
+                start: format_time(start),
+                stop: None,
+                timer_id: Some(timer.id.clone()),
+                note: String::new(),
+                edited_at: None,
+            });
+        });
+    }
+
+    fn close(&self, timer_id: &str, stop: DateTime<Utc>) {
+        if !self.is_open(timer_id) {
+            return;
+        }
+        self.update(|store| {
+            for entry in store.entries.iter_mut() {
+                if entry.stop.is_none() && entry.timer_id.as_deref() == Some(timer_id) {
+                    let start = parse_time(&entry.start).unwrap_or(stop);
+                    entry.stop = Some(format_time(stop.max(start)));
+                }
+            }
+        });
+    }
+
+    fn is_open(&self, timer_id: &str) -> bool {
+        self.lock()
+            .entries
+            .iter()
+            .any(|e| e.stop.is_none() && e.timer_id.as_deref() == Some(timer_id))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, TimeStore> {
+        self.store.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn update<T>(&self, f: impl FnOnce(&mut TimeStore) -> T) -> T {
+        let mut store = self.lock();
+        let result = f(&mut store);
+        if let Err(e) = storage::write_json(&self.path, &*store) {
+            eprintln!("Failed to save time entries: {}", e);
+        }
+        result
+    }
+}
+
+/// Which timers count as working on their task: timeboxes, and pomodoro work
+/// phases started for a task. Breaks and unnamed focus timers are not tracked.
+fn tracked_source(timer: &ActiveTimer) -> Option<EntrySource> {
+    timer.task_id.as_ref()?;
+    match timer.timer_type.as_str() {
+        "task" => Some(EntrySource::Timebox),
+        pomodoro::TIMER_TYPE if timer.pomodoro_phase.as_deref() == Some("work") => Some(EntrySource::Timer),
+        _ => None,
+    }
+}
+
+fn validate_span(start: &str, stop: Option<&str>) -> Result<(DateTime<Utc>, Option<DateTime<Utc>>), String> {
+    let start = parse_time(start).ok_or_else(|| format!("Invalid start time: {}", start))?;
+    let stop = match stop {
+        Some(stop) => Some(parse_time(stop).ok_or_else(|| format!("Invalid stop time: {}", stop))?),
+        None => None,
+    };
+    if let Some(stop) = stop {
+        if stop <= start {
+            return Err("Stop time must be after the start time".to_string());
+        }
+        if stop - start > Duration::hours(24) {
+            return Err("A time entry can't be longer than 24 hours".to_string());
+        }
+    }
+    if stop.unwrap_or(start) > Utc::now() + Duration::minutes(1) {
+        return Err("Time entries can't be in the future".to_string());
+    }
+    Ok((start, stop))
+}
+
+/// Rejects a span that overlaps another entry of the same task, which would
+/// count that time twice. Running entries extend to now.
+fn check_overlap(
+    entries: &[TimeEntry],
+    task_id: &str,
+    except_id: Option<&str>,
+    start: DateTime<Utc>,
+    stop: Option<DateTime<Utc>>,
+) -> Result<(), String> {
+    let now = Utc::now();
+    let stop = stop.unwrap_or(now);
+    let overlaps = entries
+        .iter()
+        .filter(|e| e.task_id == task_id && Some(e.id.as_str()) != except_id)
+        .any(|e| {
+            let Some(other_start) = parse_time(&e.start) else {
+                return false;
+            };
+            let other_stop = e.stop.as_deref().and_then(parse_time).unwrap_or(now);
+            start < other_stop && other_start < stop
+        });
+    if overlaps {
+        return Err("This overlaps another time entry for the task".to_string());
+    }
+    Ok(())
+}
+
+fn entry_seconds(entry: &TimeEntry, now: DateTime<Utc>) -> i64 {
+    let Some(start) = parse_time(&entry.start) else {
+        return 0;
+    };
+    let stop = entry.stop.as_deref().and_then(parse_time).unwrap_or(now);
+    (stop - start).num_seconds().max(0)
+}
+
+fn to_minutes(seconds: i64) -> i64 {
+    (seconds + 30) / 60
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").map_err(|_| format!("Invalid date (expected YYYY-MM-DD): {}", date))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DailyTask;
+
+    fn tracker() -> (TimeTracker, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("growing-time-{}", Uuid::new_v4()));
+        (TimeTracker::load(dir.join(TIME_ENTRIES_FILE)), dir)
+    }
+
+    fn ago(minutes: i64) -> DateTime<Utc> {
+        Utc::now() - Duration::minutes(minutes)
+    }
+
+    fn timebox(task_id: &str, started: DateTime<Utc>, minutes: i64) -> ActiveTimer {
+        ActiveTimer {
+            id: format!("timer-{}", task_id),
+            timer_type: "task".to_string(),
+            task_id: Some(task_id.to_string()),
+            started_at: Some(format_time(started)),
+            end_time: format_time(started + Duration::minutes(minutes)),
+            duration_minutes: minutes as i32,
+            ..Default::default()
+        }
+    }
+
+    fn total(tracker: &TimeTracker, task_id: &str) -> (i64, usize, bool) {
+        let totals = tracker.totals(Some(&[task_id.to_string()]));
+        totals.first().map_or((0, 0, false), |t| (t.actual_minutes, t.entry_count, t.running))
+    }
+
+    #[test]
+    fn timer_start_opens_an_entry_and_pause_closes_it() {
+        let (tracker, dir) = tracker();
+        let mut timer = timebox("a", ago(30), 60);
+        tracker.on_timer_event(&TimerEvent::Started { timer: timer.clone() });
+        // A second start event for the same timer doesn't open another entry.
+        tracker.on_timer_event(&TimerEvent::Started { timer: timer.clone() });
+        assert_eq!(total(&tracker, "a"), (30, 1, true));
+
+        timer.paused_at = Some(format_time(ago(10)));
+        tracker.on_timer_event(&TimerEvent::Paused { timer: timer.clone() });
+        assert_eq!(total(&tracker, "a"), (20, 1, false));
+
+        timer.paused_at = None;
+        tracker.on_timer_event(&TimerEvent::Resumed { timer: timer.clone() });
+        assert_eq!(total(&tracker, "a").1, 2);
+        tracker.on_timer_event(&TimerEvent::Cancelled { timer });
+        let entries = tracker.entries(Some("a"), None, None);
+        assert!(entries.iter().all(|e| e.stop.is_some() && e.source == EntrySource::Timebox));
+
+        // Reloading keeps the entries.
+        let reloaded = TimeTracker::load(dir.join(TIME_ENTRIES_FILE));
+        assert_eq!(reloaded.entries(None, None, None).len(), 2);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn late_expiry_counts_up_to_the_deadline() {
+        let (tracker, dir) = tracker();
+        let timer = timebox("a", ago(120), 25);
+        tracker.on_timer_event(&TimerEvent::Started { timer: timer.clone() });
+        tracker.on_timer_event(&TimerEvent::Expired { timer: timer.clone(), late: true });
+        assert_eq!(total(&tracker, "a"), (25, 1, false));
+
+        // Extending the ended timer starts a new entry.
+        tracker.on_timer_event(&TimerEvent::Extended { timer });
+        assert_eq!(total(&tracker, "a"), (25, 2, true));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn only_work_phases_and_timeboxes_with_a_task_are_tracked() {
+        let (tracker, dir) = tracker();
+        let phase = |phase: &str| ActiveTimer {
+            id: phase.to_string(),
+            timer_type: pomodoro::TIMER_TYPE.to_string(),
+            task_id: Some("a".to_string()),
+            pomodoro_phase: Some(phase.to_string()),
+            started_at: Some(format_time(ago(5))),
+            ..Default::default()
+        };
+        for timer in [phase("shortBreak"), timebox("a", ago(5), 25), phase("work")] {
+            let timer = ActiveTimer { task_id: None, ..timer };
+            tracker.on_timer_event(&TimerEvent::Started { timer });
+        }
+        tracker.on_timer_event(&TimerEvent::Started { timer: phase("shortBreak") });
+        assert!(tracker.entries(None, None, None).is_empty());
+
+        tracker.on_timer_event(&TimerEvent::Started { timer: phase("work") });
+        let entries = tracker.entries(None, None, None);
+        assert_eq!(entries.iter().map(|e| e.source).collect::<Vec<_>>(), [EntrySource::Timer]);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn manual_entries_are_validated() {
+        let (tracker, dir) = tracker();
+        let t = |minutes_ago| format_time(ago(minutes_ago));
+        let entry = tracker.add_manual("a".to_string(), &t(120), &t(60), "  notes ").unwrap();
+        assert_eq!((entry.source, entry.note.as_str()), (EntrySource::Manual, "notes"));
+
+        let err = |r: Result<TimeEntry, String>| r.unwrap_err();
+        assert_eq!(err(tracker.add_manual("a".to_string(), &t(30), &t(40), "")), "Stop time must be after the start time");
+        assert_eq!(err(tracker.add_manual("a".to_string(), "yesterday", &t(40), "")), "Invalid start time: yesterday");
+        assert_eq!(err(tracker.add_manual("a".to_string(), &t(30), &t(-30), "")), "Time entries can't be in the future");
+        assert_eq!(
+            err(tracker.add_manual("a".to_string(), &t(90), &t(30), "")),
+            "This overlaps another time entry for the task"
+        );
+        // Back to back is fine, and so is the same time on another task.
+        let next = tracker.add_manual("a".to_string(), &entry.stop.clone().unwrap(), &t(30), "").unwrap();
+        tracker.add_manual("b".to_string(), &t(90), &t(30), "").unwrap();
+
+        let edit = |start: Option<String>, stop: Option<String>| EntryEdit { start, stop, ..Default::default() };
+        assert_eq!(err(tracker.edit("missing", edit(None, None))), "No time entry with id missing");
+        assert_eq!(err(tracker.edit(&next.id, edit(None, Some(t(70))))), "Stop time must be after the start time");
+        assert_eq!(
+            err(tracker.edit(&next.id, edit(Some(t(61)), None))),
+            "This overlaps another time entry for the task"
+        );
+        // Moving the entry to another task is checked against that task's entries.
+        let to_b = EntryEdit { task_id: Some("b".to_string()), ..Default::default() };
+        assert_eq!(err(tracker.edit(&next.id, to_b)), "This overlaps another time entry for the task");
+
+        let edited = tracker.edit(&next.id, edit(Some(t(45)), None)).unwrap();
+        assert!(edited.edited_at.is_some());
+        assert_eq!(total(&tracker, "a"), (75, 2, false));
+        assert_eq!(err(tracker.delete("missing")), "No time entry with id missing");
+        tracker.delete(&entry.id).unwrap();
+        assert_eq!(total(&tracker, "a"), (15, 1, false));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn running_entries_stop_being_editable_by_hand() {
+        let (tracker, dir) = tracker();
+        tracker.on_timer_event(&TimerEvent::Started { timer: timebox("a", ago(10), 25) });
+        let id = tracker.entries(None, None, None)[0].id.clone();
+        let stop = EntryEdit { stop: Some(format_time(ago(5))), ..Default::default() };
+        assert_eq!(tracker.edit(&id, stop).unwrap_err(), "This entry is still running");
+        // Nor can a manual entry be added over the running one.
+        let err = tracker.add_manual("a".to_string(), &format_time(ago(8)), &format_time(ago(6)), "");
+        assert_eq!(err.unwrap_err(), "This overlaps another time entry for the task");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn report_compares_estimates_by_day_week_and_goal() {
+        let (tracker, dir) = tracker();
+        let task = |id: &str, date: &str, estimate: Option<i32>, goal: Option<&str>| DailyTask {
+            id: id.to_string(),
+            text: id.to_string(),
+            date: date.to_string(),
+            timebox_minutes: estimate,
+            goal_id: goal.map(str::to_string),
+            ..Default::default()
+        };
+        let data = AppData {
+            daily_tasks: vec![
+                // 2026-03-02 is a Monday.
+                task("sun", "2026-03-01", Some(10), Some("g")),
+                task("mon", "2026-03-02", Some(30), Some("g")),
+                task("wed", "2026-03-04", None, None),
+                task("sun2", "2026-03-08", Some(20), Some("g")),
+                task("next", "2026-03-09", Some(60), None),
+                DailyTask { moved_to_date: Some("2026-03-09".to_string()), ..task("moved", "2026-03-03", Some(5), None) },
+            ],
+            ..Default::default()
+        };
+        for (task_id, minutes) in [("mon", 45), ("wed", 20), ("sun2", 10), ("moved", 5)] {
+            let stop = ago(200);
+            tracker.add_manual(task_id.to_string(), &format_time(stop - Duration::minutes(minutes)), &format_time(stop), "").unwrap();
+        }
+
+        let summary = |period: ReportPeriod| {
+            let report = tracker.report(&data, &period).unwrap();
+            let rows: Vec<(String, i64)> = report.rows.iter().map(|r| (r.task_id.clone(), r.actual_minutes)).collect();
+            (rows, report.estimate_minutes, report.actual_minutes, report.estimated_tasks_actual_minutes)
+        };
+        let rows = |rows: &[(&str, i64)]| rows.iter().map(|(id, m)| (id.to_string(), *m)).collect::<Vec<_>>();
+
+        let day = summary(ReportPeriod::Day { date: "2026-03-02".to_string() });
+        assert_eq!(day, (rows(&[("mon", 45)]), 30, 45, 45));
+        // The week runs Monday to Sunday and leaves out the moved task.
+        let week = summary(ReportPeriod::Week { date: "2026-03-05".to_string() });
+        assert_eq!(week, (rows(&[("mon", 45), ("wed", 20), ("sun2", 10)]), 50, 75, 55));
+        let sunday = summary(ReportPeriod::Week { date: "2026-03-08".to_string() });
+        assert_eq!(sunday.0.len(), 3);
+        let goal = summary(ReportPeriod::Goal { goal_id: "g".to_string() });
+        assert_eq!(goal, (rows(&[("sun", 0), ("mon", 45), ("sun2", 10)]), 60, 55, 55));
+
+        let bad = tracker.report(&data, &ReportPeriod::Day { date: "March 2".to_string() });
+        assert_eq!(bad.unwrap_err(), "Invalid date (expected YYYY-MM-DD): March 2");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
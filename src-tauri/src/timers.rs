@@ -0,0 +1,413 @@
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::clock::Clock;
+use crate::models::ActiveTimer;
+use crate::pomodoro;
+use crate::storage::{format_time, parse_time};
+
+/// Longest the worker sleeps without re-reading the wall clock. Condvar
+/// timeouts run on the monotonic clock, which stops while the machine sleeps,
+/// so deadlines are re-checked at least this often.
+const MAX_WAIT: StdDuration = StdDuration::from_secs(30);
+
+/// Expiries noticed more than this long after the deadline are reported as
+/// late (the app was closed or the machine was asleep).
+const LATE_AFTER: Duration = Duration::seconds(60);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum TimerEvent {
+    Started { timer: ActiveTimer },
+    Paused { timer: ActiveTimer },
+    Resumed { timer: ActiveTimer },
+    Extended { timer: ActiveTimer },
+    Cancelled { timer: ActiveTimer },
+    Expired { timer: ActiveTimer, late: bool },
+}
+
+impl TimerEvent {
+    pub fn timer(&self) -> &ActiveTimer {
+        match self {
+            TimerEvent::Started { timer }
+            | TimerEvent::Paused { timer }
+            | TimerEvent::Resumed { timer }
+            | TimerEvent::Extended { timer }
+            | TimerEvent::Cancelled { timer }
+            | TimerEvent::Expired { timer, .. } => timer,
+        }
+    }
+}
+
+type Listener = Box<dyn Fn(&TimerEvent) + Send + Sync>;
+
+struct Inner {
+    timers: Mutex<Vec<ActiveTimer>>,
+    changed: Condvar,
+    listener: Listener,
+    clock: Arc<dyn Clock>,
+}
+
+impl Inner {
+    fn now(&self) -> DateTime<Utc> {
+        self.clock.now().with_timezone(&Utc)
+    }
+}
+
+/// Owns the active timers and fires their expiries from a background thread,
+/// independent of whether the webview is visible or throttled.
+///
+/// Every change is reported to the listener, which is responsible for
+/// persisting the timers and notifying the UI.
+#[derive(Clone)]
+pub struct TimerService {
+    inner: Arc<Inner>,
+}
+
+impl TimerService {
+    pub fn new<F>(clock: Arc<dyn Clock>, timers: Vec<ActiveTimer>, listener: F) -> Self
+    where
+        F: Fn(&TimerEvent) + Send + Sync + 'static,
+    {
+        Self {
+            inner: Arc::new(Inner {
+                timers: Mutex::new(timers),
+                changed: Condvar::new(),
+                listener: Box::new(listener),
+                clock,
+            }),
+        }
+    }
+
+    /// Starts the expiry thread. Timers restored from disk that ran out while
+    /// the app was closed fire (as late) on its first pass, so call this once
+    /// the listener can persist them.
+    pub fn run(&self) {
+        let worker = self.inner.clone();
+        thread::spawn(move || run(worker));
+    }
+
+    pub fn list(&self) -> Vec<ActiveTimer> {
+        self.lock().clone()
+    }
+
+    pub fn add(&self, mut timer: ActiveTimer) -> ActiveTimer {
+        if timer.started_at.is_none() {
+            timer.started_at = Some(format_time(self.inner.now()));
+        }
+        self.lock().push(timer.clone());
+        self.notify(TimerEvent::Started { timer: timer.clone() });
+        timer
+    }
+
+    pub fn pause(&self, id: &str) -> Result<ActiveTimer, String> {
+        let now = self.inner.now();
+        let timer = self.modify(id, |timer| {
+            if timer.paused_at.is_some() {
+                return Err("Timer is already paused".to_string());
+            }
+            if timer.expired_at.is_some() {
+                return Err("Timer has already ended".to_string());
+            }
+            let remaining = (end_time(timer) - now).num_seconds().max(0);
+            timer.paused_at = Some(format_time(now));
+            timer.remaining_seconds = Some(remaining);
+            Ok(())
+        })?;
+        self.notify(TimerEvent::Paused { timer: timer.clone() });
+        Ok(timer)
+    }
+
+    pub fn resume(&self, id: &str) -> Result<ActiveTimer, String> {
+        let now = self.inner.now();
+        let timer = self.modify(id, |timer| {
+            if timer.paused_at.is_none() {
+                return Err("Timer is not paused".to_string());
+            }
+            let remaining = timer.remaining_seconds.take().unwrap_or(0);
+            timer.paused_at = None;
+            timer.end_time = format_time(now + Duration::seconds(remaining));
+            Ok(())
+        })?;
+        self.notify(TimerEvent::Resumed { timer: timer.clone() });
+        Ok(timer)
+    }
+
+    /// Adds time to a timer. Extending a timer that already ended restarts
+    /// it from now.
+    pub fn extend(&self, id: &str, minutes: i32) -> Result<ActiveTimer, String> {
+        if minutes <= 0 {
+            return Err(format!("Extension must be a positive number of minutes, got {}", minutes));
+        }
+        let now = self.inner.now();
+        let extra = Duration::minutes(minutes as i64);
+        let timer = self.modify(id, |timer| {
+            if let Some(remaining) = timer.remaining_seconds.as_mut() {
+                *remaining += extra.num_seconds();
+            } else {
+                timer.end_time = format_time(end_time(timer).max(now) + extra);
+            }
+            timer.expired_at = None;
+            timer.duration_minutes += minutes;
+            Ok(())
+        })?;
+        self.notify(TimerEvent::Extended { timer: timer.clone() });
+        Ok(timer)
+    }
+
+    /// Removes one timer, or every timer when `id` is `None`.
+    pub fn cancel(&self, id: Option<&str>) -> Result<Vec<ActiveTimer>, String> {
+        let removed: Vec<ActiveTimer> = {
+            let mut timers = self.lock();
+            match id {
+                Some(id) => {
+                    let index = timers
+                        .iter()
+                        .position(|t| t.id == id)
+                        .ok_or_else(|| format!("No timer with id {}", id))?;
+                    vec![timers.remove(index)]
+                }
+                None => timers.drain(..).collect(),
+            }
+        };
+        for timer in &removed {
+            self.notify(TimerEvent::Cancelled { timer: timer.clone() });
+        }
+        Ok(removed)
+    }
+
+    /// Re-evaluates deadlines immediately, e.g. after the system wakes.
+    pub fn wake(&self) {
+        self.inner.changed.notify_all();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<ActiveTimer>> {
+        self.inner.timers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn modify<F>(&self, id: &str, f: F) -> Result<ActiveTimer, String>
+    where
+        F: FnOnce(&mut ActiveTimer) -> Result<(), String>,
+    {
+        let mut timers = self.lock();
+        let timer = timers
+            .iter_mut()
+            .find(|t| t.id == id)
+            .ok_or_else(|| format!("No timer with id {}", id))?;
+        f(timer)?;
+        Ok(timer.clone())
+    }
+
+    fn notify(&self, event: TimerEvent) {
+        self.inner.changed.notify_all();
+        (self.inner.listener)(&event);
+    }
+}
+
+fn run(inner: Arc<Inner>) {
+    let mut timers = inner.timers.lock().unwrap_or_else(|e| e.into_inner());
+    loop {
+        let now = inner.now();
+        let (expired, next_deadline) = expire(&mut timers, now);
+
+        if !expired.is_empty() {
+            // Never call out while holding the lock; listeners read the timers.
+            drop(timers);
+            for event in &expired {
+                (inner.listener)(event);
+            }
+            timers = inner.timers.lock().unwrap_or_else(|e| e.into_inner());
+            continue;
+        }
+
+        let wait = next_deadline
+            .and_then(|d| (d - now).to_std().ok())
+            .map_or(MAX_WAIT, |d| d.min(MAX_WAIT));
+        timers = inner
+            .changed
+            .wait_timeout(timers, wait)
+            .unwrap_or_else(|e| e.into_inner())
+            .0;
+    }
+}
+
+/// Marks the running timers whose deadline has passed as expired. Returns
+/// their expiries and the next deadline of those still running.
+fn expire(timers: &mut [ActiveTimer], now: DateTime<Utc>) -> (Vec<TimerEvent>, Option<DateTime<Utc>>) {
+    let mut expired = Vec::new();
+    let mut next_deadline: Option<DateTime<Utc>> = None;
+    for timer in timers.iter_mut() {
+        if timer.paused_at.is_some() || timer.expired_at.is_some() {
+            continue;
+        }
+        let end = end_time(timer);
+        if end <= now {
+            timer.expired_at = Some(format_time(now));
+            expired.push(TimerEvent::Expired {
+                timer: timer.clone(),
+                late: now - end > LATE_AFTER,
+            });
+        } else {
+            next_deadline = Some(next_deadline.map_or(end, |d| d.min(end)));
+        }
+    }
+    (expired, next_deadline)
+}
+
+/// Deadline of a running timer. Unparseable end times count as already due.
+pub fn end_time(timer: &ActiveTimer) -> DateTime<Utc> {
+    parse_time(&timer.end_time).unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
+
+/// Timers for working, as opposed to breaks: focus timers, task timeboxes
+/// and pomodoro work phases.
+pub fn is_focus(timer: &ActiveTimer) -> bool {
+    match timer.timer_type.as_str() {
+        "focus" | "task" => true,
+        pomodoro::TIMER_TYPE => timer.pomodoro_phase.as_deref() == Some("work"),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use chrono::TimeZone;
+    use std::sync::mpsc;
+
+    fn at(h: u32, m: u32, s: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, h, m, s).unwrap()
+    }
+
+    fn timer(id: &str, end: DateTime<Utc>) -> ActiveTimer {
+        ActiveTimer {
+            id: id.to_string(),
+            timer_type: "focus".to_string(),
+            end_time: format_time(end),
+            duration_minutes: 10,
+            ..Default::default()
+        }
+    }
+
+    /// A service on a manual clock at 09:00 UTC, and the events it reports.
+    fn service(timers: Vec<ActiveTimer>) -> (TimerService, Arc<ManualClock>, mpsc::Receiver<TimerEvent>) {
+        let clock = Arc::new(ManualClock::new(at(9, 0, 0).with_timezone(&chrono_tz::UTC)));
+        let (events, received) = mpsc::channel();
+        let events = Mutex::new(events);
+        let service = TimerService::new(clock.clone(), timers, move |event| {
+            let _ = events.lock().unwrap().send(event.clone());
+        });
+        (service, clock, received)
+    }
+
+    fn kinds(events: &mpsc::Receiver<TimerEvent>) -> Vec<String> {
+        events
+            .try_iter()
+            .map(|event| serde_json::to_value(&event).unwrap()["kind"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn resuming_moves_the_deadline_by_the_pause() {
+        let (service, clock, events) = service(Vec::new());
+        let started = service.add(timer("t", at(9, 10, 0)));
+        assert_eq!(started.started_at.as_deref(), Some("2026-03-02T09:00:00.000Z"));
+
+        clock.advance(Duration::minutes(2));
+        let paused = service.pause("t").unwrap();
+        assert_eq!(paused.remaining_seconds, Some(8 * 60));
+        assert_eq!(service.pause("t").unwrap_err(), "Timer is already paused");
+
+        clock.advance(Duration::minutes(5));
+        let resumed = service.resume("t").unwrap();
+        assert_eq!(end_time(&resumed), at(9, 15, 0));
+        assert_eq!((resumed.paused_at, resumed.remaining_seconds), (None, None));
+        assert_eq!(service.resume("t").unwrap_err(), "Timer is not paused");
+        assert_eq!(kinds(&events), ["started", "paused", "resumed"]);
+    }
+
+    #[test]
+    fn extending_a_paused_timer_adds_to_what_is_left() {
+        let (service, clock, events) = service(vec![timer("t", at(9, 10, 0))]);
+        clock.advance(Duration::minutes(4));
+        service.pause("t").unwrap();
+        let extended = service.extend("t", 5).unwrap();
+        assert_eq!(extended.remaining_seconds, Some(11 * 60));
+        assert_eq!(extended.duration_minutes, 15);
+        assert_eq!(end_time(&extended), at(9, 10, 0));
+
+        clock.advance(Duration::hours(1));
+        assert_eq!(end_time(&service.resume("t").unwrap()), at(10, 15, 0));
+        assert_eq!(kinds(&events), ["paused", "extended", "resumed"]);
+    }
+
+    #[test]
+    fn extending_adds_to_the_deadline_or_restarts_an_ended_timer() {
+        let (service, clock, _events) = service(vec![timer("t", at(9, 10, 0))]);
+        assert_eq!(end_time(&service.extend("t", 5).unwrap()), at(9, 15, 0));
+        assert!(service.extend("t", 0).is_err());
+
+        clock.set(at(9, 20, 0).with_timezone(&chrono_tz::UTC));
+        expire(&mut service.lock(), at(9, 20, 0));
+        assert_eq!(service.pause("t").unwrap_err(), "Timer has already ended");
+        let restarted = service.extend("t", 5).unwrap();
+        assert_eq!(end_time(&restarted), at(9, 25, 0));
+        assert_eq!(restarted.expired_at, None);
+    }
+
+    #[test]
+    fn timers_expire_once_at_their_deadline() {
+        let mut timers = vec![timer("soon", at(9, 5, 0)), timer("later", at(9, 30, 0)), timer("paused", at(9, 1, 0))];
+        timers[2].paused_at = Some(format_time(at(8, 59, 0)));
+        timers[2].remaining_seconds = Some(120);
+
+        let (expired, next) = expire(&mut timers, at(9, 4, 59));
+        assert!(expired.is_empty());
+        assert_eq!(next, Some(at(9, 5, 0)));
+
+        let (expired, next) = expire(&mut timers, at(9, 5, 30));
+        assert!(matches!(&expired[..], [TimerEvent::Expired { timer, late: false }] if timer.id == "soon"));
+        assert_eq!(timers[0].expired_at.as_deref(), Some("2026-03-02T09:05:30.000Z"));
+        assert_eq!(next, Some(at(9, 30, 0)));
+        assert!(expire(&mut timers, at(9, 6, 0)).0.is_empty());
+    }
+
+    #[test]
+    fn timers_restored_after_a_restart_expire_at_once() {
+        let restored = vec![timer("gone", at(8, 30, 0)), timer("just", at(8, 59, 30)), timer("running", at(9, 30, 0))];
+        let (service, _clock, events) = service(restored);
+        service.run();
+        let mut expired = Vec::new();
+        for _ in 0..2 {
+            match events.recv_timeout(StdDuration::from_secs(5)).unwrap() {
+                TimerEvent::Expired { timer, late } => expired.push((timer.id, late)),
+                other => panic!("unexpected event {:?}", other),
+            }
+        }
+        expired.sort();
+        assert_eq!(expired, [("gone".to_string(), true), ("just".to_string(), false)]);
+        assert!(events.recv_timeout(StdDuration::from_millis(100)).is_err());
+        let running = service.list().into_iter().find(|t| t.id == "running").unwrap();
+        assert_eq!(running.expired_at, None);
+    }
+
+    #[test]
+    fn cancelling_removes_one_or_all() {
+        let (service, _clock, events) = service(vec![timer("a", at(9, 10, 0)), timer("b", at(9, 20, 0))]);
+        assert_eq!(service.cancel(Some("missing")).unwrap_err(), "No timer with id missing");
+        assert_eq!(service.pause("missing").unwrap_err(), "No timer with id missing");
+        assert!(kinds(&events).is_empty());
+        assert_eq!(service.list().len(), 2);
+
+        assert_eq!(service.cancel(Some("a")).unwrap()[0].id, "a");
+        service.add(timer("c", at(9, 30, 0)));
+        assert_eq!(service.cancel(None).unwrap().len(), 2);
+        assert!(service.list().is_empty());
+        assert_eq!(kinds(&events), ["cancelled", "started", "cancelled", "cancelled"]);
+    }
+}
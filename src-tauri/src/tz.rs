@@ -0,0 +1,103 @@
+use std::sync::RwLock;
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+
+use crate::models::AppData;
+
+/// The zone chosen in settings, if any. `None` follows the system.
+static CONFIGURED: RwLock<Option<Tz>> = RwLock::new(None);
+
+/// Parses an IANA zone name such as `Europe/Berlin`.
+pub fn parse(name: &str) -> Result<Tz, String> {
+    name.trim().parse().map_err(|_| format!("Unknown time zone: {}", name))
+}
+
+/// The system's zone, read again on every call so a change of zone (travel,
+/// the user picking another one) is noticed. `TZ` wins over the system
+/// setting, like it does for the C library; anything unreadable is UTC.
+pub fn system() -> Tz {
+    std::env::var("TZ")
+        .ok()
+        .and_then(|name| parse(name.trim_start_matches(':')).ok())
+        .or_else(|| iana_time_zone::get_timezone().ok().and_then(|name| parse(&name).ok()))
+        .unwrap_or(Tz::UTC)
+}
+
+/// Applies the `timezone` setting; `None` or an empty name follows the
+/// system. Returns whether the configured zone changed.
+pub fn configure(name: Option<&str>) -> bool {
+    let zone = match name.map(str::trim).filter(|name| !name.is_empty()) {
+        Some(name) => match parse(name) {
+            Ok(zone) => Some(zone),
+            Err(e) => {
+                eprintln!("{}, following the system zone", e);
+                None
+            }
+        },
+        None => None,
+    };
+    let mut configured = CONFIGURED.write().unwrap_or_else(|e| e.into_inner());
+    let changed = *configured != zone;
+    *configured = zone;
+    changed
+}
+
+/// The zone schedules, "today" and daily statistics are computed in.
+pub fn zone() -> Tz {
+    let configured = *CONFIGURED.read().unwrap_or_else(|e| e.into_inner());
+    configured.unwrap_or_else(system)
+}
+
+pub fn now() -> DateTime<Tz> {
+    Utc::now().with_timezone(&zone())
+}
+
+/// The current time in the zone set in `data`, falling back to the system.
+/// For code that runs outside the app, such as the CLI, where the setting
+/// isn't applied globally.
+pub fn now_in(data: &AppData) -> DateTime<Tz> {
+    let zone = data.timezone.as_deref().and_then(|name| parse(name).ok()).unwrap_or_else(system);
+    Utc::now().with_timezone(&zone)
+}
+
+/// What the settings screen offers.
+#[derive(Debug, Serialize)]
+pub struct Timezones {
+    /// The system's zone, used while none is set.
+    pub system: String,
+    /// The zone in effect.
+    pub current: String,
+    /// Every known IANA zone name.
+    pub zones: Vec<&'static str>,
+}
+
+pub fn list() -> Timezones {
+    Timezones {
+        system: system().name().to_string(),
+        current: zone().name().to_string(),
+        zones: chrono_tz::TZ_VARIANTS.iter().map(|zone| zone.name()).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_iana_names() {
+        assert_eq!(parse("Europe/Berlin"), Ok(chrono_tz::Europe::Berlin));
+        assert_eq!(parse(" Asia/Tokyo "), Ok(chrono_tz::Asia::Tokyo));
+        assert!(parse("Mars/Olympus_Mons").is_err());
+        assert!(parse("").is_err());
+    }
+
+    #[test]
+    fn now_in_uses_the_zone_set_in_the_data() {
+        let data = AppData { timezone: Some("Asia/Kolkata".to_string()), ..Default::default() };
+        assert_eq!(now_in(&data).timezone(), chrono_tz::Asia::Kolkata);
+        let unknown = AppData { timezone: Some("Nowhere".to_string()), ..Default::default() };
+        assert_eq!(now_in(&unknown).timezone(), system());
+    }
+}
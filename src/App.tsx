@@ -40,7 +40,7 @@ import { CuriosityModal } from "./components/shared/CuriosityModal";
 import { ReviewModal } from "./components/shared/ReviewModal";
 import { TaskModal } from "./components/shared/TaskModal";
 import { BragDocModal } from "./components/shared/BragDocModal";
-import { NavView, Todo, NowPlayingInfo, Curiosity, Review, TaskCategory, DailyTask, BragDocEntry } from "./types";
+import { NavView, Todo, NowPlayingInfo, Curiosity, Review, TaskCategory, DailyTask, BragDocEntry, AlertRef, ReminderRule, TaskReminder } from "./types";
 import { getTodayDate } from "./utils/dateUtils";
 import { parsePrLink } from "./hooks/useReviews";
 import { formatDateHeader } from "./utils/formatUtils";
@@ -48,15 +48,17 @@ import { createScaffoldData } from "./utils/scaffoldData";
 import "./App.css";
 
 interface AppContentProps {
-  alertOverlay: { show: boolean; title: string; body: string; type: string } | null;
+  alertOverlay: { show: boolean; title: string; body: string; type: string; alert?: AlertRef; task?: Pick<TaskReminder, "kind" | "id"> } | null;
   onDismissAlert: () => void;
+  onSnoozeAlert: (minutes: number) => void;
+  onCompleteAlertTask: () => void;
   nowPlaying: NowPlayingInfo | null;
   onRefreshNowPlaying: () => void;
   onTimerExpired: (type: "focus" | "task", taskName?: string, timerId?: string) => void;
   onRegisterStopTimer: (stopFn: (timerId?: string) => void) => void;
 }
 
-function AppContent({ alertOverlay, onDismissAlert, nowPlaying, onRefreshNowPlaying, onTimerExpired, onRegisterStopTimer }: AppContentProps) {
+function AppContent({ alertOverlay, onDismissAlert, onSnoozeAlert, onCompleteAlertTask, nowPlaying, onRefreshNowPlaying, onTimerExpired, onRegisterStopTimer }: AppContentProps) {
   const { data, saveData } = useAppData();
 
   const {
@@ -84,6 +86,13 @@ function AppContent({ alertOverlay, onDismissAlert, nowPlaying, onRefreshNowPlay
     setModalTaskDescription,
     modalTaskGoalId,
     setModalTaskGoalId,
+    modalTaskRemindTime,
+    setModalTaskRemindTime,
+    modalTaskRepeat,
+    setModalTaskRepeat,
+    modalEditScope,
+    setModalEditScope,
+    modalTaskError,
     deleteTask: performDeleteTask,
     closeTaskEditModal,
     saveTaskFromModal,
@@ -311,6 +320,15 @@ function AppContent({ alertOverlay, onDismissAlert, nowPlaying, onRefreshNowPlay
     };
   }, []);
 
+  useEffect(() => {
+    const unlisten = listen<{ view: NavView }>("navigate", (event) => {
+      setActiveView(event.payload.view);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   useEffect(() => {
     if (showQuickNote && quickNoteRef.current) {
       quickNoteRef.current.focus();
@@ -515,10 +533,18 @@ function AppContent({ alertOverlay, onDismissAlert, nowPlaying, onRefreshNowPlay
         text={modalTaskText}
         description={modalTaskDescription}
         goalId={modalTaskGoalId}
+        remindTime={modalTaskRemindTime}
+        repeat={modalTaskRepeat}
+        recurring={!!taskEditModal?.templateId}
+        scope={modalEditScope}
+        error={modalTaskError}
         goals={getAllGoals()}
         onTextChange={setModalTaskText}
         onDescriptionChange={setModalTaskDescription}
         onGoalIdChange={setModalTaskGoalId}
+        onRemindTimeChange={setModalTaskRemindTime}
+        onRepeatChange={setModalTaskRepeat}
+        onScopeChange={setModalEditScope}
         onSave={saveTaskFromModal}
         onDelete={deleteTaskFromModal}
         onClose={closeTaskEditModal}
@@ -532,6 +558,8 @@ function AppContent({ alertOverlay, onDismissAlert, nowPlaying, onRefreshNowPlay
         body={alertOverlay?.body || ""}
         type={alertOverlay?.type || ""}
         onDismiss={onDismissAlert}
+        onSnooze={alertOverlay?.alert || alertOverlay?.task ? onSnoozeAlert : undefined}
+        onComplete={alertOverlay?.task ? onCompleteAlertTask : undefined}
       />
 
       <CalendarPicker
@@ -628,6 +656,8 @@ function App() {
     title: string;
     body: string;
     type: string;
+    alert?: AlertRef;
+    task?: Pick<TaskReminder, "kind" | "id">;
   } | null>(null);
   const [nowPlaying, setNowPlaying] = useState<NowPlayingInfo | null>(null);
   const stopTimerRef = useRef<((timerId?: string) => void) | null>(null);
@@ -650,12 +680,51 @@ function App() {
   }, []);
 
   const handleAlertTriggered = useCallback(
-    (alert: { type: string; title: string; body: string }) => {
-      setAlertOverlay({ show: true, ...alert });
+    (alert: { type: string; title: string; body: string; mode?: string }) => {
+      const { mode, ...overlay } = alert;
+      setAlertOverlay({
+        show: true,
+        ...overlay,
+        alert: alert.type === "stand" && (mode === "sitting" || mode === "standing") ? { type: "stand", mode } : undefined,
+      });
     },
     []
   );
 
+  useEffect(() => {
+    const unlisten = listen<{ rule: ReminderRule; due: string; late: boolean }>("reminder-fired", (event) => {
+      const { rule, late } = event.payload;
+      if (late) return;
+      setAlertOverlay({
+        show: true,
+        type: "reminder",
+        title: rule.title,
+        body: rule.body,
+        alert: { type: "reminder", ruleId: rule.id, title: rule.title, body: rule.body },
+      });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  useEffect(() => {
+    const unlisten = listen<{ reminder: TaskReminder; late: boolean }>("task-reminder", (event) => {
+      const { reminder, late } = event.payload;
+      if (late) return;
+      setAlertOverlay({
+        show: true,
+        type: "reminder",
+        title: reminder.kind === "task" ? "Task Reminder" : "Todo Reminder",
+        body: reminder.text,
+        task: { kind: reminder.kind, id: reminder.id },
+      });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const handleDataRecoveredFromBackup = useCallback(() => {
     setAlertOverlay({
       show: true,
@@ -697,8 +766,27 @@ function App() {
       stopTimerRef.current(expiredTimerIdRef.current);
       expiredTimerIdRef.current = null;
     }
+    if (alertOverlay?.alert) {
+      invoke("acknowledge_alert", { alert: alertOverlay.alert }).catch(console.error);
+    }
+    setAlertOverlay(null);
+  }, [alertOverlay?.type, alertOverlay?.alert]);
+
+  const handleSnoozeAlert = useCallback((minutes: number) => {
+    if (alertOverlay?.alert) {
+      invoke("snooze_alert", { alert: alertOverlay.alert, minutes }).catch(console.error);
+    } else if (alertOverlay?.task) {
+      invoke("postpone_task_reminder", { ...alertOverlay.task, minutes }).catch(console.error);
+    }
+    setAlertOverlay(null);
+  }, [alertOverlay?.alert, alertOverlay?.task]);
+
+  const handleCompleteAlertTask = useCallback(() => {
+    if (alertOverlay?.task) {
+      invoke("complete_task_reminder", alertOverlay.task).catch(console.error);
+    }
     setAlertOverlay(null);
-  }, [alertOverlay?.type]);
+  }, [alertOverlay?.task]);
 
   return (
     <AppDataProvider
@@ -709,6 +797,8 @@ function App() {
         <AppContent
           alertOverlay={alertOverlay}
           onDismissAlert={handleDismissAlert}
+          onSnoozeAlert={handleSnoozeAlert}
+          onCompleteAlertTask={handleCompleteAlertTask}
           nowPlaying={nowPlaying}
           onRefreshNowPlaying={handleRefreshNowPlaying}
           onTimerExpired={handleTimerExpired}
@@ -1,12 +1,18 @@
+import { useState } from "react";
+
 interface AlertOverlayProps {
   show: boolean;
   title: string;
   body: string;
   type: string;
   onDismiss: () => void;
+  onSnooze?: (minutes: number) => void;
+  onComplete?: () => void;
 }
 
-export function AlertOverlay({ show, title, body, type, onDismiss }: AlertOverlayProps) {
+export function AlertOverlay({ show, title, body, type, onDismiss, onSnooze, onComplete }: AlertOverlayProps) {
+  const [customMinutes, setCustomMinutes] = useState("");
+
   if (!show) return null;
 
   return (
@@ -26,6 +32,12 @@ export function AlertOverlay({ show, title, body, type, onDismiss }: AlertOverla
               <path d="M10.29 3.86L1.82 18a2 2 0 001.71 3h16.94a2 2 0 001.71-3L13.71 3.86a2 2 0 00-3.42 0z" />
             </svg>
           )}
+          {type === "reminder" && (
+            <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="1.5">
+              <path d="M18 8a6 6 0 10-12 0c0 7-3 9-3 9h18s-3-2-3-9" />
+              <path d="M13.73 21a2 2 0 01-3.46 0" />
+            </svg>
+          )}
           {type === "timer" && (
             <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="1.5">
               <circle cx="12" cy="13" r="8" />
@@ -36,9 +48,40 @@ export function AlertOverlay({ show, title, body, type, onDismiss }: AlertOverla
         </div>
         <h2 className="alert-title">{title}</h2>
         <p className="alert-body">{body}</p>
-        <button className="alert-dismiss" onClick={onDismiss}>
+        {onComplete && (
+          <button className="alert-dismiss" onClick={onComplete}>
+            Mark done
+          </button>
+        )}
+        <button className={onComplete ? "alert-snooze-btn alert-secondary" : "alert-dismiss"} onClick={onDismiss}>
           Got it
         </button>
+        {onSnooze && (
+          <div className="alert-snooze">
+            <button className="alert-snooze-btn" onClick={() => onSnooze(5)}>
+              Snooze 5m
+            </button>
+            <button className="alert-snooze-btn" onClick={() => onSnooze(10)}>
+              Snooze 10m
+            </button>
+            <input
+              className="alert-snooze-input"
+              type="number"
+              min="1"
+              max="1440"
+              placeholder="min"
+              value={customMinutes}
+              onChange={(e) => setCustomMinutes(e.target.value)}
+              onKeyDown={(e) => {
+                const minutes = parseInt(customMinutes);
+                if (e.key === "Enter" && minutes > 0) {
+                  setCustomMinutes("");
+                  onSnooze(minutes);
+                }
+              }}
+            />
+          </div>
+        )}
       </div>
     </div>
   );
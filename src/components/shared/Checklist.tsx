@@ -0,0 +1,91 @@
+import { useState } from "react";
+import { ChecklistItem } from "../../types";
+import { getChecklistProgress } from "../../utils/checklist";
+
+interface ChecklistProps {
+  items: ChecklistItem[];
+  onChange: (items: ChecklistItem[]) => void;
+  /** Offer an input for new steps. */
+  allowAdd?: boolean;
+}
+
+export function Checklist({ items, onChange, allowAdd = true }: ChecklistProps) {
+  const [newItemText, setNewItemText] = useState("");
+  const progress = getChecklistProgress(items);
+
+  const toggleItem = (id: string) => {
+    onChange(
+      items.map((item) =>
+        item.id === id
+          ? { ...item, completed: !item.completed, completedAt: !item.completed ? new Date().toISOString() : undefined }
+          : item
+      )
+    );
+  };
+
+  const addItem = () => {
+    const text = newItemText.trim();
+    if (!text) return;
+    onChange([...items, { id: crypto.randomUUID(), text, completed: false }]);
+    setNewItemText("");
+  };
+
+  return (
+    <div className="checklist" onClick={(e) => e.stopPropagation()}>
+      {progress && (
+        <div className="checklist-progress">
+          <div className="checklist-progress-bar">
+            <div className="checklist-progress-fill" style={{ width: `${progress.percent}%` }} />
+          </div>
+          <span>
+            {progress.done}/{progress.total} · {progress.percent}%
+          </span>
+        </div>
+      )}
+      {items.map((item) => (
+        <div key={item.id} className={`checklist-item ${item.completed ? "completed" : ""}`}>
+          <button className="checkbox" onClick={() => toggleItem(item.id)}>
+            {item.completed && (
+              <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="3">
+                <polyline points="20 6 9 17 4 12" />
+              </svg>
+            )}
+          </button>
+          <span className="checklist-item-text">{item.text}</span>
+          <button
+            className="checklist-item-remove"
+            onClick={() => onChange(items.filter((i) => i.id !== item.id))}
+            title="Remove step"
+          >
+            <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2">
+              <path d="M18 6L6 18M6 6l12 12" />
+            </svg>
+          </button>
+        </div>
+      ))}
+      {allowAdd && (
+        <input
+          type="text"
+          value={newItemText}
+          onChange={(e) => setNewItemText(e.target.value)}
+          onKeyDown={(e) => {
+            if (e.key === "Enter") addItem();
+          }}
+          className="checklist-add-input"
+          placeholder="Add a step"
+        />
+      )}
+    </div>
+  );
+}
+
+/** Steps done out of all, for task and todo cards. */
+export function ChecklistBadge({ items }: { items?: ChecklistItem[] }) {
+  const progress = getChecklistProgress(items);
+  if (!progress) return null;
+  return (
+    <span className="checklist-badge" title={`${progress.percent}% of steps done`}>
+      {progress.done}/{progress.total}
+    </span>
+  );
+}
@@ -90,7 +90,7 @@ export function MusicWidget({ nowPlaying, onRefresh }: MusicWidgetProps) {
     );
   }
 
-  const { title, artist, album, duration, artwork } = nowPlaying;
+  const { title, artist, album, duration, artwork, artwork_url } = nowPlaying;
   const progress = duration ? (currentPosition / duration) * 100 : 0;
 
   const getArtworkUrl = (base64: string): string => {
@@ -102,7 +102,7 @@ export function MusicWidget({ nowPlaying, onRefresh }: MusicWidgetProps) {
     return `data:image/jpeg;base64,${base64}`;
   };
 
-  const artworkUrl = artwork ? getArtworkUrl(artwork) : null;
+  const artworkUrl = artwork_url ?? (artwork ? getArtworkUrl(artwork) : null);
 
   return (
     <div className={`music-widget ${!isPlaying ? "paused" : ""}`}>
@@ -1,4 +1,5 @@
-import { DailyTask } from "../../types";
+import { ChecklistItem, DailyTask } from "../../types";
+import { Checklist } from "./Checklist";
 import { MarkdownText } from "./MarkdownText";
 import { FrogIcon } from "./FrogIcon";
 
@@ -8,6 +9,7 @@ interface TaskDetailModalProps {
   onClose: () => void;
   onEdit: () => void;
   onToggleComplete: () => void;
+  onChecklistChange?: (items: ChecklistItem[]) => void;
   onStartTimer?: () => void;
   onDelete: () => void;
 }
@@ -18,6 +20,7 @@ export function TaskDetailModal({
   onClose,
   onEdit,
   onToggleComplete,
+  onChecklistChange,
   onStartTimer,
   onDelete,
 }: TaskDetailModalProps) {
@@ -61,6 +64,10 @@ export function TaskDetailModal({
             <p className="task-detail-no-description">No description</p>
           )}
 
+          {onChecklistChange ? (
+            <Checklist items={task.checklist || []} onChange={onChecklistChange} />
+          ) : null}
+
           <div className="task-detail-meta">
             {goalInfo && (
               <div className="task-detail-meta-item">
@@ -1,3 +1,6 @@
+import { EditScope } from "../../hooks/useTasks";
+import { RepeatKind, TaskRepeat } from "../../utils/recurrence";
+
 interface Goal {
   id: string;
   text: string;
@@ -10,10 +13,19 @@ interface TaskEditModalProps {
   text: string;
   description: string;
   goalId: string | null;
+  remindTime: string;
+  repeat: TaskRepeat;
+  /** Whether the task is an occurrence of a recurring task. */
+  recurring: boolean;
+  scope: EditScope;
+  error: string | null;
   goals: Goal[];
   onTextChange: (value: string) => void;
   onDescriptionChange: (value: string) => void;
   onGoalIdChange: (value: string | null) => void;
+  onRemindTimeChange: (value: string) => void;
+  onRepeatChange: (value: TaskRepeat) => void;
+  onScopeChange: (value: EditScope) => void;
   onSave: () => void;
   onDelete: () => void;
   onClose: () => void;
@@ -25,10 +37,18 @@ export function TaskEditModal({
   text,
   description,
   goalId,
+  remindTime,
+  repeat,
+  recurring,
+  scope,
+  error,
   goals,
   onTextChange,
   onDescriptionChange,
   onGoalIdChange,
+  onRemindTimeChange,
+  onRepeatChange,
+  onScopeChange,
   onSave,
   onDelete,
   onClose,
@@ -77,6 +97,81 @@ export function TaskEditModal({
               </option>
             ))}
           </select>
+          <label className="task-remind-field">
+            <span>Remind me at</span>
+            <input
+              type="time"
+              value={remindTime}
+              onChange={(e) => onRemindTimeChange(e.target.value)}
+              className="task-remind-input"
+            />
+          </label>
+          {recurring && (
+            <label className="task-remind-field">
+              <span>Apply changes to</span>
+              <select
+                value={scope}
+                onChange={(e) => onScopeChange(e.target.value as EditScope)}
+                className="task-remind-input"
+              >
+                <option value="occurrence">This task only</option>
+                <option value="series">This and upcoming tasks</option>
+              </select>
+            </label>
+          )}
+          <label className="task-remind-field">
+            <span>Repeat</span>
+            <select
+              value={repeat.kind}
+              onChange={(e) => onRepeatChange({ ...repeat, kind: e.target.value as RepeatKind })}
+              className="task-remind-input"
+              disabled={recurring && scope === "occurrence"}
+            >
+              <option value="never">Never</option>
+              <option value="daily">Every day</option>
+              <option value="weekdays">Every weekday</option>
+              <option value="weekly">Every week</option>
+              <option value="monthly">Every month</option>
+              <option value="afterCompletion">Some days after done</option>
+              <option value="custom">Custom rule</option>
+            </select>
+          </label>
+          {repeat.kind === "afterCompletion" && (
+            <label className="task-remind-field">
+              <span>Days after done</span>
+              <input
+                type="number"
+                min={1}
+                max={365}
+                value={repeat.days}
+                onChange={(e) => onRepeatChange({ ...repeat, days: Number(e.target.value) })}
+                className="task-remind-input"
+                disabled={recurring && scope === "occurrence"}
+              />
+            </label>
+          )}
+          {repeat.kind === "custom" && (
+            <>
+              <input
+                type="text"
+                value={repeat.rule}
+                onChange={(e) => onRepeatChange({ ...repeat, rule: e.target.value })}
+                className="task-text-input"
+                placeholder="FREQ=WEEKLY;BYDAY=MO or 0 0 * * 1-5"
+                disabled={recurring && scope === "occurrence"}
+              />
+              <label className="task-remind-field">
+                <span>Skip weekends</span>
+                <input
+                  type="checkbox"
+                  checked={repeat.skipWeekends}
+                  onChange={(e) => onRepeatChange({ ...repeat, skipWeekends: e.target.checked })}
+                  disabled={recurring && scope === "occurrence"}
+                />
+              </label>
+            </>
+          )}
+          {error && <div className="task-edit-error">{error}</div>}
         </div>
         <div className="task-edit-modal-actions">
           <button className="btn-delete" onClick={onDelete}>
@@ -1,13 +1,15 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { useAppData } from "../../context/AppDataContext";
 import { useNotificationPermission } from "../../hooks/useNotificationPermission";
-import { NotificationSettings } from "../../types";
+import { CarryForwardPolicy, DailyTask, FocusMusicRule, NotificationSettings, Timezones } from "../../types";
 import { TimePickerModal } from "../shared/TimePickerModal";
 import { DayTimePickerModal } from "../shared/DayTimePickerModal";
 
 export function SettingsView() {
   const [showDailyTimePicker, setShowDailyTimePicker] = useState(false);
   const [showWeeklyPicker, setShowWeeklyPicker] = useState(false);
+  const [timezones, setTimezones] = useState<Timezones | null>(null);
   const { data, saveData } = useAppData();
   const {
     notificationPermission,
@@ -16,6 +18,10 @@ export function SettingsView() {
     toggleLaunchAtLogin,
   } = useNotificationPermission();
 
+  useEffect(() => {
+    invoke<Timezones>("get_timezones").then(setTimezones).catch(console.error);
+  }, []);
+
   const updateNotifications = async (updates: Partial<NotificationSettings>) => {
     if (!data) return;
     const newData = {
@@ -25,6 +31,42 @@ export function SettingsView() {
     await saveData(newData);
   };
 
+  const focusMusicRule = (timerType: FocusMusicRule["timerType"]): FocusMusicRule =>
+    data?.focusMusic?.find((rule) => rule.timerType === timerType) ?? {
+      timerType,
+      enabled: false,
+      pauseOnEnd: true,
+    };
+
+  const updateFocusMusic = async (timerType: FocusMusicRule["timerType"], updates: Partial<FocusMusicRule>) => {
+    if (!data) return;
+    const rule = { ...focusMusicRule(timerType), ...updates };
+    const others = (data.focusMusic || []).filter((r) => r.timerType !== timerType);
+    await saveData({ ...data, focusMusic: [...others, rule] });
+  };
+
+  const focusMusicTimers: { timerType: FocusMusicRule["timerType"]; name: string }[] = [
+    { timerType: "focus", name: "Focus timers" },
+    { timerType: "task", name: "Task timeboxes" },
+    { timerType: "pomodoro", name: "Pomodoros" },
+  ];
+
+  const carryForward = data?.carryForward ?? { policy: "none" as CarryForwardPolicy };
+
+  const toggleCarryForwardCategory = async (category: DailyTask["category"]) => {
+    if (!data) return;
+    const categories = carryForward.categories || [];
+    await saveData({
+      ...data,
+      carryForward: {
+        ...carryForward,
+        categories: categories.includes(category)
+          ? categories.filter((c) => c !== category)
+          : [...categories, category],
+      },
+    });
+  };
+
   const formatTime12Hour = (time: string) => {
     const [hours, minutes] = time.split(":").map(Number);
     const ampm = hours >= 12 ? "PM" : "AM";
@@ -214,6 +256,28 @@ export function SettingsView() {
         )}
       </div>
 
+      <div className="settings-group">
+        <h2>Time Zone</h2>
+        <div className="setting-item">
+          <div className="setting-info">
+            <span className="setting-name">Time zone</span>
+            <span className="setting-desc">Reminders and "today" follow this zone</span>
+          </div>
+          <select
+            className="timezone-select"
+            value={data.timezone || ""}
+            onChange={(e) => saveData({ ...data, timezone: e.target.value || undefined })}
+          >
+            <option value="">System{timezones ? ` (${timezones.system})` : ""}</option>
+            {timezones?.zones.map((zone) => (
+              <option key={zone} value={zone}>
+                {zone}
+              </option>
+            ))}
+          </select>
+        </div>
+      </div>
+
       <div className="settings-group">
         <h2>Startup</h2>
         <div className="setting-item">
@@ -257,8 +321,8 @@ export function SettingsView() {
         <h2>Features</h2>
         <div className="setting-item">
           <div className="setting-info">
-            <span className="setting-name">Apple Music integration</span>
-            <span className="setting-desc">Show now playing bar below navigation</span>
+            <span className="setting-name">Music integration</span>
+            <span className="setting-desc">Show now playing from Apple Music, or any MPRIS player on Linux</span>
           </div>
           <button
             className={`toggle ${data.appleMusicEnabled !== false ? "on" : ""}`}
@@ -267,6 +331,47 @@ export function SettingsView() {
             <span className="toggle-knob" />
           </button>
         </div>
+        {data.appleMusicEnabled !== false &&
+          focusMusicTimers.map(({ timerType, name }) => {
+            const rule = focusMusicRule(timerType);
+            return (
+              <div key={timerType}>
+                <div className="setting-item">
+                  <div className="setting-info">
+                    <span className="setting-name">Music with {name.toLowerCase()}</span>
+                    <span className="setting-desc">
+                      Start music with the timer, pause it when the timer ends or a break starts
+                    </span>
+                  </div>
+                  <button
+                    className={`toggle ${rule.enabled ? "on" : ""}`}
+                    onClick={() => updateFocusMusic(timerType, { enabled: !rule.enabled })}
+                  >
+                    <span className="toggle-knob" />
+                  </button>
+                </div>
+                {rule.enabled && (
+                  <div className="setting-item">
+                    <div className="setting-info">
+                      <span className="setting-name">Playlist</span>
+                      <span className="setting-desc">Leave empty to play what's queued</span>
+                    </div>
+                    <input
+                      className="playlist-input"
+                      placeholder="Playlist or station"
+                      defaultValue={rule.playlist || ""}
+                      onBlur={(e) => {
+                        const playlist = e.target.value.trim();
+                        if (playlist !== (rule.playlist || "")) {
+                          updateFocusMusic(timerType, { playlist: playlist || undefined });
+                        }
+                      }}
+                    />
+                  </div>
+                )}
+              </div>
+            );
+          })}
         <div className="setting-item">
           <div className="setting-info">
             <span className="setting-name">Eat the Frog</span>
@@ -279,6 +384,41 @@ export function SettingsView() {
             <span className="toggle-knob" />
           </button>
         </div>
+        <div className="setting-item">
+          <div className="setting-info">
+            <span className="setting-name">Carry forward</span>
+            <span className="setting-desc">Move unfinished tasks to the new day at midnight</span>
+          </div>
+          <select
+            className="timezone-select"
+            value={carryForward.policy}
+            onChange={(e) =>
+              saveData({
+                ...data,
+                carryForward: { ...carryForward, policy: e.target.value as CarryForwardPolicy },
+              })
+            }
+          >
+            <option value="none">Off</option>
+            <option value="all">All tasks</option>
+            <option value="frogOnly">Frog only</option>
+            <option value="categories">By category</option>
+          </select>
+        </div>
+        {carryForward.policy === "categories" &&
+          (["work", "personal"] as const).map((category) => (
+            <div key={category} className="setting-item">
+              <div className="setting-info">
+                <span className="setting-name">{category === "work" ? "Work tasks" : "Personal tasks"}</span>
+              </div>
+              <button
+                className={`toggle ${carryForward.categories?.includes(category) ? "on" : ""}`}
+                onClick={() => toggleCarryForwardCategory(category)}
+              >
+                <span className="toggle-knob" />
+              </button>
+            </div>
+          ))}
       </div>
     </div>
   );
@@ -12,6 +12,7 @@ import { FrogIcon } from "../shared/FrogIcon";
 import { CategoryToggle } from "../shared/CategoryToggle";
 import { MarkdownText } from "../shared/MarkdownText";
 import { TaskDetailModal } from "../shared/TaskDetailModal";
+import { Checklist, ChecklistBadge } from "../shared/Checklist";
 
 interface TasksViewProps {
   selectedDate: string;
@@ -64,6 +65,7 @@ export function TasksView({ selectedDate, onOpenDatePicker, onOpenSchedulePicker
     getIncompleteTasksBeforeDate,
     addTask,
     toggleTaskComplete,
+    updateTaskChecklist,
     deleteTask: performDeleteTask,
     startEditingTask,
     cancelEditingTask,
@@ -84,6 +86,8 @@ export function TasksView({ selectedDate, onOpenDatePicker, onOpenSchedulePicker
     setBacklogDescription,
     backlogGoalId,
     setBacklogGoalId,
+    backlogRemindAt,
+    setBacklogRemindAt,
     editingTodoId,
     editTodoText,
     setEditTodoText,
@@ -91,11 +95,14 @@ export function TasksView({ selectedDate, onOpenDatePicker, onOpenSchedulePicker
     setEditTodoDescription,
     editTodoGoalId,
     setEditTodoGoalId,
+    editTodoRemindAt,
+    setEditTodoRemindAt,
     addTodo,
     deleteTodo: performDeleteTodo,
     startEditingTodo,
     cancelEditingTodo,
     updateTodo,
+    updateTodoChecklist,
     deferTaskToBacklog,
   } = useTodos();
 
@@ -423,6 +430,7 @@ export function TasksView({ selectedDate, onOpenDatePicker, onOpenSchedulePicker
                         <span className={`task-category-badge ${task.category || "work"}`}>
                           {task.category === "personal" ? "Personal" : "Work"}
                         </span>
+                        <ChecklistBadge items={task.checklist} />
                       </div>
                       {isMoved && (
                         <span className="task-moved-tag">
@@ -533,6 +541,7 @@ export function TasksView({ selectedDate, onOpenDatePicker, onOpenSchedulePicker
                     setBacklogText("");
                     setBacklogDescription("");
                     setBacklogGoalId(null);
+                    setBacklogRemindAt("");
                   }
                 }}
               />
@@ -554,6 +563,15 @@ export function TasksView({ selectedDate, onOpenDatePicker, onOpenSchedulePicker
                   </option>
                 ))}
               </select>
+              <label className="task-remind-field">
+                <span>Remind me at</span>
+                <input
+                  type="datetime-local"
+                  value={backlogRemindAt}
+                  onChange={(e) => setBacklogRemindAt(e.target.value)}
+                  className="task-remind-input"
+                />
+              </label>
               <div className="task-form-actions">
                 <button className="btn-save" onClick={addTodo} disabled={!backlogText.trim()}>
                   Add to Backlog
@@ -565,6 +583,7 @@ export function TasksView({ selectedDate, onOpenDatePicker, onOpenSchedulePicker
                     setBacklogText("");
                     setBacklogDescription("");
                     setBacklogGoalId(null);
+                    setBacklogRemindAt("");
                   }}
                 >
                   Cancel
@@ -603,6 +622,10 @@ export function TasksView({ selectedDate, onOpenDatePicker, onOpenSchedulePicker
                           onChange={(e) => setEditTodoDescription(e.target.value)}
                           className="task-description-input"
                         />
+                        <Checklist
+                          items={todo.checklist || []}
+                          onChange={(checklist) => updateTodoChecklist(todo.id, checklist)}
+                        />
                         <select
                           value={editTodoGoalId || ""}
                           onChange={(e) => setEditTodoGoalId(e.target.value || null)}
@@ -615,6 +638,15 @@ export function TasksView({ selectedDate, onOpenDatePicker, onOpenSchedulePicker
                             </option>
                           ))}
                         </select>
+                        <label className="task-remind-field">
+                          <span>Remind me at</span>
+                          <input
+                            type="datetime-local"
+                            value={editTodoRemindAt}
+                            onChange={(e) => setEditTodoRemindAt(e.target.value)}
+                            className="task-remind-input"
+                          />
+                        </label>
                         <div className="task-form-actions">
                           <button className="btn-save" onClick={updateTodo} disabled={!editTodoText.trim()}>
                             Save
@@ -638,6 +670,13 @@ export function TasksView({ selectedDate, onOpenDatePicker, onOpenSchedulePicker
                         <div className="backlog-item-content">
                           <p className="backlog-item-text">{todo.text}</p>
                           {todo.description && <p className="backlog-item-description"><MarkdownText text={todo.description} /></p>}
+                          {todo.checklist?.length ? (
+                            <Checklist
+                              items={todo.checklist}
+                              onChange={(checklist) => updateTodoChecklist(todo.id, checklist)}
+                              allowAdd={false}
+                            />
+                          ) : null}
                           {todo.lastScheduledDate && (
                             <span className="task-scheduled-tag">
                               <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="1.5">
@@ -696,6 +735,10 @@ export function TasksView({ selectedDate, onOpenDatePicker, onOpenSchedulePicker
             toggleTaskComplete(selectedTask.id);
             setSelectedTask(null);
           }}
+          onChecklistChange={(checklist) => {
+            updateTaskChecklist(selectedTask.id, checklist);
+            setSelectedTask({ ...selectedTask, checklist: checklist.length ? checklist : undefined });
+          }}
           onStartTimer={onStartTaskTimer && isToday(selectedDate) ? () => {
             onStartTaskTimer(selectedTask.id, selectedTask.text);
             setSelectedTask(null);
@@ -11,6 +11,7 @@ import { FrogIcon } from "../shared/FrogIcon";
 import { CategoryToggle } from "../shared/CategoryToggle";
 import { MarkdownText } from "../shared/MarkdownText";
 import { TaskDetailModal } from "../shared/TaskDetailModal";
+import { ChecklistBadge } from "../shared/Checklist";
 import { MusicWidget } from "../shared/MusicWidget";
 
 interface TodayViewProps {
@@ -74,6 +75,7 @@ export function TodayView({ currentTime, onNavigate, onStartTaskTimer, todayRevi
     getFrogForDate,
     addTask,
     toggleTaskComplete,
+    updateTaskChecklist,
     deleteTask: performDeleteTask,
     startEditingTask,
     cancelEditingTask,
@@ -451,6 +453,7 @@ export function TodayView({ currentTime, onNavigate, onStartTaskTimer, todayRevi
                           <span className={`task-category-badge ${task.category || "work"}`}>
                             {task.category === "personal" ? "Personal" : "Work"}
                           </span>
+                          <ChecklistBadge items={task.checklist} />
                         </div>
                       </div>
                     </>
@@ -477,6 +480,10 @@ export function TodayView({ currentTime, onNavigate, onStartTaskTimer, todayRevi
             toggleTaskComplete(selectedTask.id);
             setSelectedTask(null);
           }}
+          onChecklistChange={(checklist) => {
+            updateTaskChecklist(selectedTask.id, checklist);
+            setSelectedTask({ ...selectedTask, checklist: checklist.length ? checklist : undefined });
+          }}
           onStartTimer={onStartTaskTimer ? () => {
             onStartTaskTimer(selectedTask.id, selectedTask.text);
             setSelectedTask(null);
@@ -2,6 +2,7 @@ import { createContext, useContext, useState, useEffect, ReactNode } from "react
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { AppData } from "../types";
+import { setTimeZone } from "../utils/dateUtils";
 
 interface AppDataContextValue {
   data: AppData | null;
@@ -15,7 +16,7 @@ interface AppDataProviderProps {
   children: ReactNode;
   onDataLoad?: (data: AppData) => void;
   onSystemWake?: () => void;
-  onAlertTriggered?: (alert: { type: string; title: string; body: string }) => void;
+  onAlertTriggered?: (alert: { type: string; title: string; body: string; mode?: string }) => void;
   onDataRecoveredFromBackup?: () => void;
 }
 
@@ -41,11 +42,13 @@ export function AppDataProvider({
       normalizedData.activeTimer = undefined;
     }
 
+    setTimeZone(normalizedData.timezone);
     setData(normalizedData);
     return normalizedData;
   };
 
   const saveData = async (newData: AppData) => {
+    setTimeZone(newData.timezone);
     setData(newData);
     await invoke("save_data", { data: newData });
   };
@@ -74,7 +77,7 @@ export function AppDataProvider({
   }, [onSystemWake]);
 
   useEffect(() => {
-    const unlisten = listen<{ type: string; title: string; body: string }>(
+    const unlisten = listen<{ type: string; title: string; body: string; mode?: string }>(
       "alert-triggered",
       async (event) => {
         await loadData();
@@ -86,6 +89,15 @@ export function AppDataProvider({
     };
   }, [onAlertTriggered]);
 
+  useEffect(() => {
+    const unlisten = listen("data-changed", async () => {
+      await loadData();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   useEffect(() => {
     const unlisten = listen("data-recovered-from-backup", () => {
       console.log("Data recovered from backup");
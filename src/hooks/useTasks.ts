@@ -1,7 +1,13 @@
 import { useState, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { useAppData } from "../context/AppDataContext";
-import { DailyTask, TaskCategory } from "../types";
-import { getTodayDate } from "../utils/dateUtils";
+import { ChecklistItem, DailyTask, TaskCategory, TaskTemplate } from "../types";
+import { getUnfinishedItems } from "../utils/checklist";
+import { getReminderTime, getTodayDate } from "../utils/dateUtils";
+import { NO_REPEAT, TaskRepeat, repeatFromTemplate, repeatToTemplate } from "../utils/recurrence";
+
+/** Whether an edit to a recurring task applies to this occurrence or the whole series. */
+export type EditScope = "occurrence" | "series";
 
 export function useTasks() {
   const { data, saveData } = useAppData();
@@ -24,6 +30,10 @@ export function useTasks() {
   const [modalTaskText, setModalTaskText] = useState("");
   const [modalTaskDescription, setModalTaskDescription] = useState("");
   const [modalTaskGoalId, setModalTaskGoalId] = useState<string | null>(null);
+  const [modalTaskRemindTime, setModalTaskRemindTime] = useState("");
+  const [modalTaskRepeat, setModalTaskRepeat] = useState<TaskRepeat>(NO_REPEAT);
+  const [modalEditScope, setModalEditScope] = useState<EditScope>("occurrence");
+  const [modalTaskError, setModalTaskError] = useState<string | null>(null);
 
   const sortTasks = (tasks: DailyTask[]) => {
     return tasks.sort((a, b) => {
@@ -130,6 +140,20 @@ export function useTasks() {
     [data, saveData]
   );
 
+  const updateTaskChecklist = useCallback(
+    async (taskId: string, checklist: ChecklistItem[]) => {
+      if (!data) return;
+      const newData = {
+        ...data,
+        dailyTasks: (data.dailyTasks || []).map((task) =>
+          task.id === taskId ? { ...task, checklist: checklist.length ? checklist : undefined } : task
+        ),
+      };
+      await saveData(newData);
+    },
+    [data, saveData]
+  );
+
   const deleteTask = useCallback(
     async (taskId: string) => {
       if (!data) return;
@@ -189,20 +213,56 @@ export function useTasks() {
     cancelEditingTask();
   }, [data, saveData, editingTaskId, editTaskText, editTaskDescription, editTaskGoalId, editTaskIsFrog, editTaskCategory, cancelEditingTask]);
 
+  const getTaskTemplate = useCallback(
+    (task: DailyTask) => data?.taskTemplates?.find((template) => template.id === task.templateId),
+    [data]
+  );
+
   const openTaskEditModal = useCallback((task: DailyTask) => {
     setTaskEditModal(task);
     setModalTaskText(task.text);
     setModalTaskDescription(task.description);
     setModalTaskGoalId(task.goalId || null);
-  }, []);
+    setModalTaskRemindTime(task.remindAt ? getReminderTime(task.remindAt) : "");
+    setModalTaskRepeat(repeatFromTemplate(getTaskTemplate(task)));
+    setModalEditScope("occurrence");
+    setModalTaskError(null);
+  }, [getTaskTemplate]);
 
   const closeTaskEditModal = useCallback(() => {
     setTaskEditModal(null);
     setModalTaskText("");
     setModalTaskDescription("");
     setModalTaskGoalId(null);
+    setModalTaskRemindTime("");
+    setModalTaskRepeat(NO_REPEAT);
+    setModalEditScope("occurrence");
+    setModalTaskError(null);
   }, []);
 
+  /** Starts, changes or ends the series after the task itself was saved. */
+  const saveTaskSeries = useCallback(
+    async (task: DailyTask) => {
+      const template = getTaskTemplate(task);
+      if (template && modalEditScope !== "series") return;
+      if (!template && modalTaskRepeat.kind === "never") return;
+      if (template && modalTaskRepeat.kind === "never") {
+        await invoke("delete_task_template", { id: template.id, keepTaskId: task.id });
+        return;
+      }
+      const updated: TaskTemplate = {
+        ...(template ?? { id: "", category: task.category || "work", timeboxMinutes: task.timeboxMinutes, startDate: task.date }),
+        text: modalTaskText.trim(),
+        description: modalTaskDescription.trim(),
+        goalId: modalTaskGoalId || undefined,
+        remindTime: modalTaskRemindTime || undefined,
+        ...repeatToTemplate(modalTaskRepeat),
+      };
+      await invoke("save_task_template", { template: updated, taskId: template ? null : task.id });
+    },
+    [getTaskTemplate, modalEditScope, modalTaskRepeat, modalTaskText, modalTaskDescription, modalTaskGoalId, modalTaskRemindTime]
+  );
+
   const saveTaskFromModal = useCallback(async () => {
     if (!data || !taskEditModal || !modalTaskText.trim()) return;
     const newData = {
@@ -214,13 +274,20 @@ export function useTasks() {
               text: modalTaskText.trim(),
               description: modalTaskDescription.trim(),
               goalId: modalTaskGoalId || undefined,
+              remindAt: modalTaskRemindTime ? `${task.date}T${modalTaskRemindTime}` : undefined,
             }
           : task
       ),
     };
     await saveData(newData);
+    try {
+      await saveTaskSeries(taskEditModal);
+    } catch (error) {
+      setModalTaskError(String(error));
+      return;
+    }
     closeTaskEditModal();
-  }, [data, saveData, taskEditModal, modalTaskText, modalTaskDescription, modalTaskGoalId, closeTaskEditModal]);
+  }, [data, saveData, taskEditModal, modalTaskText, modalTaskDescription, modalTaskGoalId, modalTaskRemindTime, saveTaskSeries, closeTaskEditModal]);
 
   const carryForwardTask = useCallback(
     async (task: DailyTask) => {
@@ -235,6 +302,10 @@ export function useTasks() {
         date: today,
         order: 0,
         category: task.category || "work",
+        remindAt: task.remindAt,
+        templateId: task.templateId,
+        occurrence: task.occurrence,
+        checklist: getUnfinishedItems(task.checklist),
       };
       const updatedTasks = (data.dailyTasks || []).map((t) => {
         if (t.id === task.id) return { ...t, movedToDate: today };
@@ -336,6 +407,14 @@ export function useTasks() {
     setModalTaskDescription,
     modalTaskGoalId,
     setModalTaskGoalId,
+    modalTaskRemindTime,
+    setModalTaskRemindTime,
+    modalTaskRepeat,
+    setModalTaskRepeat,
+    modalEditScope,
+    setModalEditScope,
+    modalTaskError,
+    getTaskTemplate,
     getTodayTasks,
     getTasksForDate,
     getFrogForDate,
@@ -343,6 +422,7 @@ export function useTasks() {
     getIncompleteTasksBeforeDate,
     addTask,
     toggleTaskComplete,
+    updateTaskChecklist,
     deleteTask,
     startEditingTask,
     cancelEditingTask,
@@ -1,4 +1,6 @@
 import { useState, useCallback, useEffect, useRef } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { useAppData } from "../context/AppDataContext";
 import { ActiveTimer } from "../types";
 
@@ -13,28 +15,39 @@ interface UseTimerOptions {
 }
 
 export function useTimer(options?: UseTimerOptions) {
-  const { data, saveData } = useAppData();
+  const { data } = useAppData();
   const [timerStates, setTimerStates] = useState<Map<string, TimerState>>(new Map());
   const intervalRef = useRef<number | null>(null);
-  const expiredTimersRef = useRef<Set<string>>(new Set());
   const onExpiredRef = useRef(options?.onExpired);
 
   onExpiredRef.current = options?.onExpired;
 
   const activeTimers = data?.activeTimers;
 
-  const calculateTimeRemaining = useCallback((endTime: string) => {
-    const end = new Date(endTime).getTime();
+  const calculateTimeRemaining = useCallback((timer: ActiveTimer) => {
+    if (timer.pausedAt) {
+      return Math.max(0, timer.remainingSeconds ?? 0);
+    }
+    const end = new Date(timer.endTime).getTime();
     const now = Date.now();
     return Math.max(0, Math.floor((end - now) / 1000));
   }, []);
 
+  // Expiry is detected by the backend so it fires even while the window is hidden.
+  useEffect(() => {
+    const unlisten = listen<ActiveTimer>("timer-expired", (event) => {
+      onExpiredRef.current?.(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   useEffect(() => {
     const timers = activeTimers || [];
 
     if (timers.length === 0) {
       setTimerStates(new Map());
-      expiredTimersRef.current = new Set();
       if (intervalRef.current) {
         clearInterval(intervalRef.current);
         intervalRef.current = null;
@@ -45,14 +58,9 @@ export function useTimer(options?: UseTimerOptions) {
     const updateTimerStates = () => {
       const newStates = new Map<string, TimerState>();
       for (const timer of timers) {
-        const remaining = calculateTimeRemaining(timer.endTime);
-        const isExpired = remaining <= 0;
+        const remaining = calculateTimeRemaining(timer);
+        const isExpired = !!timer.expiredAt || (!timer.pausedAt && remaining <= 0);
         newStates.set(timer.id, { timer, timeRemaining: remaining, isExpired });
-
-        if (isExpired && !expiredTimersRef.current.has(timer.id)) {
-          expiredTimersRef.current.add(timer.id);
-          onExpiredRef.current?.(timer);
-        }
       }
       setTimerStates(newStates);
     };
@@ -75,45 +83,30 @@ export function useTimer(options?: UseTimerOptions) {
 
   const startTimer = useCallback(
     async (durationMinutes: number, type: "focus" | "task", taskId?: string, taskName?: string) => {
-      if (!data) return;
-      const endTime = new Date(Date.now() + durationMinutes * 60 * 1000).toISOString();
-      const newTimer: ActiveTimer = {
-        id: crypto.randomUUID(),
-        type,
-        taskId,
-        taskName,
-        endTime,
-        durationMinutes,
-      };
-      const existingTimers = data.activeTimers || [];
-      const newData = {
-        ...data,
-        activeTimers: [...existingTimers, newTimer],
-        activeTimer: undefined,
-      };
-      await saveData(newData);
+      await invoke("start_timer", {
+        minutes: durationMinutes,
+        taskId: type === "task" ? taskId : undefined,
+        name: type === "focus" ? taskName : undefined,
+      });
     },
-    [data, saveData]
+    []
   );
 
   const stopTimer = useCallback(async (timerId?: string) => {
-    if (!data) return;
-    const existingTimers = data.activeTimers || [];
-
-    let newTimers: ActiveTimer[];
-    if (timerId) {
-      newTimers = existingTimers.filter((t) => t.id !== timerId);
-    } else {
-      newTimers = [];
-    }
+    await invoke("cancel_timer", { id: timerId });
+  }, []);
 
-    const newData = {
-      ...data,
-      activeTimers: newTimers,
-      activeTimer: undefined,
-    };
-    await saveData(newData);
-  }, [data, saveData]);
+  const pauseTimer = useCallback(async (timerId: string) => {
+    await invoke("pause_timer", { id: timerId });
+  }, []);
+
+  const resumeTimer = useCallback(async (timerId: string) => {
+    await invoke("resume_timer", { id: timerId });
+  }, []);
+
+  const extendTimer = useCallback(async (timerId: string, minutes: number) => {
+    await invoke("extend_timer", { id: timerId, minutes });
+  }, []);
 
   const formatTime = useCallback((seconds: number) => {
     const mins = Math.floor(seconds / 60);
@@ -126,7 +119,7 @@ export function useTimer(options?: UseTimerOptions) {
   }, [timerStates]);
 
   const getFocusTimers = useCallback(() => {
-    return (activeTimers || []).filter((t) => t.type === "focus");
+    return (activeTimers || []).filter((t) => t.type === "focus" || t.type === "pomodoro");
   }, [activeTimers]);
 
   const getTaskTimer = useCallback((taskId: string): ActiveTimer | undefined => {
@@ -138,6 +131,9 @@ export function useTimer(options?: UseTimerOptions) {
     timerStates,
     startTimer,
     stopTimer,
+    pauseTimer,
+    resumeTimer,
+    extendTimer,
     formatTime,
     getTimerState,
     getFocusTimers,
@@ -1,6 +1,7 @@
 import { useState, useCallback } from "react";
 import { useAppData } from "../context/AppDataContext";
-import { Todo, DailyTask } from "../types";
+import { ChecklistItem, Todo, DailyTask } from "../types";
+import { getReminderDateTime } from "../utils/dateUtils";
 
 export function useTodos() {
   const { data, saveData } = useAppData();
@@ -9,11 +10,13 @@ export function useTodos() {
   const [backlogText, setBacklogText] = useState("");
   const [backlogDescription, setBacklogDescription] = useState("");
   const [backlogGoalId, setBacklogGoalId] = useState<string | null>(null);
+  const [backlogRemindAt, setBacklogRemindAt] = useState("");
 
   const [editingTodoId, setEditingTodoId] = useState<string | null>(null);
   const [editTodoText, setEditTodoText] = useState("");
   const [editTodoDescription, setEditTodoDescription] = useState("");
   const [editTodoGoalId, setEditTodoGoalId] = useState<string | null>(null);
+  const [editTodoRemindAt, setEditTodoRemindAt] = useState("");
 
   const [pendingScheduleTodo, setPendingScheduleTodo] = useState<Todo | null>(null);
 
@@ -25,6 +28,7 @@ export function useTodos() {
       description: backlogDescription.trim(),
       goalId: backlogGoalId || undefined,
       createdAt: new Date().toISOString(),
+      remindAt: backlogRemindAt || undefined,
     };
     const newData = {
       ...data,
@@ -34,8 +38,9 @@ export function useTodos() {
     setBacklogText("");
     setBacklogDescription("");
     setBacklogGoalId(null);
+    setBacklogRemindAt("");
     setShowBacklogForm(false);
-  }, [data, saveData, backlogText, backlogDescription, backlogGoalId]);
+  }, [data, saveData, backlogText, backlogDescription, backlogGoalId, backlogRemindAt]);
 
   const deleteTodo = useCallback(
     async (todoId: string) => {
@@ -54,6 +59,7 @@ export function useTodos() {
     setEditTodoText(todo.text);
     setEditTodoDescription(todo.description);
     setEditTodoGoalId(todo.goalId || null);
+    setEditTodoRemindAt(todo.remindAt ? getReminderDateTime(todo.remindAt) : "");
   }, []);
 
   const cancelEditingTodo = useCallback(() => {
@@ -61,6 +67,7 @@ export function useTodos() {
     setEditTodoText("");
     setEditTodoDescription("");
     setEditTodoGoalId(null);
+    setEditTodoRemindAt("");
   }, []);
 
   const updateTodo = useCallback(async () => {
@@ -74,13 +81,14 @@ export function useTodos() {
               text: editTodoText.trim(),
               description: editTodoDescription.trim(),
               goalId: editTodoGoalId || undefined,
+              remindAt: editTodoRemindAt || undefined,
             }
           : todo
       ),
     };
     await saveData(newData);
     cancelEditingTodo();
-  }, [data, saveData, editingTodoId, editTodoText, editTodoDescription, editTodoGoalId, cancelEditingTodo]);
+  }, [data, saveData, editingTodoId, editTodoText, editTodoDescription, editTodoGoalId, editTodoRemindAt, cancelEditingTodo]);
 
   const scheduleTodo = useCallback(
     async (todo: Todo, date: string) => {
@@ -93,6 +101,8 @@ export function useTodos() {
         completed: false,
         date: date,
         category: "work",
+        remindAt: todo.remindAt,
+        checklist: todo.checklist,
       };
       const newData = {
         ...data,
@@ -105,6 +115,20 @@ export function useTodos() {
     [data, saveData]
   );
 
+  const updateTodoChecklist = useCallback(
+    async (todoId: string, checklist: ChecklistItem[]) => {
+      if (!data) return;
+      const newData = {
+        ...data,
+        todos: (data.todos || []).map((todo) =>
+          todo.id === todoId ? { ...todo, checklist: checklist.length ? checklist : undefined } : todo
+        ),
+      };
+      await saveData(newData);
+    },
+    [data, saveData]
+  );
+
   const deferTaskToBacklog = useCallback(
     async (task: DailyTask) => {
       if (!data) return;
@@ -115,6 +139,8 @@ export function useTodos() {
         goalId: task.goalId,
         lastScheduledDate: task.date,
         createdAt: new Date().toISOString(),
+        remindAt: task.remindAt,
+        checklist: task.checklist,
       };
       const newData = {
         ...data,
@@ -135,6 +161,8 @@ export function useTodos() {
     setBacklogDescription,
     backlogGoalId,
     setBacklogGoalId,
+    backlogRemindAt,
+    setBacklogRemindAt,
     editingTodoId,
     editTodoText,
     setEditTodoText,
@@ -142,6 +170,8 @@ export function useTodos() {
     setEditTodoDescription,
     editTodoGoalId,
     setEditTodoGoalId,
+    editTodoRemindAt,
+    setEditTodoRemindAt,
     pendingScheduleTodo,
     setPendingScheduleTodo,
     addTodo,
@@ -150,6 +180,7 @@ export function useTodos() {
     cancelEditingTodo,
     updateTodo,
     scheduleTodo,
+    updateTodoChecklist,
     deferTaskToBacklog,
   };
 }
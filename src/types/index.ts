@@ -25,6 +25,39 @@ export interface NotificationSettings {
   stand_duration_minutes?: number;
   stand_mode?: string;
   stand_mode_changed_at?: string;
+  stand_idle_minutes?: number;
+  stand_idle_action?: "reset" | "pause";
+  reminders?: ReminderRule[];
+  missed_reminders?: "late" | "summary" | "skip";
+  quiet_hours?: QuietHours[];
+  suppress_during_timebox?: boolean;
+  defer_during_timebox?: boolean;
+  atc_profile?: ReminderProfile;
+}
+
+export interface QuietHours {
+  /** Weekday names; empty means every day */
+  days: string[];
+  /** "HH:MM"; a window ending before it starts runs past midnight */
+  start: string;
+  end: string;
+}
+
+export interface ReminderProfile {
+  quiet_hours: QuietHours[];
+  stand_reminders: boolean;
+  muted_reminders: string[];
+}
+
+export interface ReminderRule {
+  id: string;
+  title: string;
+  body: string;
+  /** Cron expression ("0 9 * * MON-FRI") or RRULE ("FREQ=WEEKLY;BYDAY=MO;BYHOUR=9") */
+  schedule: string;
+  enabled: boolean;
+  start_date?: string;
+  action?: { type: "open_view"; view: NavView };
 }
 
 export interface QuickNote {
@@ -57,15 +90,173 @@ export interface DailyTask {
   isFrog?: boolean;
   category: TaskCategory;
   timeboxMinutes?: number;
+  remindAt?: string;
+  /** The recurring task this is an occurrence of. */
+  templateId?: string;
+  /** The day the series scheduled this occurrence for. */
+  occurrence?: string;
+  checklist?: ChecklistItem[];
+}
+
+/** A step of a task or todo, ticked off on its own. */
+export interface ChecklistItem {
+  id: string;
+  text: string;
+  completed: boolean;
+  completedAt?: string;
+}
+
+/** A recurring task, generated as `DailyTask`s for the coming days. */
+export interface TaskTemplate {
+  id: string;
+  text: string;
+  description: string;
+  goalId?: string;
+  category: TaskCategory;
+  timeboxMinutes?: number;
+  /** `HH:MM` reminder for each occurrence. */
+  remindTime?: string;
+  /** RRULE subset or cron expression; unused with `everyDaysAfterCompletion`. */
+  rule?: string;
+  skipWeekends: boolean;
+  everyDaysAfterCompletion?: number;
+  startDate: string;
+  generatedThrough?: string;
 }
 
 export interface ActiveTimer {
   id: string;
-  type: "focus" | "task";
+  type: "focus" | "task" | "pomodoro";
   taskId?: string;
   taskName?: string;
   endTime: string;
   durationMinutes: number;
+  startedAt?: string;
+  pausedAt?: string;
+  remainingSeconds?: number;
+  expiredAt?: string;
+  sessionId?: string;
+  pomodoroPhase?: PomodoroPhase;
+}
+
+export type PomodoroPhase = "work" | "shortBreak" | "longBreak";
+
+export interface PomodoroSettings {
+  workMinutes: number;
+  shortBreakMinutes: number;
+  longBreakMinutes: number;
+  cycles: number;
+  autoAdvance: boolean;
+}
+
+export interface TimeEntry {
+  id: string;
+  taskId: string;
+  source: "timer" | "timebox" | "manual";
+  start: string;
+  stop?: string;
+  timerId?: string;
+  note?: string;
+  editedAt?: string;
+}
+
+export type TimeReportPeriod =
+  | { kind: "day"; date: string }
+  | { kind: "week"; date: string }
+  | { kind: "goal"; goalId: string };
+
+export interface TimeReport {
+  rows: {
+    taskId: string;
+    text: string;
+    date: string;
+    goalId?: string;
+    completed: boolean;
+    estimateMinutes?: number;
+    actualMinutes: number;
+  }[];
+  estimateMinutes: number;
+  actualMinutes: number;
+  estimatedTasksActualMinutes: number;
+}
+
+export interface NotificationRecord {
+  id: string;
+  source: "reminder" | "timer" | "pomodoro" | "stand" | "app";
+  ruleId?: string;
+  dueAt?: string;
+  title: string;
+  body: string;
+  firedAt: string;
+  status: "delivered" | "denied" | "failed" | "suppressed";
+  error?: string;
+  reason?: string;
+  acknowledgedAt?: string;
+}
+
+export type AlertRef =
+  | { type: "stand"; mode: "sitting" | "standing" }
+  | { type: "reminder"; ruleId: string; title: string; body: string };
+
+export interface Snooze {
+  id: string;
+  alert: AlertRef;
+  until: string;
+  snoozedAt: string;
+}
+
+export interface TaskReminder {
+  kind: "task" | "todo";
+  id: string;
+  text: string;
+  due: string;
+}
+
+export interface StandEvent {
+  id: string;
+  at: string;
+  kind: "prompt" | "manual" | "resume" | "acknowledged" | "snoozed" | "stopped";
+  mode?: "sitting" | "standing";
+  awaySince?: string;
+}
+
+export interface DailyStandStats {
+  date: string;
+  standingMinutes: number;
+  sittingMinutes: number;
+  prompts: number;
+  acknowledged: number;
+  complied: number;
+  complianceRate?: number;
+}
+
+export interface WeeklyStandStats {
+  weekStart: string;
+  standingMinutes: number;
+  sittingMinutes: number;
+  prompts: number;
+  complied: number;
+  complianceRate?: number;
+}
+
+export interface StandStats {
+  days: DailyStandStats[];
+  weeks: WeeklyStandStats[];
+  standingMinutes: number;
+  sittingMinutes: number;
+  prompts: number;
+  complied: number;
+  complianceRate?: number;
+  currentStreak: number;
+  longestStreak: number;
+}
+
+export interface DailyFocusStats {
+  date: string;
+  focusMinutes: number;
+  breakMinutes: number;
+  completedPomodoros: number;
+  interruptedPomodoros: number;
 }
 
 export interface Todo {
@@ -75,6 +266,8 @@ export interface Todo {
   goalId?: string;
   lastScheduledDate?: string;
   createdAt: string;
+  remindAt?: string;
+  checklist?: ChecklistItem[];
 }
 
 export interface FeatureRequest {
@@ -113,6 +306,36 @@ export interface AppData {
   activeTimer?: ActiveTimer;
   activeTimers?: ActiveTimer[];
   atcDays?: string[];
+  pomodoro?: PomodoroSettings;
+  /** IANA zone such as "Europe/Berlin"; unset follows the system. */
+  timezone?: string;
+  focusMusic?: FocusMusicRule[];
+  carryForward?: CarryForwardSettings;
+  taskTemplates?: TaskTemplate[];
+  /** One-time data migrations applied so far; maintained by the backend. */
+  schemaVersion?: number;
+}
+
+export type CarryForwardPolicy = "none" | "all" | "frogOnly" | "categories";
+
+export interface CarryForwardSettings {
+  policy: CarryForwardPolicy;
+  /** Categories carried forward by the "categories" policy. */
+  categories?: TaskCategory[];
+}
+
+export interface FocusMusicRule {
+  timerType: ActiveTimer["type"];
+  enabled: boolean;
+  /** Playlist name (Apple Music) or URI (MPRIS); empty plays what is queued. */
+  playlist?: string;
+  pauseOnEnd: boolean;
+}
+
+export interface Timezones {
+  system: string;
+  current: string;
+  zones: string[];
 }
 
 export type NavView = "today" | "tasks" | "goals" | "notes" | "bragdoc" | "curiosities" | "reviews" | "settings";
@@ -148,4 +371,60 @@ export interface NowPlayingInfo {
   duration?: number;
   position?: number;
   artwork?: string;
+  artwork_url?: string;
+}
+
+export interface TimerLink {
+  timerId: string;
+  timerType: ActiveTimer["type"];
+  taskId?: string;
+  taskName?: string;
+  sessionId?: string;
+  focus: boolean;
+}
+
+export interface Play {
+  id: string;
+  title: string;
+  artist?: string;
+  album?: string;
+  duration?: number;
+  start: string;
+  end?: string;
+  timers?: TimerLink[];
+}
+
+export interface TrackStats {
+  title: string;
+  artist?: string;
+  album?: string;
+  plays: number;
+  minutes: number;
+}
+
+export interface SessionPlaylist {
+  sessionId: string;
+  timerType: ActiveTimer["type"];
+  taskId?: string;
+  taskName?: string;
+  focus: boolean;
+  plays: Play[];
+}
+
+/** Payload of the `system-event` event. */
+export type SystemEvent = "sleep" | "wake" | "lock" | "unlock";
+
+export interface CarriedTask {
+  id: string;
+  fromId: string;
+  fromDate: string;
+  text: string;
+}
+
+/** Payload of the `tasks-carried-forward` event. */
+export interface RolloverSummary {
+  date: string;
+  policy: CarryForwardPolicy;
+  moved: CarriedTask[];
+  leftBehind: number;
 }
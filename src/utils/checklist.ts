@@ -0,0 +1,20 @@
+import { ChecklistItem } from "../types";
+
+export interface ChecklistProgress {
+  done: number;
+  total: number;
+  /** Whole percent of items done. */
+  percent: number;
+}
+
+export const getChecklistProgress = (items?: ChecklistItem[]): ChecklistProgress | null => {
+  if (!items?.length) return null;
+  const done = items.filter((item) => item.completed).length;
+  return { done, total: items.length, percent: Math.floor((done * 100) / items.length) };
+};
+
+/** The items a task carried forward takes along; done ones stay behind. */
+export const getUnfinishedItems = (items?: ChecklistItem[]): ChecklistItem[] | undefined => {
+  const unfinished = items?.filter((item) => !item.completed);
+  return unfinished?.length ? unfinished : undefined;
+};
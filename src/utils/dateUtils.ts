@@ -1,5 +1,56 @@
-export const getTodayDate = (): string => {
-  return new Date().toISOString().split("T")[0];
+let timeZone: string | undefined;
+
+/** Sets the IANA zone dates are computed in; unset follows the system. */
+export const setTimeZone = (zone?: string) => {
+  try {
+    new Intl.DateTimeFormat("en-US", { timeZone: zone || undefined });
+    timeZone = zone || undefined;
+  } catch {
+    timeZone = undefined;
+  }
+};
+
+export const getTimeZone = (): string | undefined => timeZone;
+
+const zonedParts = (date: Date, options: Intl.DateTimeFormatOptions): Record<string, string> =>
+  Object.fromEntries(
+    new Intl.DateTimeFormat("en-US", { ...options, timeZone })
+      .formatToParts(date)
+      .map((part) => [part.type, part.value])
+  );
+
+/** `YYYY-MM-DD` for `date` in the configured zone. */
+export const formatDateInZone = (date: Date): string => {
+  const { year, month, day } = zonedParts(date, { year: "numeric", month: "2-digit", day: "2-digit" });
+  return `${year}-${month}-${day}`;
+};
+
+/** 24-hour `HH:MM` for `date` in the configured zone. */
+export const formatTimeInZone = (date: Date): string => {
+  const { hour, minute } = zonedParts(date, { hour: "2-digit", minute: "2-digit", hourCycle: "h23" });
+  return `${hour}:${minute}`;
+};
+
+export const getHourInZone = (date: Date): number => {
+  return Number(zonedParts(date, { hour: "numeric", hourCycle: "h23" }).hour);
+};
+
+export const getTodayDate = (): string => formatDateInZone(new Date());
+
+/** The `HH:MM` a task reminder is set for. Wall-clock values are kept as written. */
+export const getReminderTime = (remindAt: string): string =>
+  /^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}$/.test(remindAt) ? remindAt.slice(11, 16) : formatTimeInZone(new Date(remindAt));
+
+/** The `YYYY-MM-DDTHH:MM` a todo reminder is set for, as a datetime input shows it. */
+export const getReminderDateTime = (remindAt: string): string =>
+  /^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}$/.test(remindAt)
+    ? remindAt
+    : `${formatDateInZone(new Date(remindAt))}T${formatTimeInZone(new Date(remindAt))}`;
+
+export const addDays = (date: string, days: number): string => {
+  const result = new Date(`${date}T12:00:00Z`);
+  result.setUTCDate(result.getUTCDate() + days);
+  return result.toISOString().split("T")[0];
 };
 
 export const isToday = (date: string): boolean => date === getTodayDate();
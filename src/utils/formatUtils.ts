@@ -1,13 +1,9 @@
-import { getTodayDate } from "./dateUtils";
+import { addDays, getHourInZone, getTimeZone, getTodayDate } from "./dateUtils";
 
 export const formatDateHeader = (date: string): string => {
   const today = getTodayDate();
-  const yesterday = new Date();
-  yesterday.setDate(yesterday.getDate() - 1);
-  const yesterdayStr = yesterday.toISOString().split("T")[0];
-  const tomorrow = new Date();
-  tomorrow.setDate(tomorrow.getDate() + 1);
-  const tomorrowStr = tomorrow.toISOString().split("T")[0];
+  const yesterdayStr = addDays(today, -1);
+  const tomorrowStr = addDays(today, 1);
 
   if (date === today) return "Today";
   if (date === yesterdayStr) return "Yesterday";
@@ -32,7 +28,7 @@ export const formatRelativeTime = (iso: string): string => {
   if (mins < 60) return `${mins}m ago`;
   if (hours < 24) return `${hours}h ago`;
   if (days < 7) return `${days}d ago`;
-  return date.toLocaleDateString("en-US", { month: "short", day: "numeric" });
+  return date.toLocaleDateString("en-US", { month: "short", day: "numeric", timeZone: getTimeZone() });
 };
 
 export const formatFullDate = (iso: string): string => {
@@ -43,13 +39,14 @@ export const formatFullDate = (iso: string): string => {
     day: "numeric",
     hour: "numeric",
     minute: "numeric",
+    timeZone: getTimeZone(),
   } as const;
 
   return new Date(iso).toLocaleString("en-US", timeDisplayOptions);
 };
 
 export const getGreeting = (time: Date): string => {
-  const hour = time.getHours();
+  const hour = getHourInZone(time);
   if (hour < 12) return "Good morning";
   if (hour < 17) return "Good afternoon";
   return "Good evening";
@@ -61,11 +58,13 @@ export const formatDateTime = (time: Date): string => {
       weekday: "long",
       month: "short",
       day: "numeric",
+      timeZone: getTimeZone(),
     }) +
     " · " +
     time.toLocaleTimeString("en-US", {
       hour: "numeric",
       minute: "2-digit",
+      timeZone: getTimeZone(),
     })
   );
 };
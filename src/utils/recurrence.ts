@@ -0,0 +1,45 @@
+import { TaskTemplate } from "../types";
+
+export type RepeatKind = "never" | "daily" | "weekdays" | "weekly" | "monthly" | "afterCompletion" | "custom";
+
+/** How the task editor shows a series' schedule. */
+export interface TaskRepeat {
+  kind: RepeatKind;
+  /** Days after completion, for "afterCompletion". */
+  days: number;
+  /** RRULE or cron expression, for "custom". */
+  rule: string;
+  skipWeekends: boolean;
+}
+
+export const NO_REPEAT: TaskRepeat = { kind: "never", days: 7, rule: "", skipWeekends: false };
+
+const PRESETS: { kind: RepeatKind; rule: string; skipWeekends: boolean }[] = [
+  { kind: "daily", rule: "FREQ=DAILY", skipWeekends: false },
+  { kind: "weekdays", rule: "FREQ=DAILY", skipWeekends: true },
+  { kind: "weekly", rule: "FREQ=WEEKLY", skipWeekends: false },
+  { kind: "monthly", rule: "FREQ=MONTHLY", skipWeekends: false },
+];
+
+export const repeatFromTemplate = (template?: TaskTemplate): TaskRepeat => {
+  if (!template) return NO_REPEAT;
+  if (template.everyDaysAfterCompletion) {
+    return { ...NO_REPEAT, kind: "afterCompletion", days: template.everyDaysAfterCompletion };
+  }
+  const rule = template.rule || "";
+  const preset = PRESETS.find((p) => p.rule === rule && p.skipWeekends === template.skipWeekends);
+  return { ...NO_REPEAT, kind: preset?.kind ?? "custom", rule, skipWeekends: template.skipWeekends };
+};
+
+/** The schedule fields of a template repeating as `repeat`. */
+export const repeatToTemplate = (
+  repeat: TaskRepeat
+): Pick<TaskTemplate, "rule" | "skipWeekends" | "everyDaysAfterCompletion"> => {
+  if (repeat.kind === "afterCompletion") {
+    return { rule: undefined, skipWeekends: false, everyDaysAfterCompletion: repeat.days };
+  }
+  const preset = PRESETS.find((p) => p.kind === repeat.kind);
+  return preset
+    ? { rule: preset.rule, skipWeekends: preset.skipWeekends, everyDaysAfterCompletion: undefined }
+    : { rule: repeat.rule.trim(), skipWeekends: repeat.skipWeekends, everyDaysAfterCompletion: undefined };
+};